@@ -0,0 +1,90 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use fjall::{Config, PartitionCreateOptions, PartitionHandle};
+use std::time::{Duration, Instant};
+
+const WRITER_COUNTS: [usize; 7] = [1, 2, 4, 8, 16, 32, 64];
+const INSERTS_PER_ITERATION: usize = 1_000;
+
+/// Spawns `writers` threads that each insert `count` items, returning the insert latencies
+fn run_writers(partition: &PartitionHandle, writers: usize, count: usize) -> Vec<Duration> {
+    std::thread::scope(|scope| {
+        let threads = (0..writers)
+            .map(|writer| {
+                scope.spawn(move || {
+                    let mut latencies = Vec::with_capacity(count);
+
+                    for x in 0..count {
+                        let key = ((writer * count + x) as u64).to_be_bytes();
+
+                        let start = Instant::now();
+                        partition.insert(key, "a".repeat(100)).unwrap();
+                        latencies.push(start.elapsed());
+                    }
+
+                    latencies
+                })
+            })
+            .collect::<Vec<_>>();
+
+        threads
+            .into_iter()
+            .flat_map(|t| t.join().unwrap())
+            .collect()
+    })
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[idx]
+}
+
+fn journal_writers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("journal writers");
+
+    for writers in WRITER_COUNTS {
+        let folder = tempfile::tempdir().unwrap();
+        let keyspace = Config::new(&folder).open().unwrap();
+        let partition = keyspace
+            .open_partition("default", PartitionCreateOptions::default())
+            .unwrap();
+
+        // NOTE: Criterion does not report tail latencies, so print them once per writer count
+        let mut latencies = run_writers(&partition, writers, INSERTS_PER_ITERATION);
+        latencies.sort();
+
+        eprintln!(
+            "{writers} writers: p50={:?} p99={:?} p99.9={:?} max={:?}",
+            percentile(&latencies, 0.5),
+            percentile(&latencies, 0.99),
+            percentile(&latencies, 0.999),
+            latencies.last().unwrap(),
+        );
+
+        group.throughput(Throughput::Elements(
+            (writers * INSERTS_PER_ITERATION) as u64,
+        ));
+
+        group.bench_with_input(
+            BenchmarkId::new("insert", writers),
+            &writers,
+            |b, &writers| {
+                b.iter_custom(|iters| {
+                    let mut elapsed = Duration::ZERO;
+
+                    for _ in 0..iters {
+                        let start = Instant::now();
+                        run_writers(&partition, writers, INSERTS_PER_ITERATION);
+                        elapsed += start.elapsed();
+                    }
+
+                    elapsed
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, journal_writers);
+criterion_main!(benches);
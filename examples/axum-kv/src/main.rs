@@ -7,7 +7,7 @@ use axum::{
     routing::{delete, get, post, put},
     Router,
 };
-use fjall::{Config, Keyspace, PartitionHandle, PersistMode};
+use fjall::{AsyncKeyspace, AsyncPartitionHandle, Config, PersistMode};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -26,8 +26,8 @@ struct BulkBody {
 
 #[derive(Clone)]
 struct State {
-    keyspace: Keyspace,
-    db: PartitionHandle,
+    keyspace: AsyncKeyspace,
+    db: AsyncPartitionHandle,
 }
 
 async fn insert_batch(
@@ -38,28 +38,26 @@ async fn insert_batch(
 
     let before = std::time::Instant::now();
 
-    tokio::task::spawn_blocking(move || {
-        let mut batch = state.keyspace.batch();
+    let mut batch = state.keyspace.batch();
 
-        if let Some(remove) = &body.remove {
-            for key in remove {
-                batch.remove(&state.db, key.clone());
-            }
+    if let Some(remove) = &body.remove {
+        for key in remove {
+            batch.remove(&state.db, key.clone());
         }
+    }
 
-        if let Some(upsert) = &body.upsert {
-            for item in upsert {
-                batch.insert(
-                    &state.db,
-                    item.0.clone(),
-                    serde_json::to_string(&item.1).unwrap(),
-                );
-            }
+    if let Some(upsert) = &body.upsert {
+        for item in upsert {
+            batch.insert(
+                &state.db,
+                item.0.clone(),
+                serde_json::to_string(&item.1).unwrap(),
+            );
         }
+    }
 
-        batch.commit()?;
-        state.keyspace.persist(PersistMode::SyncAll)
-    }).await.unwrap()?;
+    batch.commit().await?;
+    state.keyspace.persist(PersistMode::SyncAll).await?;
 
     Ok((
         StatusCode::OK,
@@ -76,10 +74,8 @@ async fn delete_item(
 
     let before = std::time::Instant::now();
 
-    tokio::task::spawn_blocking(move || {
-        state.db.remove(key)?;
-        state.keyspace.persist(PersistMode::SyncAll)
-    }).await.unwrap()?;
+    state.db.remove(key).await?;
+    state.keyspace.persist(PersistMode::SyncAll).await?;
 
     Ok((
         StatusCode::OK,
@@ -100,10 +96,11 @@ async fn insert_item(
 
     let before = std::time::Instant::now();
 
-    tokio::task::spawn_blocking(move || {
-        state.db.insert(key, serde_json::to_string(&body.item).unwrap())?;
-        state.keyspace.persist(PersistMode::SyncAll)
-    }).await.unwrap()?;
+    state
+        .db
+        .insert(key, serde_json::to_string(&body.item).unwrap())
+        .await?;
+    state.keyspace.persist(PersistMode::SyncAll).await?;
 
     Ok((
         StatusCode::CREATED,
@@ -120,9 +117,7 @@ async fn get_item(
 
     let before = std::time::Instant::now();
 
-    let item = tokio::task::spawn_blocking(move || {
-        state.db.get(key)
-    }).await.unwrap()?;
+    let item = state.db.get(key).await?;
 
     Ok(match item {
         Some(item) => (
@@ -155,8 +150,8 @@ async fn main() -> Result<(), Error> {
 
     log::info!("Opening database");
 
-    let keyspace = Config::default().open()?;
-    let db = keyspace.open_partition("data", Default::default())?;
+    let keyspace = Config::default().open_async().await?;
+    let db = keyspace.open_partition("data", Default::default()).await?;
 
     let state = State {
         keyspace,
@@ -199,8 +194,8 @@ mod tests {
 
         let data_folder = tempfile::tempdir()?;
 
-        let keyspace = Config::new(data_folder).open()?;
-        let db = keyspace.open_partition("data", Default::default())?;
+        let keyspace = Config::new(data_folder).open_async().await?;
+        let db = keyspace.open_partition("data", Default::default()).await?;
 
         let state = State {
             keyspace,
@@ -1,28 +1,22 @@
-use tokio::task::spawn_blocking;
-
 #[tokio::main]
 async fn main() -> fjall::Result<()> {
-    let keyspace = fjall::Config::default().open()?;
-    let items = keyspace.open_partition("items", Default::default())?;
-
-    {
-        let items = items.clone();
-        spawn_blocking(move || items.insert("hello", "world"))
-            .await
-            .expect("join failed")?;
-    }
+    let keyspace = fjall::Config::default().open_async().await?;
+    let items = keyspace.open_partition("items", Default::default()).await?;
 
-    let item = {
-        let items = items.clone();
-        spawn_blocking(move || items.get("hello"))
-            .await
-            .expect("join failed")?
-    };
+    items.insert("hello", "world").await?;
 
+    let item = items.get("hello").await?;
     let item = item.expect("should exist");
 
     assert_eq!(b"world", &*item);
 
+    let mut stream = items.prefix("hell");
+
+    while let Some(kv) = stream.next().await {
+        let (key, _) = kv?;
+        assert_eq!(b"hello", &*key);
+    }
+
     println!("OK");
 
     Ok(())
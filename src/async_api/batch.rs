@@ -0,0 +1,43 @@
+use super::{check_write_stalls, partition::AsyncPartitionHandle, spawn_blocking};
+use crate::{Batch, Instant};
+
+/// An atomic write batch of an [`AsyncKeyspace`](crate::AsyncKeyspace)
+///
+/// Allows atomically writing across partitions inside the keyspace.
+#[allow(clippy::module_name_repetitions)]
+pub struct AsyncBatch {
+    pub(crate) inner: Batch,
+}
+
+impl AsyncBatch {
+    /// Inserts a key-value pair into the batch
+    pub fn insert<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &mut self,
+        p: &AsyncPartitionHandle,
+        key: K,
+        value: V,
+    ) {
+        self.inner.insert(&p.inner, key, value);
+    }
+
+    /// Adds a tombstone marker for a key
+    pub fn remove<K: AsRef<[u8]>>(&mut self, p: &AsyncPartitionHandle, key: K) {
+        self.inner.remove(&p.inner, key);
+    }
+
+    /// Commits the batch to the keyspace atomically
+    ///
+    /// Returns the seqno of the batch, see [`Batch::commit`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub async fn commit(self) -> crate::Result<Instant> {
+        let batch = self.inner;
+        let (seqno, partitions) = spawn_blocking(move || batch.write(None, None)).await?;
+
+        check_write_stalls(partitions).await;
+
+        Ok(seqno)
+    }
+}
@@ -0,0 +1,269 @@
+use super::{batch::AsyncBatch, partition::AsyncPartitionHandle, spawn_blocking};
+use crate::{batch::PartitionKey, Config, Keyspace, PartitionCreateOptions, PersistMode};
+
+/// A keyspace for async code, running on a tokio runtime
+///
+/// Every blocking operation (disk I/O, fsync) runs on tokio's blocking
+/// thread pool, and write stalls yield to the runtime instead of
+/// blocking a worker thread.
+///
+/// # Examples
+///
+/// ```
+/// # use fjall::{Config, PartitionCreateOptions, PersistMode};
+/// #
+/// # tokio::runtime::Runtime::new()?.block_on(async {
+/// # let folder = tempfile::tempdir()?;
+/// let keyspace = Config::new(folder).open_async().await?;
+/// let items = keyspace.open_partition("items", PartitionCreateOptions::default()).await?;
+///
+/// items.insert("hello", "world").await?;
+/// keyspace.persist(PersistMode::SyncAll).await?;
+///
+/// assert_eq!(Some("world".as_bytes().into()), items.get("hello").await?);
+/// #
+/// # Ok::<(), fjall::Error>(())
+/// # })?;
+/// # Ok::<(), fjall::Error>(())
+/// ```
+#[derive(Clone)]
+#[allow(clippy::module_name_repetitions)]
+pub struct AsyncKeyspace {
+    inner: Keyspace,
+}
+
+impl From<Keyspace> for AsyncKeyspace {
+    fn from(inner: Keyspace) -> Self {
+        Self { inner }
+    }
+}
+
+impl AsyncKeyspace {
+    /// Opens a keyspace in the given directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if an IO error occured.
+    pub async fn open(config: Config) -> crate::Result<Self> {
+        let inner = spawn_blocking(move || Keyspace::open(config)).await?;
+        Ok(Self { inner })
+    }
+
+    /// Returns the underlying synchronous keyspace
+    #[must_use]
+    pub fn inner(&self) -> &Keyspace {
+        &self.inner
+    }
+
+    /// Initializes a new atomic write batch.
+    ///
+    /// Call [`AsyncBatch::commit`] to commit the batch to the keyspace.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # tokio::runtime::Runtime::new()?.block_on(async {
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_async().await?;
+    /// let partition = keyspace.open_partition("default", PartitionCreateOptions::default()).await?;
+    ///
+    /// let mut batch = keyspace.batch();
+    /// batch.insert(&partition, "1", "abc");
+    /// batch.insert(&partition, "3", "abc");
+    /// batch.commit().await?;
+    ///
+    /// assert!(partition.contains_key("3").await?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// # })?;
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn batch(&self) -> AsyncBatch {
+        AsyncBatch {
+            inner: self.inner.batch(),
+        }
+    }
+
+    /// Flushes the active journal. The durability depends on the [`PersistMode`]
+    /// used.
+    ///
+    /// The flush runs on tokio's blocking thread pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if an IO error occured.
+    pub async fn persist(&self, mode: PersistMode) -> crate::Result<()> {
+        let keyspace = self.inner.clone();
+        spawn_blocking(move || keyspace.persist(mode)).await
+    }
+
+    /// Creates or opens a keyspace partition.
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if an IO error occured.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the partition name includes characters other than: a-z A-Z 0-9 _ -
+    pub async fn open_partition(
+        &self,
+        name: &str,
+        create_options: PartitionCreateOptions,
+    ) -> crate::Result<AsyncPartitionHandle> {
+        let keyspace = self.inner.clone();
+        let name = name.to_owned();
+
+        let inner = spawn_blocking(move || keyspace.open_partition(&name, create_options)).await?;
+
+        Ok(AsyncPartitionHandle { inner })
+    }
+
+    /// Destroys the partition, removing all data associated with it.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub async fn delete_partition(&self, handle: AsyncPartitionHandle) -> crate::Result<()> {
+        let keyspace = self.inner.clone();
+        spawn_blocking(move || keyspace.delete_partition(handle.inner)).await
+    }
+
+    /// Renames a partition.
+    ///
+    /// See [`Keyspace::rename_partition`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionExists`](crate::Error::PartitionExists) if a partition
+    /// with the new name already exists.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new partition name is invalid.
+    pub async fn rename_partition(
+        &self,
+        handle: &AsyncPartitionHandle,
+        new_name: &str,
+    ) -> crate::Result<()> {
+        let keyspace = self.inner.clone();
+        let handle = handle.inner.clone();
+        let new_name = new_name.to_owned();
+
+        spawn_blocking(move || keyspace.rename_partition(&handle, &new_name)).await
+    }
+
+    /// Creates a new partition as a copy-on-write clone of another partition.
+    ///
+    /// See [`Keyspace::fork_partition`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionExists`](crate::Error::PartitionExists) if a partition
+    /// with the new name already exists.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new partition name is invalid.
+    pub async fn fork_partition(
+        &self,
+        source: &AsyncPartitionHandle,
+        new_name: &str,
+    ) -> crate::Result<AsyncPartitionHandle> {
+        let keyspace = self.inner.clone();
+        let source = source.inner.clone();
+        let new_name = new_name.to_owned();
+
+        let inner = spawn_blocking(move || keyspace.fork_partition(&source, &new_name)).await?;
+
+        Ok(AsyncPartitionHandle { inner })
+    }
+
+    /// Atomically exchanges the contents of two partitions.
+    ///
+    /// See [`Keyspace::swap_partitions`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionDeleted`](crate::Error::PartitionDeleted)
+    /// if either partition has been deleted.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub async fn swap_partitions(
+        &self,
+        a: &AsyncPartitionHandle,
+        b: &AsyncPartitionHandle,
+    ) -> crate::Result<()> {
+        let keyspace = self.inner.clone();
+        let a = a.inner.clone();
+        let b = b.inner.clone();
+
+        spawn_blocking(move || keyspace.swap_partitions(&a, &b)).await
+    }
+
+    /// Atomically replaces the contents of a partition with another partition.
+    ///
+    /// See [`Keyspace::replace_partition`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionDeleted`](crate::Error::PartitionDeleted)
+    /// if either partition has been deleted.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub async fn replace_partition(
+        &self,
+        target: &AsyncPartitionHandle,
+        source: AsyncPartitionHandle,
+    ) -> crate::Result<()> {
+        let keyspace = self.inner.clone();
+        let target = target.inner.clone();
+
+        spawn_blocking(move || keyspace.replace_partition(&target, source.inner)).await
+    }
+
+    /// Closes the partition, freeing its memory and file descriptors.
+    ///
+    /// See [`Keyspace::close_partition`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub async fn close_partition(&self, handle: AsyncPartitionHandle) -> crate::Result<()> {
+        let keyspace = self.inner.clone();
+        spawn_blocking(move || keyspace.close_partition(handle.inner)).await
+    }
+
+    /// Returns the amount of partitions, including unloaded partitions
+    #[must_use]
+    pub fn partition_count(&self) -> usize {
+        self.inner.partition_count()
+    }
+
+    /// Gets a list of all partition names in the keyspace, including unloaded partitions
+    #[must_use]
+    pub fn list_partitions(&self) -> Vec<PartitionKey> {
+        self.inner.list_partitions()
+    }
+
+    /// Returns `true` if the partition with the given name exists.
+    #[must_use]
+    pub fn partition_exists(&self, name: &str) -> bool {
+        self.inner.partition_exists(name)
+    }
+
+    /// Gets the current sequence number.
+    ///
+    /// Can be used to start a cross-partition snapshot, using [`crate::PartitionHandle::snapshot_at`].
+    #[must_use]
+    pub fn instant(&self) -> crate::Instant {
+        self.inner.instant()
+    }
+}
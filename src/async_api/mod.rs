@@ -0,0 +1,75 @@
+pub mod batch;
+pub mod keyspace;
+pub mod partition;
+pub mod stream;
+
+use crate::{partition::write_delay::WriteDelay, PartitionHandle};
+
+/// Runs blocking work (I/O, fsync) on tokio's blocking thread pool
+async fn spawn_blocking<T, F>(f: F) -> crate::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> crate::Result<T> + Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(std::io::Error::other(e).into()),
+    }
+}
+
+/// Waits until the check allows the write to proceed, yielding to the runtime
+async fn wait<F: FnMut() -> Option<WriteDelay>>(mut check: F) {
+    loop {
+        match check() {
+            Some(WriteDelay::Halt(duration)) => tokio::time::sleep(duration).await,
+            Some(WriteDelay::Stall(duration)) => {
+                tokio::time::sleep(duration).await;
+                return;
+            }
+            None => return,
+        }
+    }
+}
+
+/// Async version of `PartitionHandle::check_memtable_overflow`
+async fn check_memtable_overflow(partition: &PartitionHandle, size: u32) -> crate::Result<()> {
+    if partition.is_memtable_overflowing(size) {
+        let handle = partition.clone();
+        spawn_blocking(move || handle.rotate_memtable()).await?;
+
+        // NOTE: Non-journaled writes do not grow the journal
+        if partition.is_journaled() {
+            wait(|| partition.journal_size_delay()).await;
+        }
+
+        wait(|| partition.write_halt_delay()).await;
+    }
+
+    wait(|| partition.write_stall_delay()).await;
+
+    Ok(())
+}
+
+/// Async version of `PartitionHandle::check_write_buffer_size`
+async fn check_write_buffer_size(partition: &PartitionHandle, initial_size: u64) {
+    if initial_size > partition.keyspace_config.max_write_buffer_size_in_bytes {
+        wait(|| partition.write_buffer_delay()).await;
+    }
+}
+
+/// Async version of `batch::check_write_stalls`
+async fn check_write_stalls(partitions: Vec<PartitionHandle>) {
+    for partition in partitions {
+        let memtable_size = partition.tree().active_memtable_size();
+
+        if let Err(e) = check_memtable_overflow(&partition, memtable_size).await {
+            log::error!("Failed memtable rotate check: {e:?}");
+        }
+
+        // IMPORTANT: Check write buffer as well
+        // Otherwise batch writes are never stalled/halted
+        let write_buffer_size = partition.write_buffer_manager.get();
+        check_write_buffer_size(&partition, write_buffer_size).await;
+    }
+}
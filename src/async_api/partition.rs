@@ -0,0 +1,303 @@
+use super::{check_memtable_overflow, check_write_buffer_size, spawn_blocking, stream::KvStream};
+use crate::{batch::PartitionKey, PartitionHandle};
+use lsm_tree::{UserKey, UserValue, ValueType};
+use std::{
+    ops::{Bound, RangeBounds},
+    path::PathBuf,
+};
+
+fn to_owned_bound<K: AsRef<[u8]>>(bound: Bound<&K>) -> Bound<UserKey> {
+    match bound {
+        Bound::Included(key) => Bound::Included(key.as_ref().into()),
+        Bound::Excluded(key) => Bound::Excluded(key.as_ref().into()),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// Access to a partition of an [`AsyncKeyspace`](crate::AsyncKeyspace)
+///
+/// Reads and writes run on tokio's blocking thread pool, and write stalls
+/// yield to the runtime instead of blocking a worker thread.
+#[derive(Clone)]
+#[allow(clippy::module_name_repetitions)]
+pub struct AsyncPartitionHandle {
+    pub(crate) inner: PartitionHandle,
+}
+
+impl AsyncPartitionHandle {
+    /// Returns the underlying synchronous partition handle
+    #[must_use]
+    pub fn inner(&self) -> &PartitionHandle {
+        &self.inner
+    }
+
+    /// Returns the partition name
+    #[must_use]
+    pub fn name(&self) -> PartitionKey {
+        self.inner.name()
+    }
+
+    /// Returns the underlying LSM-tree's path
+    #[must_use]
+    pub fn path(&self) -> PathBuf {
+        self.inner.path()
+    }
+
+    /// Inserts a key-value pair into the partition.
+    ///
+    /// Keys may be up to 65536 bytes long, values up to 2^32 bytes.
+    /// Shorter keys and values result in better performance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # tokio::runtime::Runtime::new()?.block_on(async {
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_async().await?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default()).await?;
+    /// partition.insert("a", "abc").await?;
+    ///
+    /// assert!(partition.contains_key("a").await?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// # })?;
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub async fn insert<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: V,
+    ) -> crate::Result<()> {
+        self.write(key.as_ref().into(), value.as_ref().into(), ValueType::Value)
+            .await
+    }
+
+    /// Removes an item from the partition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # tokio::runtime::Runtime::new()?.block_on(async {
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_async().await?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default()).await?;
+    /// partition.insert("a", "abc").await?;
+    /// partition.remove("a").await?;
+    ///
+    /// assert_eq!(None, partition.get("a").await?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// # })?;
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub async fn remove<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<()> {
+        self.write(key.as_ref().into(), [].into(), ValueType::Tombstone)
+            .await
+    }
+
+    /// Removes all items of the partition.
+    ///
+    /// See [`PartitionHandle::clear`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # tokio::runtime::Runtime::new()?.block_on(async {
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_async().await?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default()).await?;
+    /// partition.insert("a", "abc").await?;
+    /// partition.clear().await?;
+    ///
+    /// assert_eq!(None, partition.get("a").await?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// # })?;
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub async fn clear(&self) -> crate::Result<()> {
+        let partition = self.inner.clone();
+        spawn_blocking(move || partition.clear()).await
+    }
+
+    async fn write(
+        &self,
+        key: UserKey,
+        value: UserValue,
+        value_type: ValueType,
+    ) -> crate::Result<()> {
+        let partition = self.inner.clone();
+
+        let (_, memtable_size, write_buffer_size) =
+            spawn_blocking(move || partition.write_item(&key, &value, value_type, None)).await?;
+
+        check_memtable_overflow(&self.inner, memtable_size).await?;
+        check_write_buffer_size(&self.inner, write_buffer_size).await;
+
+        Ok(())
+    }
+
+    /// Retrieves an item from the partition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # tokio::runtime::Runtime::new()?.block_on(async {
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_async().await?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default()).await?;
+    /// partition.insert("a", "my_value").await?;
+    ///
+    /// let item = partition.get("a").await?;
+    /// assert_eq!(Some("my_value".as_bytes().into()), item);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// # })?;
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub async fn get<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<Option<UserValue>> {
+        let partition = self.inner.clone();
+        let key: UserKey = key.as_ref().into();
+
+        spawn_blocking(move || partition.get(key)).await
+    }
+
+    /// Retrieves multiple items from the partition.
+    ///
+    /// See [`PartitionHandle::multi_get`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # tokio::runtime::Runtime::new()?.block_on(async {
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_async().await?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default()).await?;
+    /// partition.insert("a", "my_value").await?;
+    ///
+    /// let items = partition.multi_get(["b", "a"]).await?;
+    /// assert_eq!(vec![None, Some("my_value".as_bytes().into())], items);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// # })?;
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub async fn multi_get<K: AsRef<[u8]>, I: IntoIterator<Item = K>>(
+        &self,
+        keys: I,
+    ) -> crate::Result<Vec<Option<UserValue>>> {
+        let partition = self.inner.clone();
+        let keys = keys
+            .into_iter()
+            .map(|key| UserKey::from(key.as_ref()))
+            .collect::<Vec<_>>();
+
+        spawn_blocking(move || partition.multi_get(keys)).await
+    }
+
+    /// Returns `true` if the partition contains the specified key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub async fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<bool> {
+        let partition = self.inner.clone();
+        let key: UserKey = key.as_ref().into();
+
+        spawn_blocking(move || partition.contains_key(key)).await
+    }
+
+    /// Returns a stream that scans through the entire partition.
+    ///
+    /// Needs to be called inside a tokio runtime.
+    #[must_use]
+    #[allow(clippy::iter_not_returning_iterator)]
+    pub fn iter(&self) -> KvStream {
+        KvStream::spawn(self.inner.clone(), |partition| Box::new(partition.iter()))
+    }
+
+    /// Returns a stream over a range of items.
+    ///
+    /// Needs to be called inside a tokio runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, PartitionCreateOptions};
+    /// #
+    /// # tokio::runtime::Runtime::new()?.block_on(async {
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_async().await?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default()).await?;
+    /// partition.insert("a", "abc").await?;
+    /// partition.insert("f", "abc").await?;
+    /// partition.insert("g", "abc").await?;
+    ///
+    /// let mut stream = partition.range("a"..="f");
+    /// let mut count = 0;
+    ///
+    /// while let Some(item) = stream.next().await {
+    ///     let (_key, _value) = item?;
+    ///     count += 1;
+    /// }
+    ///
+    /// assert_eq!(2, count);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// # })?;
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn range<K: AsRef<[u8]>, R: RangeBounds<K>>(&self, range: R) -> KvStream {
+        let bounds = (
+            to_owned_bound(range.start_bound()),
+            to_owned_bound(range.end_bound()),
+        );
+
+        KvStream::spawn(self.inner.clone(), move |partition| {
+            Box::new(partition.range(bounds))
+        })
+    }
+
+    /// Returns a stream over a prefixed set of items.
+    ///
+    /// Needs to be called inside a tokio runtime.
+    #[must_use]
+    pub fn prefix<K: AsRef<[u8]>>(&self, prefix: K) -> KvStream {
+        let prefix: UserKey = prefix.as_ref().into();
+
+        KvStream::spawn(self.inner.clone(), move |partition| {
+            Box::new(partition.prefix(prefix))
+        })
+    }
+}
@@ -0,0 +1,61 @@
+use crate::PartitionHandle;
+use lsm_tree::{UserKey, UserValue};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::sync::mpsc::{channel, Receiver};
+
+/// Amount of items that are read ahead of the consumer
+const READ_AHEAD: usize = 64;
+
+type Item = crate::Result<(UserKey, UserValue)>;
+
+/// Asynchronous stream over key-value pairs of a partition
+///
+/// The items are read on tokio's blocking thread pool, so reading
+/// from disk never blocks a runtime worker thread.
+///
+/// Dropping the stream stops the background read.
+#[allow(clippy::module_name_repetitions)]
+pub struct KvStream {
+    rx: Receiver<Item>,
+}
+
+impl KvStream {
+    /// Spawns a blocking task that feeds the items of the partition's iterator into the stream
+    pub(crate) fn spawn<F>(partition: PartitionHandle, f: F) -> Self
+    where
+        F: for<'a> FnOnce(&'a PartitionHandle) -> Box<dyn Iterator<Item = Item> + 'a>
+            + Send
+            + 'static,
+    {
+        let (tx, rx) = channel(READ_AHEAD);
+
+        tokio::task::spawn_blocking(move || {
+            for item in f(&partition) {
+                // NOTE: If the receiver was dropped, stop reading
+                if tx.blocking_send(item).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { rx }
+    }
+
+    /// Returns the next item, or `None` if the stream is exhausted.
+    ///
+    /// This allows consuming the stream without a stream extension trait.
+    pub async fn next(&mut self) -> Option<Item> {
+        self.rx.recv().await
+    }
+}
+
+impl futures_core::Stream for KvStream {
+    type Item = Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
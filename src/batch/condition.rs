@@ -0,0 +1,37 @@
+use crate::{Instant, PartitionHandle};
+use lsm_tree::{UserKey, UserValue, Value};
+
+/// The expected state of a key
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Expected {
+    /// The key's value, or `None` if the key should not exist
+    Value(Option<UserValue>),
+
+    /// The seqno the key's value was written at, or `None` if the key should not exist
+    Seqno(Option<Instant>),
+}
+
+/// A condition that needs to hold for a batch to be committed
+#[derive(Clone)]
+pub struct Precondition {
+    /// Partition the key belongs to
+    pub partition: PartitionHandle,
+
+    /// User-defined key
+    pub key: UserKey,
+
+    /// Expected state of the key
+    pub expected: Expected,
+}
+
+impl Precondition {
+    /// Returns `true` if the latest version of the key satisfies the precondition.
+    pub fn holds(&self, latest: Option<&Value>) -> bool {
+        let current = latest.filter(|item| !item.is_tombstone());
+
+        match &self.expected {
+            Expected::Value(value) => current.map(|item| &item.value) == value.as_ref(),
+            Expected::Seqno(seqno) => current.map(|item| item.seqno) == *seqno,
+        }
+    }
+}
@@ -1,8 +1,13 @@
+pub mod condition;
 pub mod item;
 
-use crate::{Keyspace, PartitionHandle};
+use crate::{
+    partition::read::{get_latest, sealed_memtables},
+    Instant, Keyspace, PartitionHandle, PersistMode, WriteOptions,
+};
+use condition::{Expected, Precondition};
 use item::Item;
-use lsm_tree::{Value, ValueType};
+use lsm_tree::{SeqNo, Value, ValueType};
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
@@ -15,7 +20,12 @@ pub type PartitionKey = Arc<str>;
 ///
 /// Allows atomically writing across partitions inside the [`Keyspace`].
 pub struct Batch {
-    pub(crate) data: Vec<Item>,
+    /// Items and the partitions they are written to
+    ///
+    /// The partition name of an item is only resolved when the batch is written,
+    /// because the partition may be renamed in the meantime.
+    pub(crate) data: Vec<(PartitionHandle, Item)>,
+    preconditions: Vec<Precondition>,
     keyspace: Keyspace,
 }
 
@@ -31,6 +41,7 @@ impl Batch {
     pub(crate) fn with_capacity(keyspace: Keyspace, capacity: usize) -> Self {
         Self {
             data: Vec::with_capacity(capacity),
+            preconditions: Vec::new(),
             keyspace,
         }
     }
@@ -42,30 +53,170 @@ impl Batch {
         key: K,
         value: V,
     ) {
-        self.data.push(Item::new(
-            p.name.clone(),
-            key.as_ref(),
-            value.as_ref(),
-            ValueType::Value,
+        self.data.push((
+            p.clone(),
+            Item::new(p.name(), key.as_ref(), value.as_ref(), ValueType::Value),
         ));
     }
 
     /// Adds a tombstone marker for a key
     pub fn remove<K: AsRef<[u8]>>(&mut self, p: &PartitionHandle, key: K) {
-        self.data.push(Item::new(
-            p.name.clone(),
-            key.as_ref(),
-            vec![],
-            ValueType::Tombstone,
+        self.data.push((
+            p.clone(),
+            Item::new(p.name(), key.as_ref(), vec![], ValueType::Tombstone),
         ));
     }
 
+    /// Only commits the batch if the key's value is still the expected one.
+    ///
+    /// `None` expects the key not to exist.
+    /// If the precondition does not hold at commit time,
+    /// the batch fails with [`crate::Error::PreconditionFailed`].
+    ///
+    /// See [`PartitionHandle::compare_and_swap`] for the guarantees of conditional writes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let accounts = keyspace.open_partition("accounts", PartitionCreateOptions::default())?;
+    /// # let log = keyspace.open_partition("log", PartitionCreateOptions::default())?;
+    /// accounts.insert("alice", "100")?;
+    ///
+    /// let mut batch = keyspace.batch();
+    /// batch.require_value(&accounts, "alice", Some("100"));
+    /// batch.insert(&accounts, "alice", "50");
+    /// batch.insert(&log, "1", "alice: -50");
+    /// batch.commit()?;
+    ///
+    /// // The balance has changed, so the same batch fails now
+    /// let mut batch = keyspace.batch();
+    /// batch.require_value(&accounts, "alice", Some("100"));
+    /// batch.insert(&accounts, "alice", "50");
+    /// assert!(matches!(batch.commit(), Err(fjall::Error::PreconditionFailed(_))));
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    pub fn require_value<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &mut self,
+        p: &PartitionHandle,
+        key: K,
+        value: Option<V>,
+    ) {
+        self.preconditions.push(Precondition {
+            partition: p.clone(),
+            key: key.as_ref().into(),
+            expected: Expected::Value(value.map(|value| value.as_ref().into())),
+        });
+    }
+
+    /// Only commits the batch if the key's value was still written at the expected instant.
+    ///
+    /// `None` expects the key not to exist.
+    /// The instant of a value can be retrieved using [`PartitionHandle::get_with_seqno`].
+    /// If the precondition does not hold at commit time,
+    /// the batch fails with [`crate::Error::PreconditionFailed`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// let (_, seqno) = partition.get_with_seqno("a")?.expect("should exist");
+    ///
+    /// let mut batch = keyspace.batch();
+    /// batch.require_seqno(&partition, "a", Some(seqno));
+    /// batch.insert(&partition, "a", "def");
+    /// batch.commit()?;
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    pub fn require_seqno<K: AsRef<[u8]>>(
+        &mut self,
+        p: &PartitionHandle,
+        key: K,
+        seqno: Option<Instant>,
+    ) {
+        self.preconditions.push(Precondition {
+            partition: p.clone(),
+            key: key.as_ref().into(),
+            expected: Expected::Seqno(seqno),
+        });
+    }
+
     /// Commits the batch to the [`Keyspace`] atomically
     ///
+    /// Returns the seqno of the batch, which can be used to wait for its writes
+    /// to become durable using [`Keyspace::wait_for_durable`].
+    ///
+    /// A snapshot only sees writes with a seqno lower than its instant,
+    /// so the batch's writes are visible to snapshots at `seqno + 1` or later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let mut batch = keyspace.batch();
+    /// batch.insert(&partition, "a", "abc");
+    /// let seqno = batch.commit()?;
+    ///
+    /// assert!(!partition.snapshot_at(seqno).contains_key("a")?);
+    /// assert!(partition.snapshot_at(seqno + 1).contains_key("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
     /// # Errors
     ///
-    /// Will return `Err` if an IO error occurs.
-    pub fn commit(mut self) -> crate::Result<()> {
+    /// Will return `Err` if an IO error occurs, or a precondition does not hold.
+    pub fn commit(self) -> crate::Result<Instant> {
+        self.commit_with_options(WriteOptions::default())
+    }
+
+    /// Commits the batch to the [`Keyspace`] atomically, using the given write options
+    ///
+    /// Returns the seqno of the batch, see [`Batch::commit`]. If a durability is set,
+    /// the batch is synced to disk accordingly when this function returns.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or a precondition does not hold.
+    pub fn commit_with_options(self, options: WriteOptions) -> crate::Result<Instant> {
+        let (seqno, partitions) = self.write(None, options.durability)?;
+        check_write_stalls(partitions);
+        Ok(seqno)
+    }
+
+    /// Commits the batch using the given seqno, instead of assigning a new one
+    ///
+    /// Used to apply batches of a primary keyspace to a follower.
+    pub(crate) fn commit_at(self, seqno: SeqNo) -> crate::Result<()> {
+        let (_, partitions) = self.write(Some(seqno), None)?;
+        check_write_stalls(partitions);
+        Ok(())
+    }
+
+    /// Writes the batch to the journal and memtables, without stalling
+    ///
+    /// Returns the seqno of the batch, and the affected partitions,
+    /// which need to be checked for write stalls by the caller.
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn write(
+        mut self,
+        seqno: Option<SeqNo>,
+        durability: Option<PersistMode>,
+    ) -> crate::Result<(Instant, Vec<PartitionHandle>)> {
         if self
             .keyspace
             .is_poisoned
@@ -79,55 +230,159 @@ impl Batch {
 
         // NOTE: Fully (write) lock, so the batch can be committed atomically
         log::trace!("batch: Acquiring partitions lock");
-        let partitions = self.keyspace.partitions.write().expect("lock is poisoned");
+        let mut partitions = self.keyspace.partitions.write().expect("lock is poisoned");
+
+        // NOTE: Partitions cannot be renamed, swapped or closed while the partitions lock is held
+        let mut slots = HashMap::new();
+
+        let affected_partitions = self.data.iter().map(|(partition, _)| partition).chain(
+            self.preconditions
+                .iter()
+                .map(|condition| &condition.partition),
+        );
+
+        for partition in affected_partitions {
+            if slots.contains_key(&partition.id()) {
+                continue;
+            }
+
+            if partition
+                .is_deleted
+                .load(std::sync::atomic::Ordering::Relaxed)
+            {
+                return Err(crate::Error::PartitionDeleted);
+            }
+
+            // NOTE: A partition may have been closed after the item was added to the batch
+            let name = partition.name();
+
+            if !partitions.contains_key(&name) {
+                self.keyspace.load_partition(&mut partitions, &name)?;
+            }
+
+            let slot = partition.slot();
+            slots.insert(slot.id, slot);
+        }
+
+        // NOTE: A partition may have been renamed after the item was added to the batch
+        for (partition, item) in &mut self.data {
+            item.partition = partition.name();
+        }
+
+        // IMPORTANT: Exclusively lock the partitions that have preconditions,
+        // so no plain write can be in flight while checking them
+        let write_locks = self
+            .preconditions
+            .iter()
+            .map(|condition| condition.partition.id())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .filter_map(|partition_id| slots.get(&partition_id))
+            .map(|slot| slot.write_lock.write().expect("lock is poisoned"))
+            .collect::<Vec<_>>();
+
+        // IMPORTANT: Preconditions need to see sealed memtables and segments as well,
+        // so lock them before the memtables to keep the lock order:
+        // write lock -> flush manager -> levels -> active memtable
+        let (flush_manager, levels) = if self.preconditions.is_empty() {
+            (None, HashMap::new())
+        } else {
+            let flush_manager = self
+                .keyspace
+                .flush_manager
+                .read()
+                .expect("lock is poisoned");
+
+            let levels = slots
+                .iter()
+                .map(|(partition_id, slot)| {
+                    (
+                        *partition_id,
+                        slot.tree.levels.read().expect("lock is poisoned"),
+                    )
+                })
+                .collect::<HashMap<_, _>>();
+
+            (Some(flush_manager), levels)
+        };
 
         // IMPORTANT: Need to WRITE lock all affected partition's memtables
         // Otherwise, there may be read skew
         log::trace!("batch: Acquiring memtable locks");
-        let locked_memtables = {
-            let mut lock_map = HashMap::new();
+        let locked_memtables = slots
+            .iter()
+            .map(|(partition_id, slot)| (*partition_id, slot.tree.lock_active_memtable()))
+            .collect::<HashMap<_, _>>();
 
-            for item in &self.data {
-                if lock_map.contains_key(&item.partition) {
-                    continue;
-                }
-
-                let Some(partition) = partitions.get(&item.partition) else {
+        if let Some(flush_manager) = &flush_manager {
+            for condition in &self.preconditions {
+                let (Some(slot), Some(levels), Some(active_memtable)) = (
+                    slots.get(&condition.partition.id()),
+                    levels.get(&condition.partition.id()),
+                    locked_memtables.get(&condition.partition.id()),
+                ) else {
                     continue;
                 };
 
-                if partition
-                    .is_deleted
-                    .load(std::sync::atomic::Ordering::Relaxed)
-                {
-                    return Err(crate::Error::PartitionDeleted);
+                let sealed_memtables = sealed_memtables(flush_manager, slot, levels);
+                let latest =
+                    get_latest(active_memtable, &sealed_memtables, levels, &condition.key)?;
+
+                if !condition.holds(latest.as_ref()) {
+                    let current = latest
+                        .filter(|item| !item.is_tombstone())
+                        .map(|item| item.value);
+
+                    return Err(crate::Error::PreconditionFailed(current));
                 }
+            }
+        }
+
+        // IMPORTANT: Lock replication sink before getting the seqno,
+        // so batches are shipped in seqno order
+        let mut replication = self.keyspace.replication.lock();
+
+        let batch_seqno = seqno.unwrap_or_else(|| self.keyspace.seqno.next());
+
+        // NOTE: Items of non-journaled partitions skip the journal
+        let items = self
+            .data
+            .iter()
+            .filter_map(|(partition, item)| {
+                slots
+                    .get(&partition.id())
+                    .filter(|slot| slot.is_journaled)
+                    .map(|slot| (slot.id, item))
+            })
+            .collect::<Vec<_>>();
 
-                lock_map.insert(
-                    item.partition.clone(),
-                    partition.tree.lock_active_memtable(),
-                );
+        let pending_sync = if items.is_empty() && !self.data.is_empty() {
+            None
+        } else {
+            let _ = shard.writer.write_batch(&items, batch_seqno)?;
+
+            for (partition_id, _) in &items {
+                shard.mark_dirty(*partition_id, batch_seqno);
             }
 
-            lock_map
-        };
+            let pending_sync = shard.prepare_durability(batch_seqno, durability)?;
 
-        let batch_seqno = self.keyspace.seqno.next();
+            if let Some(sink) = &mut replication {
+                let items = items.iter().map(|(_, item)| *item).collect::<Vec<_>>();
+                sink.ship(&items, batch_seqno);
+            }
 
-        let items = self.data.iter().collect::<Vec<_>>();
-        let _ = shard.writer.write_batch(&items, batch_seqno)?;
+            pending_sync
+        };
+        drop(replication);
 
         let mut partitions_with_possible_stall = HashSet::new();
 
         let mut batch_size = 0u64;
 
         log::trace!("Applying {} batched items to memtable(s)", self.data.len());
-        for item in std::mem::take(&mut self.data) {
-            let Some(partition) = partitions.get(&item.partition) else {
-                continue;
-            };
-
-            let Some(active_memtable) = locked_memtables.get(&item.partition) else {
+        for (partition, item) in std::mem::take(&mut self.data) {
+            let Some(active_memtable) = locked_memtables.get(&partition.id()) else {
                 continue;
             };
 
@@ -141,11 +396,21 @@ impl Batch {
             let (item_size, _) = active_memtable.insert(value);
             batch_size += u64::from(item_size);
 
-            // IMPORTANT: Clone the handle, because we don't want to keep the partitions lock open
-            partitions_with_possible_stall.insert(partition.clone());
+            partitions_with_possible_stall.insert(partition);
+        }
+
+        // NOTE: If the seqno was given (replicated batch), make sure
+        // the keyspace's seqno does not fall behind
+        if seqno.is_some() {
+            self.keyspace
+                .seqno
+                .fetch_max(batch_seqno + 1, std::sync::atomic::Ordering::AcqRel);
         }
 
         drop(locked_memtables);
+        drop(levels);
+        drop(flush_manager);
+        drop(write_locks);
         drop(partitions);
         drop(shard);
 
@@ -153,20 +418,35 @@ impl Batch {
         // Otherwise write buffer growth is unbounded when using batches
         self.keyspace.write_buffer_manager.allocate(batch_size);
 
-        // Check each affected partition for write stall/halt
-        for partition in partitions_with_possible_stall {
-            let memtable_size = partition.tree.active_memtable_size();
+        // NOTE: Wait for the sync after releasing the journal shard,
+        // so concurrent writers can join the group commit
+        if let Some(pending_sync) = pending_sync {
+            pending_sync.wait(
+                &self.keyspace.journal,
+                &self.keyspace.seqno,
+                &self.keyspace.is_poisoned,
+            )?;
+        }
+
+        Ok((
+            batch_seqno,
+            partitions_with_possible_stall.into_iter().collect(),
+        ))
+    }
+}
 
-            if let Err(e) = partition.check_memtable_overflow(memtable_size) {
-                log::error!("Failed memtable rotate check: {e:?}");
-            };
+/// Checks each partition affected by a batch for write stall/halt
+pub fn check_write_stalls(partitions: Vec<PartitionHandle>) {
+    for partition in partitions {
+        let memtable_size = partition.tree().active_memtable_size();
 
-            // IMPORTANT: Check write buffer as well
-            // Otherwise batch writes are never stalled/halted
-            let write_buffer_size = self.keyspace.write_buffer_manager.get();
-            partition.check_write_buffer_size(write_buffer_size);
+        if let Err(e) = partition.check_memtable_overflow(memtable_size) {
+            log::error!("Failed memtable rotate check: {e:?}");
         }
 
-        Ok(())
+        // IMPORTANT: Check write buffer as well
+        // Otherwise batch writes are never stalled/halted
+        let write_buffer_size = partition.write_buffer_manager.get();
+        partition.check_write_buffer_size(write_buffer_size);
     }
 }
@@ -0,0 +1,276 @@
+/// Encodes a nibble as a lowercase hex digit
+fn hex_digit(nibble: u8) -> u8 {
+    if nibble < 10 {
+        b'0' + nibble
+    } else {
+        b'a' + nibble - 10
+    }
+}
+
+/// Decodes a hex digit
+fn hex_value(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Encodes bytes as lowercase hex
+pub fn to_hex(bytes: &[u8]) -> String {
+    // NOTE: Exports may contain large values, so avoid formatting each byte
+    let mut s = Vec::with_capacity(bytes.len() * 2);
+
+    for byte in bytes {
+        s.push(hex_digit(byte >> 4));
+        s.push(hex_digit(byte & 0x0f));
+    }
+
+    String::from_utf8(s).expect("hex digits should be ASCII")
+}
+
+/// Decodes a hex string
+pub fn from_hex(s: &str) -> Option<Vec<u8>> {
+    let pairs = s.as_bytes().chunks_exact(2);
+
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(s.len() / 2);
+
+    for pair in pairs {
+        let &[hi, lo] = pair else {
+            return None;
+        };
+
+        bytes.push((hex_value(hi)? << 4) | hex_value(lo)?);
+    }
+
+    Some(bytes)
+}
+
+/// How user keys and values are read from the command line and printed
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Format {
+    Utf8,
+    Hex,
+}
+
+impl Format {
+    pub fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Self::Hex => to_hex(bytes),
+        }
+    }
+
+    pub fn decode(self, s: &str) -> Option<Vec<u8>> {
+        match self {
+            Self::Utf8 => Some(s.as_bytes().to_vec()),
+            Self::Hex => from_hex(s),
+        }
+    }
+}
+
+/// A single exported key-value pair
+///
+/// Serialized as one JSON object per line:
+///
+/// `{"partition":"<name>","key":"<hex>","value":"<hex>"}`
+#[derive(Debug, Eq, PartialEq)]
+pub struct ExportLine {
+    pub partition: String,
+    pub key: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+fn write_json_string(out: &mut String, s: &str) {
+    use std::fmt::Write;
+
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                // NOTE: Writing into a String cannot fail
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+/// Reads a JSON string, starting *after* the opening quote
+fn read_json_string(rest: &mut &str) -> Option<String> {
+    let mut s = String::new();
+
+    loop {
+        // NOTE: Copy everything up to the next special character at once,
+        // values can be large
+        let end = rest.find('"')?;
+        let idx = rest.split_at(end).0.find('\\').unwrap_or(end);
+        let (content, tail) = rest.split_at(idx);
+        s.push_str(content);
+
+        let mut chars = tail.chars();
+
+        match chars.next()? {
+            '"' => {
+                *rest = chars.as_str();
+                return Some(s);
+            }
+            _ => match chars.next()? {
+                '"' => s.push('"'),
+                '\\' => s.push('\\'),
+                '/' => s.push('/'),
+                'n' => s.push('\n'),
+                'r' => s.push('\r'),
+                't' => s.push('\t'),
+                'b' => s.push('\u{8}'),
+                'f' => s.push('\u{c}'),
+                'u' => {
+                    let code = (0..4).map(|_| chars.next()).collect::<Option<String>>()?;
+                    let code = u32::from_str_radix(&code, 16).ok()?;
+                    s.push(char::from_u32(code)?);
+                }
+                _ => return None,
+            },
+        }
+
+        *rest = chars.as_str();
+    }
+}
+
+/// Returns the next non-whitespace character
+fn next_token(rest: &mut &str) -> Option<char> {
+    *rest = rest.trim_start();
+
+    let mut chars = rest.chars();
+    let c = chars.next()?;
+    *rest = chars.as_str();
+
+    Some(c)
+}
+
+impl ExportLine {
+    pub fn to_json(&self) -> String {
+        let mut out = String::with_capacity(32 + self.key.len() * 2 + self.value.len() * 2);
+
+        // NOTE: Hex strings never need to be escaped
+        out.push_str("{\"partition\":");
+        write_json_string(&mut out, &self.partition);
+        out.push_str(",\"key\":\"");
+        out.push_str(&to_hex(&self.key));
+        out.push_str("\",\"value\":\"");
+        out.push_str(&to_hex(&self.value));
+        out.push_str("\"}");
+
+        out
+    }
+
+    /// Parses a flat JSON object with string fields
+    ///
+    /// Unknown fields are ignored.
+    pub fn from_json(line: &str) -> Option<Self> {
+        let mut rest = line;
+
+        let mut partition = None;
+        let mut key = None;
+        let mut value = None;
+
+        if next_token(&mut rest)? != '{' {
+            return None;
+        }
+
+        loop {
+            match next_token(&mut rest)? {
+                '}' => break,
+                ',' => continue,
+                '"' => {
+                    let field = read_json_string(&mut rest)?;
+
+                    if next_token(&mut rest)? != ':' {
+                        return None;
+                    }
+
+                    if next_token(&mut rest)? != '"' {
+                        return None;
+                    }
+                    let content = read_json_string(&mut rest)?;
+
+                    match field.as_str() {
+                        "partition" => partition = Some(content),
+                        "key" => key = Some(from_hex(&content)?),
+                        "value" => value = Some(from_hex(&content)?),
+                        _ => {}
+                    }
+                }
+                _ => return None,
+            }
+        }
+
+        Some(Self {
+            partition: partition?,
+            key: key?,
+            value: value?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_hex_round_trip() {
+        let bytes = [0, 1, 2, 254, 255];
+        assert_eq!("000102feff", to_hex(&bytes));
+        assert_eq!(Some(bytes.to_vec()), from_hex("000102feff"));
+        assert_eq!(None, from_hex("0"));
+        assert_eq!(None, from_hex("zz"));
+    }
+
+    #[test]
+    fn cli_export_line_round_trip() {
+        let line = ExportLine {
+            partition: "my_items".into(),
+            key: b"abc".to_vec(),
+            value: vec![0, 1, 2, 3],
+        };
+
+        let json = line.to_json();
+        assert_eq!(
+            r#"{"partition":"my_items","key":"616263","value":"00010203"}"#,
+            json
+        );
+        assert_eq!(Some(line), ExportLine::from_json(&json));
+    }
+
+    #[test]
+    fn cli_export_line_parse_whitespace() {
+        let line = ExportLine::from_json(
+            r#" { "key" : "61", "other": "x", "partition": "a\"b", "value": "" } "#,
+        )
+        .expect("should parse");
+
+        assert_eq!("a\"b", line.partition);
+        assert_eq!(b"a", &*line.key);
+        assert!(line.value.is_empty());
+    }
+
+    #[test]
+    fn cli_export_line_parse_invalid() {
+        assert!(ExportLine::from_json("").is_none());
+        assert!(ExportLine::from_json(r#"{"partition":"a","key":"6"}"#).is_none());
+        assert!(ExportLine::from_json(r#"{"partition":"a","key":"61"}"#).is_none());
+    }
+}
@@ -0,0 +1,157 @@
+use crate::encoding::Format;
+use fjall::{JournalMarker, JournalShardReader};
+use std::{io::Write, path::Path};
+
+const JOURNALS_FOLDER: &str = "journals";
+const FLUSH_MARKER: &str = ".flush";
+const FLUSH_PARTITIONS_LIST: &str = ".partition_ids";
+const LEGACY_FLUSH_PARTITIONS_LIST: &str = ".partitions";
+
+/// Returns the journal folders of a keyspace, sorted by journal ID
+fn list_journals(keyspace_path: &Path) -> std::io::Result<Vec<(u64, std::path::PathBuf)>> {
+    let mut journals = vec![];
+
+    for dirent in std::fs::read_dir(keyspace_path.join(JOURNALS_FOLDER))? {
+        let dirent = dirent?;
+
+        let Some(id) = dirent
+            .file_name()
+            .to_str()
+            .and_then(|x| x.parse::<u64>().ok())
+        else {
+            continue;
+        };
+
+        journals.push((id, dirent.path()));
+    }
+
+    journals.sort_by_key(|(id, _)| *id);
+
+    Ok(journals)
+}
+
+/// Returns the shard files of a journal, sorted by shard index
+fn list_shards(journal_path: &Path) -> std::io::Result<Vec<(u64, std::path::PathBuf)>> {
+    let mut shards = vec![];
+
+    for dirent in std::fs::read_dir(journal_path)? {
+        let dirent = dirent?;
+
+        let Some(idx) = dirent
+            .file_name()
+            .to_str()
+            .and_then(|x| x.parse::<u64>().ok())
+        else {
+            continue;
+        };
+
+        shards.push((idx, dirent.path()));
+    }
+
+    shards.sort_by_key(|(idx, _)| *idx);
+
+    Ok(shards)
+}
+
+/// Returns the amount of journals of a keyspace (active + sealed)
+pub fn count(keyspace_path: &Path) -> std::io::Result<usize> {
+    list_journals(keyspace_path).map(|x| x.len())
+}
+
+/// Prints all markers of all journals (active and sealed), without modifying them
+pub fn dump<W: Write>(keyspace_path: &Path, format: Format, out: &mut W) -> fjall::Result<()> {
+    for (id, journal_path) in list_journals(keyspace_path)? {
+        let is_sealed = journal_path.join(FLUSH_MARKER).try_exists()?;
+
+        writeln!(
+            out,
+            "journal {id} ({})",
+            if is_sealed { "sealed" } else { "active" }
+        )?;
+
+        if is_sealed {
+            for list in [FLUSH_PARTITIONS_LIST, LEGACY_FLUSH_PARTITIONS_LIST] {
+                let partitions_list = journal_path.join(list);
+
+                if partitions_list.try_exists()? {
+                    for line in std::fs::read_to_string(partitions_list)?.lines() {
+                        if !line.is_empty() {
+                            writeln!(out, "  partition seqno {line}")?;
+                        }
+                    }
+                }
+            }
+        }
+
+        for (idx, shard_path) in list_shards(&journal_path)? {
+            writeln!(out, "  shard {idx}")?;
+
+            // NOTE: Shards are preallocated (zeroed), so the tail of a shard
+            // decodes as garbage markers, which is where we stop.
+            // Batches are only printed once they are terminated.
+            let mut batch: Option<Vec<String>> = None;
+
+            for item in JournalShardReader::read_only(&shard_path)? {
+                let (pos, marker) = item?;
+
+                match marker {
+                    JournalMarker::Start { item_count, seqno } => {
+                        if batch.is_some() {
+                            break;
+                        }
+
+                        batch = Some(vec![format!(
+                            "    @{pos} start seqno={seqno} items={item_count}"
+                        )]);
+                    }
+                    JournalMarker::Item {
+                        partition,
+                        key,
+                        value,
+                        value_type,
+                    } => {
+                        let Some(lines) = &mut batch else {
+                            break;
+                        };
+
+                        lines.push(format!(
+                            "    @{pos} item {partition} {:?} key={} value={}",
+                            value_type,
+                            format.encode(&key),
+                            format.encode(&value),
+                        ));
+                    }
+                    JournalMarker::ItemById {
+                        partition_id,
+                        key,
+                        value,
+                        value_type,
+                    } => {
+                        let Some(lines) = &mut batch else {
+                            break;
+                        };
+
+                        lines.push(format!(
+                            "    @{pos} item #{partition_id} {:?} key={} value={}",
+                            value_type,
+                            format.encode(&key),
+                            format.encode(&value),
+                        ));
+                    }
+                    JournalMarker::End(crc) => {
+                        let Some(lines) = batch.take() else {
+                            break;
+                        };
+
+                        for line in lines {
+                            writeln!(out, "{line}")?;
+                        }
+                        writeln!(out, "    @{pos} end crc={crc}")?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
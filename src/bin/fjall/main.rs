@@ -0,0 +1,424 @@
+//! Command-line tool to inspect and maintain fjall keyspaces offline.
+//!
+//! The keyspace must not be opened by another process while using this tool.
+
+mod encoding;
+mod journal;
+
+use encoding::{ExportLine, Format};
+use fjall::{Config, Keyspace, PartitionCreateOptions, PartitionHandle};
+use std::{
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+type CliResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+const USAGE: &str = "\
+Usage: fjall <command> <keyspace path> [options]
+
+Commands:
+  info                          Prints version, partitions, sizes and journal count
+  dump-journal [--hex]          Prints all journal markers, including sealed journals
+  scan <partition>              Prints the items of a partition
+      [--prefix <prefix>] [--from <key>] [--to <key>]
+      [--limit <n>] [--reverse] [--hex]
+  get <partition> <key> [--hex] Prints a single item
+  export [--partition <name>]... [--output <file>]
+                                Exports partitions as JSON lines (default: all, to stdout)
+  import [--input <file>]       Imports JSON lines, creating the keyspace if needed
+                                (default: from stdin)
+  verify                        Verifies the checksums of all disk segments
+  compact [--partition <name>]  Runs a major compaction (default: all partitions)
+";
+
+const FJALL_MARKER: &str = "version";
+
+/// Parsed command-line arguments, split into positionals and options
+struct Args {
+    positionals: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    fn parse<I: Iterator<Item = String>>(args: I) -> CliResult<Self> {
+        const FLAGS: &[&str] = &["--hex", "--reverse"];
+
+        let mut positionals = vec![];
+        let mut options = vec![];
+
+        let mut args = args.peekable();
+
+        while let Some(arg) = args.next() {
+            if FLAGS.contains(&arg.as_str()) {
+                options.push((arg, None));
+            } else if arg.starts_with("--") {
+                let Some(value) = args.next() else {
+                    return Err(format!("missing value for option {arg}").into());
+                };
+                options.push((arg, Some(value)));
+            } else {
+                positionals.push(arg);
+            }
+        }
+
+        Ok(Self {
+            positionals,
+            options,
+        })
+    }
+
+    fn positional(&self, idx: usize, name: &str) -> CliResult<&str> {
+        self.positionals
+            .get(idx)
+            .map(String::as_str)
+            .ok_or_else(|| format!("missing argument <{name}>").into())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(k, _)| k == name)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(k, _)| k == name)
+            .and_then(|(_, v)| v.as_deref())
+    }
+
+    fn options(&self, name: &str) -> Vec<&str> {
+        self.options
+            .iter()
+            .filter(|(k, _)| k == name)
+            .filter_map(|(_, v)| v.as_deref())
+            .collect()
+    }
+
+    fn format(&self) -> Format {
+        if self.flag("--hex") {
+            Format::Hex
+        } else {
+            Format::Utf8
+        }
+    }
+}
+
+/// Opens an existing keyspace
+fn open_keyspace(path: &Path) -> CliResult<Keyspace> {
+    if !path.join(FJALL_MARKER).try_exists()? {
+        return Err(format!("{path:?} is not a keyspace").into());
+    }
+
+    Ok(Config::new(path).open()?)
+}
+
+fn open_existing_partition(keyspace: &Keyspace, name: &str) -> CliResult<PartitionHandle> {
+    if !keyspace.partition_exists(name) {
+        return Err(format!("partition {name:?} does not exist").into());
+    }
+
+    Ok(keyspace.open_partition(name, PartitionCreateOptions::default())?)
+}
+
+fn read_version(path: &Path) -> CliResult<u16> {
+    let bytes = std::fs::read(path.join(FJALL_MARKER))?;
+
+    match bytes.get(0..5) {
+        Some([b'F', b'J', b'L', hi, lo]) => Ok(u16::from_be_bytes([*hi, *lo])),
+        _ => Err("invalid version marker".into()),
+    }
+}
+
+fn sorted_partition_names(keyspace: &Keyspace) -> Vec<String> {
+    let mut names = keyspace
+        .list_partitions()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+fn info<W: Write>(path: &Path, out: &mut W) -> CliResult<()> {
+    let keyspace = open_keyspace(path)?;
+
+    let version = read_version(path)?;
+    let journal_count = journal::count(path)?;
+
+    writeln!(out, "path: {}", path.display())?;
+    writeln!(out, "version: {version}")?;
+    writeln!(out, "journals: {journal_count}")?;
+    writeln!(out, "disk space: {} B", keyspace.disk_space())?;
+    writeln!(out, "write buffer: {} B", keyspace.write_buffer_size())?;
+    writeln!(out, "partitions: {}", keyspace.partition_count())?;
+
+    for name in sorted_partition_names(&keyspace) {
+        let partition = open_existing_partition(&keyspace, &name)?;
+
+        writeln!(
+            out,
+            "  {name}: {} B on disk, {} segments, ~{} items",
+            partition.disk_space(),
+            partition.segment_count(),
+            partition.approximate_len(),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn scan<W: Write>(path: &Path, args: &Args, out: &mut W) -> CliResult<()> {
+    use std::ops::Bound::{Included, Unbounded};
+
+    let keyspace = open_keyspace(path)?;
+    let partition = open_existing_partition(&keyspace, args.positional(2, "partition")?)?;
+
+    let format = args.format();
+    let decode = |s: &str| {
+        format
+            .decode(s)
+            .ok_or_else(|| format!("invalid key: {s:?}"))
+    };
+
+    let limit = args
+        .option("--limit")
+        .map(str::parse::<usize>)
+        .transpose()?
+        .unwrap_or(usize::MAX);
+
+    let iter: Box<dyn DoubleEndedIterator<Item = fjall::Result<_>>> =
+        if let Some(prefix) = args.option("--prefix") {
+            Box::new(partition.prefix(decode(prefix)?))
+        } else {
+            let lo = args
+                .option("--from")
+                .map(decode)
+                .transpose()?
+                .map_or(Unbounded, Included);
+
+            let hi = args
+                .option("--to")
+                .map(decode)
+                .transpose()?
+                .map_or(Unbounded, Included);
+
+            Box::new(partition.range::<Vec<u8>, _>((lo, hi)))
+        };
+
+    let iter = if args.flag("--reverse") {
+        Box::new(iter.rev())
+    } else {
+        iter
+    };
+
+    for kv in iter.take(limit) {
+        let (key, value) = kv?;
+        writeln!(out, "{} => {}", format.encode(&key), format.encode(&value))?;
+    }
+
+    Ok(())
+}
+
+fn get<W: Write>(path: &Path, args: &Args, out: &mut W) -> CliResult<()> {
+    let keyspace = open_keyspace(path)?;
+    let partition = open_existing_partition(&keyspace, args.positional(2, "partition")?)?;
+
+    let format = args.format();
+    let key = args.positional(3, "key")?;
+    let key = format
+        .decode(key)
+        .ok_or_else(|| format!("invalid key: {key:?}"))?;
+
+    match partition.get(key)? {
+        Some(value) => writeln!(out, "{}", format.encode(&value))?,
+        None => return Err("key not found".into()),
+    }
+
+    Ok(())
+}
+
+fn export<W: Write>(path: &Path, args: &Args, out: W) -> CliResult<()> {
+    let keyspace = open_keyspace(path)?;
+
+    let names = args.options("--partition");
+    let names = if names.is_empty() {
+        sorted_partition_names(&keyspace)
+    } else {
+        names.into_iter().map(String::from).collect()
+    };
+
+    let partitions = names
+        .iter()
+        .map(|name| open_existing_partition(&keyspace, name))
+        .collect::<CliResult<Vec<_>>>()?;
+
+    // NOTE: Export all partitions at the same point in time
+    let instant = keyspace.instant();
+
+    let mut out = BufWriter::new(out);
+    let mut count = 0;
+
+    for partition in &partitions {
+        for kv in partition.snapshot_at(instant).iter() {
+            let (key, value) = kv?;
+
+            let line = ExportLine {
+                partition: partition.name().to_string(),
+                key: key.to_vec(),
+                value: value.to_vec(),
+            };
+            writeln!(out, "{}", line.to_json())?;
+
+            count += 1;
+        }
+    }
+
+    out.flush()?;
+
+    eprintln!("exported {count} items");
+
+    Ok(())
+}
+
+fn import<R: BufRead>(path: &Path, reader: R) -> CliResult<()> {
+    const BATCH_SIZE: usize = 1_000;
+
+    // NOTE: Importing may create a new keyspace
+    //
+    // IMPORTANT: Memtables need to be flushed in the background,
+    // otherwise writes stall forever once the write buffer is full
+    let keyspace = Config::new(path).open()?;
+
+    let mut batch = keyspace.batch();
+    let mut batch_len = 0;
+    let mut count = 0;
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let item = ExportLine::from_json(&line)
+            .ok_or_else(|| format!("invalid item in line {}", idx + 1))?;
+
+        let partition =
+            keyspace.open_partition(&item.partition, PartitionCreateOptions::default())?;
+        batch.insert(&partition, item.key, item.value);
+
+        batch_len += 1;
+        count += 1;
+
+        if batch_len == BATCH_SIZE {
+            std::mem::replace(&mut batch, keyspace.batch()).commit()?;
+            batch_len = 0;
+        }
+    }
+
+    batch.commit()?;
+    keyspace.persist(fjall::PersistMode::SyncAll)?;
+
+    eprintln!("imported {count} items");
+
+    Ok(())
+}
+
+fn verify<W: Write>(path: &Path, out: &mut W) -> CliResult<()> {
+    let keyspace = open_keyspace(path)?;
+
+    let mut broken = 0;
+
+    for name in sorted_partition_names(&keyspace) {
+        let partition = open_existing_partition(&keyspace, &name)?;
+
+        match partition.verify() {
+            Ok(0) => writeln!(out, "{name}: OK")?,
+            Ok(n) => {
+                writeln!(out, "{name}: {n} corrupted blocks")?;
+                broken += n;
+            }
+            Err(e) => {
+                writeln!(out, "{name}: {e:?}")?;
+                broken += 1;
+            }
+        }
+    }
+
+    if broken > 0 {
+        return Err("keyspace is corrupted".into());
+    }
+
+    Ok(())
+}
+
+fn compact<W: Write>(path: &Path, args: &Args, out: &mut W) -> CliResult<()> {
+    let keyspace = open_keyspace(path)?;
+
+    let names = args.options("--partition");
+    let names = if names.is_empty() {
+        sorted_partition_names(&keyspace)
+    } else {
+        names.into_iter().map(String::from).collect()
+    };
+
+    let partitions = names
+        .iter()
+        .map(|name| open_existing_partition(&keyspace, name))
+        .collect::<CliResult<Vec<_>>>()?;
+
+    for (name, partition) in names.iter().zip(&partitions) {
+        writeln!(out, "compacting {name}...")?;
+        partition.major_compact()?;
+        writeln!(out, "{name}: {} segments", partition.segment_count())?;
+    }
+
+    Ok(())
+}
+
+fn run() -> CliResult<()> {
+    let args = Args::parse(std::env::args().skip(1))?;
+
+    let Some(command) = args.positionals.first() else {
+        eprint!("{USAGE}");
+        return Err("missing command".into());
+    };
+
+    if command == "help" || args.flag("--help") {
+        print!("{USAGE}");
+        return Ok(());
+    }
+
+    let path = PathBuf::from(args.positional(1, "keyspace path")?);
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+
+    match command.as_str() {
+        "info" => info(&path, &mut out),
+        "dump-journal" => Ok(journal::dump(&path, args.format(), &mut out)?),
+        "scan" => scan(&path, &args, &mut out),
+        "get" => get(&path, &args, &mut out),
+        "export" => match args.option("--output") {
+            Some(file) => export(&path, &args, std::fs::File::create(file)?),
+            None => export(&path, &args, out),
+        },
+        "import" => match args.option("--input") {
+            Some(file) => import(&path, BufReader::new(std::fs::File::open(file)?)),
+            None => import(&path, std::io::stdin().lock()),
+        },
+        "verify" => verify(&path, &mut out),
+        "compact" => compact(&path, &args, &mut out),
+        _ => {
+            eprint!("{USAGE}");
+            Err(format!("unknown command: {command}").into())
+        }
+    }
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
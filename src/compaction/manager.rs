@@ -1,4 +1,4 @@
-use crate::PartitionHandle;
+use crate::{manifest::PartitionId, PartitionHandle};
 use std::{
     collections::VecDeque,
     sync::{Arc, Mutex},
@@ -39,9 +39,9 @@ impl std::ops::Deref for CompactionManager {
 }
 
 impl CompactionManager {
-    pub fn remove_partition(&self, name: &str) {
+    pub fn remove_partition(&self, partition_id: PartitionId) {
         let mut lock = self.partitions.lock().expect("lock is poisoned");
-        lock.retain(|x| &*x.name != name);
+        lock.retain(|x| x.id() != partition_id);
     }
 
     pub fn wait_for(&self) {
@@ -8,7 +8,7 @@ pub fn run(compaction_manager: &CompactionManager) {
 
     log::trace!(
         "compactor: calling compaction strategy for partition {:?}",
-        item.0.name
+        item.name()
     );
     let strategy = item
         .compaction_strategy
@@ -18,7 +18,7 @@ pub fn run(compaction_manager: &CompactionManager) {
 
     // TODO: loop if there's more work to do
 
-    if let Err(e) = item.tree.compact(strategy) {
+    if let Err(e) = item.tree().compact(strategy) {
         log::error!("Compaction failed: {e:?}");
     };
 }
@@ -1,4 +1,7 @@
-use crate::{journal::shard::RecoveryMode, Keyspace};
+use crate::{
+    journal::{shard::RecoveryMode, writer::DEFAULT_PRE_ALLOCATED_BYTES, DEFAULT_SHARD_COUNT},
+    Keyspace,
+};
 use lsm_tree::{descriptor_table::FileDescriptorTable, BlockCache};
 use path_absolutize::Absolutize;
 use std::{
@@ -45,6 +48,18 @@ pub struct Config {
     pub(crate) fsync_ms: Option<u16>,
 
     pub(crate) journal_recovery_mode: RecoveryMode,
+
+    /// Amount of journal shards, which can be written to concurrently
+    pub(crate) journal_shard_count: u8,
+
+    /// Amount of bytes that are preallocated for each journal shard file
+    pub(crate) journal_preallocated_bytes: u64,
+
+    /// Folder that fully flushed journals are moved to, instead of being deleted
+    pub(crate) journal_archive_path: Option<PathBuf>,
+
+    /// Point in time to roll back to when recovering
+    pub(crate) recover_until: Option<crate::Instant>,
 }
 
 const DEFAULT_CPU_CORES: usize = 4;
@@ -79,6 +94,10 @@ impl Default for Config {
             flush_workers_count: cpus,
             compaction_workers_count: cpus,
             journal_recovery_mode: RecoveryMode::default(),
+            journal_shard_count: DEFAULT_SHARD_COUNT,
+            journal_preallocated_bytes: DEFAULT_PRE_ALLOCATED_BYTES,
+            journal_archive_path: None,
+            recover_until: None,
         }
     }
 }
@@ -135,7 +154,12 @@ impl Config {
         self
     }
 
-    /// Max size of all journals in bytes.
+    /// Max size of all sealed journals in bytes.
+    ///
+    /// Once sealed journals take up more space, writes are halted until
+    /// flushes allow the oldest journals to be evicted. The active journal
+    /// (see [`Config::journal_preallocation`]) is not counted, as flushing
+    /// cannot free it up.
     ///
     /// Default = 512 MiB
     ///
@@ -143,9 +167,6 @@ impl Config {
     ///
     /// Panics if bytes < 24 MiB.
     ///
-    /// This option should be at least 24 MiB, as one journal takes up at least 16 MiB, so
-    /// anything less will immediately stall the system.
-    ///
     /// Same as `max_total_wal_size` in `RocksDB`.
     #[must_use]
     pub fn max_journaling_size(mut self, bytes: u64) -> Self {
@@ -172,6 +193,39 @@ impl Config {
         self
     }
 
+    /// Sets the amount of journal shards.
+    ///
+    /// Each shard is a separate journal file that can be written to
+    /// concurrently, so more shards reduce contention between writers
+    /// on machines with many cores. An existing journal that was written
+    /// with a different shard count is still fully recovered.
+    ///
+    /// Default = 4
+    ///
+    /// # Panics
+    ///
+    /// Panics if n is 0.
+    #[must_use]
+    pub fn journal_shards(mut self, n: u8) -> Self {
+        assert!(n > 0);
+
+        self.journal_shard_count = n;
+        self
+    }
+
+    /// Sets the amount of bytes that are preallocated for each journal shard file.
+    ///
+    /// The active journal takes up at least `journal_shards * bytes` on disk.
+    /// This is included in [`crate::Keyspace::disk_space`], but does not count
+    /// towards the maximum journaling size (see [`Config::max_journaling_size`]).
+    ///
+    /// Default = 8 MiB
+    #[must_use]
+    pub fn journal_preallocation(mut self, bytes: u64) -> Self {
+        self.journal_preallocated_bytes = bytes;
+        self
+    }
+
     /// If Some, starts an fsync thread that asynchronously
     /// persists data.
     ///
@@ -190,6 +244,51 @@ impl Config {
         self
     }
 
+    /// If set, fully flushed journals are moved into the given folder, instead of being deleted.
+    ///
+    /// Together with a copy of the keyspace folder (a base checkpoint), the archive
+    /// can be used to recover the keyspace to an earlier point in time,
+    /// see [`Config::recover_until`].
+    ///
+    /// The archive is never cleaned up by the keyspace, and should only be used by a single keyspace.
+    ///
+    /// Default = None
+    #[must_use]
+    pub fn journal_archive<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.journal_archive_path = Some(absolute_path(path));
+        self
+    }
+
+    /// Rolls back the keyspace to the given instant when opening it.
+    ///
+    /// Journals in the keyspace and the journal archive (see [`Config::journal_archive`])
+    /// are replayed, up to (excluding) the given instant. Journals that are still
+    /// in use by another keyspace may be copied into the archive, to roll forward further.
+    ///
+    /// The rollback is only possible if no disk segment of the keyspace contains
+    /// any data at or after the instant, so it should be applied to an older copy of the keyspace
+    /// (a base checkpoint), that was taken before the instant.
+    ///
+    /// All data at or after the instant is discarded from the keyspace.
+    /// Partition deletions are not journaled, so partitions that have been deleted
+    /// before the instant may reappear.
+    ///
+    /// The option should only be set for opening the keyspace once: after a successful
+    /// rollback, the data is persisted in disk segments. A new journal archive should be used
+    /// from then on, as the discarded journals remain in the old archive.
+    ///
+    /// Default = None
+    #[must_use]
+    pub fn recover_until(mut self, instant: crate::Instant) -> Self {
+        self.recover_until = Some(instant);
+        self
+    }
+
+    /// Returns the preallocated size of the active journal
+    pub(crate) fn active_journal_size(&self) -> u64 {
+        u64::from(self.journal_shard_count) * self.journal_preallocated_bytes
+    }
+
     /// Opens a keyspace using the config.
     ///
     /// # Errors
@@ -208,4 +307,14 @@ impl Config {
     pub fn open_transactional(self) -> crate::Result<crate::TxKeyspace> {
         crate::TxKeyspace::open(self)
     }
+
+    /// Opens an async keyspace using the config.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    #[cfg(feature = "async")]
+    pub async fn open_async(self) -> crate::Result<crate::AsyncKeyspace> {
+        crate::AsyncKeyspace::open(self).await
+    }
 }
@@ -0,0 +1,320 @@
+use crate::{partition::read::PendingWrites, Instant, PartitionHandle, ReadOptions};
+use lsm_tree::{KvPair, Snapshot, UserKey, UserValue};
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+type BoxedIter = Box<dyn DoubleEndedIterator<Item = crate::Result<KvPair>>>;
+
+/// Direction the underlying iterator was created for
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Direction {
+    Forward,
+    Backward,
+}
+
+/// Returns the tighter of two lower bounds
+fn max_lower(a: Bound<UserKey>, b: Bound<UserKey>) -> Bound<UserKey> {
+    match (&a, &b) {
+        (_, Unbounded) => a,
+        (Included(x) | Excluded(x), Included(y) | Excluded(y)) if x > y => a,
+        (Excluded(x), Included(y)) if x == y => a,
+        _ => b,
+    }
+}
+
+/// Returns the tighter of two upper bounds
+fn min_upper(a: Bound<UserKey>, b: Bound<UserKey>) -> Bound<UserKey> {
+    match (&a, &b) {
+        (_, Unbounded) => a,
+        (Included(x) | Excluded(x), Included(y) | Excluded(y)) if x < y => a,
+        (Excluded(x), Included(y)) if x == y => a,
+        _ => b,
+    }
+}
+
+/// Returns `true` if no key can be inside the given bounds
+fn is_empty_range(lower: &Bound<UserKey>, upper: &Bound<UserKey>) -> bool {
+    match (lower, upper) {
+        (Included(lo), Included(hi)) => lo > hi,
+        (Included(lo) | Excluded(lo), Included(hi) | Excluded(hi)) => lo >= hi,
+        _ => false,
+    }
+}
+
+/// A seekable, bidirectional cursor over a consistent view of a partition
+///
+/// Unlike iterators, a cursor can be repositioned using [`Cursor::seek`]
+/// and [`Cursor::seek_for_prev`], and can change direction at any point.
+///
+/// A new cursor is not positioned; use one of the seek functions first.
+///
+/// As long as the cursor is open, old versions of items will not be evicted, so
+/// the cursor's view stays consistent. Thus, cursors should not be kept around for long.
+pub struct Cursor {
+    partition: PartitionHandle,
+    seqno: Instant,
+    pending: Option<PendingWrites>,
+
+    /// Keeps old versions from being evicted while the cursor is open
+    #[allow(unused)]
+    snapshot: Snapshot,
+
+    lower_bound: Bound<UserKey>,
+    upper_bound: Bound<UserKey>,
+
+    current: Option<KvPair>,
+    iter: Option<(Direction, BoxedIter)>,
+}
+
+impl Cursor {
+    pub(crate) fn new(
+        partition: PartitionHandle,
+        seqno: Instant,
+        pending: Option<PendingWrites>,
+    ) -> Self {
+        Self {
+            snapshot: partition.tree().snapshot(seqno),
+            partition,
+            seqno,
+            pending,
+            lower_bound: Unbounded,
+            upper_bound: Unbounded,
+            current: None,
+            iter: None,
+        }
+    }
+
+    /// Returns `true` if the cursor is positioned at an item.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Returns the key of the current item, if the cursor is positioned.
+    #[must_use]
+    pub fn key(&self) -> Option<&UserKey> {
+        self.current.as_ref().map(|(key, _)| key)
+    }
+
+    /// Returns the value of the current item, if the cursor is positioned.
+    #[must_use]
+    pub fn value(&self) -> Option<&UserValue> {
+        self.current.as_ref().map(|(_, value)| value)
+    }
+
+    /// Returns the current item, if the cursor is positioned.
+    #[must_use]
+    pub fn item(&self) -> Option<&(UserKey, UserValue)> {
+        self.current.as_ref()
+    }
+
+    /// Sets the lower bound of the cursor.
+    ///
+    /// Items below the bound are skipped. The cursor is unpositioned,
+    /// so it needs to be seeked again.
+    pub fn set_lower_bound<K: AsRef<[u8]>>(&mut self, bound: Bound<K>) {
+        self.lower_bound = to_owned_bound(bound);
+        self.reset();
+    }
+
+    /// Sets the upper bound of the cursor.
+    ///
+    /// Items above the bound are skipped. The cursor is unpositioned,
+    /// so it needs to be seeked again.
+    pub fn set_upper_bound<K: AsRef<[u8]>>(&mut self, bound: Bound<K>) {
+        self.upper_bound = to_owned_bound(bound);
+        self.reset();
+    }
+
+    /// Positions the cursor at the first item inside the bounds.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn seek_to_first(&mut self) -> crate::Result<()> {
+        self.position(Direction::Forward, Unbounded, Unbounded)
+    }
+
+    /// Positions the cursor at the last item inside the bounds.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn seek_to_last(&mut self) -> crate::Result<()> {
+        self.position(Direction::Backward, Unbounded, Unbounded)
+    }
+
+    /// Positions the cursor at the first item with a key that is greater than or equal to the given key.
+    ///
+    /// If there is no such item inside the bounds, the cursor becomes invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("c", "abc")?;
+    ///
+    /// let mut cursor = partition.cursor();
+    ///
+    /// cursor.seek("b")?;
+    /// assert_eq!(Some(&"c".as_bytes().into()), cursor.key());
+    ///
+    /// cursor.seek("d")?;
+    /// assert!(!cursor.is_valid());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn seek<K: AsRef<[u8]>>(&mut self, key: K) -> crate::Result<()> {
+        self.position(Direction::Forward, Included(key.as_ref().into()), Unbounded)
+    }
+
+    /// Positions the cursor at the last item with a key that is less than or equal to the given key.
+    ///
+    /// If there is no such item inside the bounds, the cursor becomes invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("c", "abc")?;
+    ///
+    /// let mut cursor = partition.cursor();
+    ///
+    /// cursor.seek_for_prev("b")?;
+    /// assert_eq!(Some(&"a".as_bytes().into()), cursor.key());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn seek_for_prev<K: AsRef<[u8]>>(&mut self, key: K) -> crate::Result<()> {
+        self.position(
+            Direction::Backward,
+            Unbounded,
+            Included(key.as_ref().into()),
+        )
+    }
+
+    /// Moves the cursor to the next item.
+    ///
+    /// If the cursor is not positioned, this is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> crate::Result<()> {
+        self.step(Direction::Forward)
+    }
+
+    /// Moves the cursor to the previous item.
+    ///
+    /// If the cursor is not positioned, this is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn prev(&mut self) -> crate::Result<()> {
+        self.step(Direction::Backward)
+    }
+
+    fn reset(&mut self) {
+        self.current = None;
+        self.iter = None;
+    }
+
+    fn step(&mut self, direction: Direction) -> crate::Result<()> {
+        let Some((key, _)) = &self.current else {
+            return Ok(());
+        };
+
+        // NOTE: Keep advancing the existing iterator as long as the direction does not change
+        if let Some((iter_direction, iter)) = &mut self.iter {
+            if *iter_direction == direction {
+                let item = match direction {
+                    Direction::Forward => iter.next(),
+                    Direction::Backward => iter.next_back(),
+                };
+                return self.set_current(item);
+            }
+        }
+
+        let key = key.clone();
+
+        match direction {
+            Direction::Forward => self.position(direction, Excluded(key), Unbounded),
+            Direction::Backward => self.position(direction, Unbounded, Excluded(key)),
+        }
+    }
+
+    /// Creates a new iterator inside the cursor's bounds, and moves to its first item
+    fn position(
+        &mut self,
+        direction: Direction,
+        lower: Bound<UserKey>,
+        upper: Bound<UserKey>,
+    ) -> crate::Result<()> {
+        self.reset();
+
+        let lower = max_lower(lower, self.lower_bound.clone());
+        let upper = min_upper(upper, self.upper_bound.clone());
+
+        if is_empty_range(&lower, &upper) {
+            return Ok(());
+        }
+
+        let mut iter: BoxedIter = self.partition.create_range_with_options(
+            (lower, upper),
+            self.pending.clone(),
+            ReadOptions::default().snapshot(Some(self.seqno)),
+        );
+
+        let item = match direction {
+            Direction::Forward => iter.next(),
+            Direction::Backward => iter.next_back(),
+        };
+        self.iter = Some((direction, iter));
+
+        self.set_current(item)
+    }
+
+    fn set_current(&mut self, item: Option<crate::Result<KvPair>>) -> crate::Result<()> {
+        match item {
+            Some(Ok(kv)) => {
+                self.current = Some(kv);
+                Ok(())
+            }
+            Some(Err(e)) => {
+                self.reset();
+                Err(e)
+            }
+            None => {
+                self.reset();
+                Ok(())
+            }
+        }
+    }
+}
+
+fn to_owned_bound<K: AsRef<[u8]>>(bound: Bound<K>) -> Bound<UserKey> {
+    match bound {
+        Included(key) => Included(key.as_ref().into()),
+        Excluded(key) => Excluded(key.as_ref().into()),
+        Unbounded => Unbounded,
+    }
+}
@@ -0,0 +1,766 @@
+use crate::{
+    batch::PartitionKey, file::SEGMENTS_FOLDER, Keyspace, PartitionCreateOptions, PartitionHandle,
+};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use lsm_tree::{
+    serde::{Deserializable, Serializable},
+    DeserializeError, MemTable, SeqNo, SerializeError, UserKey, UserValue, Value, ValueType,
+};
+use std::{
+    io::{Read, Write},
+    sync::Arc,
+};
+
+const DUMP_MAGIC: &[u8] = b"FJLDMP";
+
+/// Approximate size of segments created when bulk loading a partition
+const BULK_LOAD_SEGMENT_SIZE: u32 = 64 * 1_024 * 1_024;
+
+/// Amount of items per batch when importing into an existing partition
+const IMPORT_BATCH_SIZE: usize = 1_000;
+
+/// Version of the dump format
+pub const DUMP_FORMAT_VERSION: u16 = 1;
+
+/// Errors that may occur when importing a dump
+#[derive(Debug, Eq, PartialEq)]
+#[allow(clippy::module_name_repetitions)]
+pub enum DumpError {
+    /// The stream does not start with the dump header
+    InvalidHeader,
+
+    /// The dump was written using an unsupported format version
+    UnsupportedVersion(u16),
+
+    /// A marker was found in a place it does not belong to
+    UnexpectedMarker,
+
+    /// The stream ended before the dump end marker
+    Truncated,
+
+    /// Partition name is not valid
+    InvalidPartitionName,
+
+    /// Partition creation options are not valid
+    InvalidPartitionOptions,
+
+    /// Keys of a partition are not in strictly ascending order
+    UnsortedKeys,
+
+    /// The item count of a partition does not match the expected value
+    ItemCount,
+
+    /// The CRC value of a partition does not match the expected value
+    CrcCheck,
+}
+
+/// A dump is a header, followed by N partitions, followed by an end marker.
+///
+/// Each partition is wrapped in a `PartitionStart` marker (containing its name
+/// and creation options), followed by its items in ascending key order,
+/// followed by a `PartitionEnd` marker, containing the item count & CRC of the items.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Marker {
+    PartitionStart {
+        name: PartitionKey,
+        block_size: u32,
+        level_count: u8,
+        level_ratio: u8,
+    },
+    Item {
+        key: UserKey,
+        value: UserValue,
+    },
+    PartitionEnd {
+        item_count: u64,
+        crc: u32,
+    },
+    End {
+        partition_count: u32,
+    },
+}
+
+pub enum Tag {
+    PartitionStart = 0,
+    Item = 1,
+    PartitionEnd = 2,
+    End = 3,
+}
+
+impl TryFrom<u8> for Tag {
+    type Error = DeserializeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use Tag::{End, Item, PartitionEnd, PartitionStart};
+
+        match value {
+            0 => Ok(PartitionStart),
+            1 => Ok(Item),
+            2 => Ok(PartitionEnd),
+            3 => Ok(End),
+            _ => Err(DeserializeError::InvalidTag(("DumpMarkerTag", value))),
+        }
+    }
+}
+
+impl From<Tag> for u8 {
+    fn from(val: Tag) -> Self {
+        val as Self
+    }
+}
+
+impl Serializable for Marker {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
+        use Marker::{End, Item, PartitionEnd, PartitionStart};
+
+        match self {
+            PartitionStart {
+                name,
+                block_size,
+                level_count,
+                level_ratio,
+            } => {
+                writer.write_u8(Tag::PartitionStart.into())?;
+
+                // NOTE: Partition names are limited to 255 characters
+                #[allow(clippy::cast_possible_truncation)]
+                writer.write_u8(name.len() as u8)?;
+                writer.write_all(name.as_bytes())?;
+
+                writer.write_u32::<BigEndian>(*block_size)?;
+                writer.write_u8(*level_count)?;
+                writer.write_u8(*level_ratio)?;
+            }
+            Item { key, value } => {
+                writer.write_u8(Tag::Item.into())?;
+
+                // NOTE: Keys are limited to 2^16 bytes
+                #[allow(clippy::cast_possible_truncation)]
+                writer.write_u16::<BigEndian>(key.len() as u16)?;
+                writer.write_all(key)?;
+
+                // NOTE: Values are limited to 2^32 bytes
+                #[allow(clippy::cast_possible_truncation)]
+                writer.write_u32::<BigEndian>(value.len() as u32)?;
+                writer.write_all(value)?;
+            }
+            PartitionEnd { item_count, crc } => {
+                writer.write_u8(Tag::PartitionEnd.into())?;
+                writer.write_u64::<BigEndian>(*item_count)?;
+                writer.write_u32::<BigEndian>(*crc)?;
+            }
+            End { partition_count } => {
+                writer.write_u8(Tag::End.into())?;
+                writer.write_u32::<BigEndian>(*partition_count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Deserializable for Marker {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self, DeserializeError> {
+        match reader.read_u8()?.try_into()? {
+            Tag::PartitionStart => {
+                let name_len = reader.read_u8()?;
+                let mut name = vec![0; name_len.into()];
+                reader.read_exact(&mut name)?;
+
+                let name = String::from_utf8(name).map_err(|e| {
+                    DeserializeError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+                })?;
+
+                let block_size = reader.read_u32::<BigEndian>()?;
+                let level_count = reader.read_u8()?;
+                let level_ratio = reader.read_u8()?;
+
+                Ok(Self::PartitionStart {
+                    name: name.into(),
+                    block_size,
+                    level_count,
+                    level_ratio,
+                })
+            }
+            Tag::Item => {
+                let key_len = reader.read_u16::<BigEndian>()?;
+                let mut key = vec![0; key_len.into()];
+                reader.read_exact(&mut key)?;
+
+                let value_len = reader.read_u32::<BigEndian>()?;
+
+                // NOTE: The length is not verified yet, so read through `take`
+                // instead of allocating it upfront, as a corrupt length
+                // could otherwise cause a huge allocation
+                let mut value = vec![];
+                reader
+                    .by_ref()
+                    .take(value_len.into())
+                    .read_to_end(&mut value)?;
+
+                if value.len() != value_len as usize {
+                    return Err(DeserializeError::Io(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "dump item value is truncated",
+                    )));
+                }
+
+                Ok(Self::Item {
+                    key: key.into(),
+                    value: value.into(),
+                })
+            }
+            Tag::PartitionEnd => {
+                let item_count = reader.read_u64::<BigEndian>()?;
+                let crc = reader.read_u32::<BigEndian>()?;
+                Ok(Self::PartitionEnd { item_count, crc })
+            }
+            Tag::End => {
+                let partition_count = reader.read_u32::<BigEndian>()?;
+                Ok(Self::End { partition_count })
+            }
+        }
+    }
+}
+
+/// Updates a partition checksum with a single item
+fn hash_item(hasher: &mut crc32fast::Hasher, key: &[u8], value: &[u8]) {
+    // NOTE: Lengths are hashed too, so moving bytes between key & value is detected
+    #[allow(clippy::cast_possible_truncation)]
+    hasher.update(&(key.len() as u16).to_be_bytes());
+    hasher.update(key);
+
+    #[allow(clippy::cast_possible_truncation)]
+    hasher.update(&(value.len() as u32).to_be_bytes());
+    hasher.update(value);
+}
+
+/// Writes a dump, partition by partition
+pub struct Writer<W: Write> {
+    inner: W,
+    partition_count: u32,
+    item_count: u64,
+    hasher: crc32fast::Hasher,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(mut inner: W) -> crate::Result<Self> {
+        inner.write_all(DUMP_MAGIC)?;
+        inner.write_u16::<BigEndian>(DUMP_FORMAT_VERSION)?;
+
+        Ok(Self {
+            inner,
+            partition_count: 0,
+            item_count: 0,
+            hasher: crc32fast::Hasher::new(),
+        })
+    }
+
+    pub fn start_partition(
+        &mut self,
+        name: PartitionKey,
+        tree: &lsm_tree::Tree,
+    ) -> crate::Result<()> {
+        Marker::PartitionStart {
+            name,
+            block_size: tree.config.inner.block_size,
+            level_count: tree.config.inner.level_count,
+            level_ratio: tree.config.level_ratio,
+        }
+        .serialize(&mut self.inner)?;
+
+        self.item_count = 0;
+        self.hasher = crc32fast::Hasher::new();
+
+        Ok(())
+    }
+
+    pub fn write_item(&mut self, key: UserKey, value: UserValue) -> crate::Result<()> {
+        hash_item(&mut self.hasher, &key, &value);
+        self.item_count += 1;
+
+        Marker::Item { key, value }.serialize(&mut self.inner)?;
+
+        Ok(())
+    }
+
+    /// Returns the amount of items written for the partition
+    pub fn finish_partition(&mut self) -> crate::Result<u64> {
+        let hasher = std::mem::take(&mut self.hasher);
+
+        Marker::PartitionEnd {
+            item_count: self.item_count,
+            crc: hasher.finalize(),
+        }
+        .serialize(&mut self.inner)?;
+
+        self.partition_count += 1;
+
+        Ok(self.item_count)
+    }
+
+    pub fn finish(mut self) -> crate::Result<()> {
+        Marker::End {
+            partition_count: self.partition_count,
+        }
+        .serialize(&mut self.inner)?;
+
+        self.inner.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Event emitted when reading a dump
+pub enum Event {
+    PartitionStart {
+        name: PartitionKey,
+        create_options: PartitionCreateOptions,
+    },
+    Item {
+        key: UserKey,
+        value: UserValue,
+    },
+    PartitionEnd,
+}
+
+/// Reads and validates a dump, partition by partition
+pub struct Reader<R: Read> {
+    inner: R,
+    partition_count: u32,
+    last_key: Option<UserKey>,
+    item_count: u64,
+    hasher: crc32fast::Hasher,
+    in_partition: bool,
+    is_done: bool,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(mut inner: R) -> crate::Result<Self> {
+        let mut magic = [0u8; DUMP_MAGIC.len()];
+
+        if let Err(e) = inner.read_exact(&mut magic) {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                return Err(crate::Error::Dump(DumpError::InvalidHeader));
+            }
+            return Err(e.into());
+        }
+
+        if magic != DUMP_MAGIC {
+            return Err(crate::Error::Dump(DumpError::InvalidHeader));
+        }
+
+        let version = inner.read_u16::<BigEndian>()?;
+
+        if version != DUMP_FORMAT_VERSION {
+            return Err(crate::Error::Dump(DumpError::UnsupportedVersion(version)));
+        }
+
+        Ok(Self {
+            inner,
+            partition_count: 0,
+            last_key: None,
+            item_count: 0,
+            hasher: crc32fast::Hasher::new(),
+            in_partition: false,
+            is_done: false,
+        })
+    }
+
+    fn read_marker(&mut self) -> crate::Result<Marker> {
+        match Marker::deserialize(&mut self.inner) {
+            Ok(marker) => Ok(marker),
+            Err(DeserializeError::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                Err(crate::Error::Dump(DumpError::Truncated))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Returns the next event, or `None` once the dump end marker has been read.
+    pub fn next_event(&mut self) -> crate::Result<Option<Event>> {
+        use crate::partition::name::is_valid_partition_name;
+
+        if self.is_done {
+            return Ok(None);
+        }
+
+        match self.read_marker()? {
+            Marker::PartitionStart {
+                name,
+                block_size,
+                level_count,
+                level_ratio,
+            } => {
+                if self.in_partition {
+                    return Err(crate::Error::Dump(DumpError::UnexpectedMarker));
+                }
+
+                if !is_valid_partition_name(&name) {
+                    return Err(crate::Error::Dump(DumpError::InvalidPartitionName));
+                }
+
+                self.in_partition = true;
+                self.last_key = None;
+                self.item_count = 0;
+                self.hasher = crc32fast::Hasher::new();
+
+                // NOTE: Check the invariants of the option builder, which would panic otherwise
+                if block_size < 1_024 || level_count < 2 || level_ratio == 0 {
+                    return Err(crate::Error::Dump(DumpError::InvalidPartitionOptions));
+                }
+
+                let create_options = PartitionCreateOptions::default()
+                    .block_size(block_size)
+                    .level_count(level_count)
+                    .level_ratio(level_ratio);
+
+                Ok(Some(Event::PartitionStart {
+                    name,
+                    create_options,
+                }))
+            }
+            Marker::Item { key, value } => {
+                if !self.in_partition {
+                    return Err(crate::Error::Dump(DumpError::UnexpectedMarker));
+                }
+
+                if key.is_empty() || self.last_key.as_ref().is_some_and(|last| *last >= key) {
+                    return Err(crate::Error::Dump(DumpError::UnsortedKeys));
+                }
+
+                hash_item(&mut self.hasher, &key, &value);
+                self.item_count += 1;
+                self.last_key = Some(key.clone());
+
+                Ok(Some(Event::Item { key, value }))
+            }
+            Marker::PartitionEnd { item_count, crc } => {
+                if !self.in_partition {
+                    return Err(crate::Error::Dump(DumpError::UnexpectedMarker));
+                }
+
+                if item_count != self.item_count {
+                    return Err(crate::Error::Dump(DumpError::ItemCount));
+                }
+
+                let hasher = std::mem::take(&mut self.hasher);
+
+                if crc != hasher.finalize() {
+                    return Err(crate::Error::Dump(DumpError::CrcCheck));
+                }
+
+                self.in_partition = false;
+                self.partition_count += 1;
+
+                Ok(Some(Event::PartitionEnd))
+            }
+            Marker::End { partition_count } => {
+                if self.in_partition || partition_count != self.partition_count {
+                    return Err(crate::Error::Dump(DumpError::UnexpectedMarker));
+                }
+
+                self.is_done = true;
+
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Writes the given partitions as they were at the given instant.
+///
+/// Returns the amount of items written.
+pub fn export<W: Write>(
+    partitions: &[PartitionHandle],
+    instant: crate::Instant,
+    writer: W,
+) -> crate::Result<u64> {
+    let mut writer = Writer::new(writer)?;
+    let mut item_count = 0;
+
+    for partition in partitions {
+        if partition
+            .is_deleted
+            .load(std::sync::atomic::Ordering::Relaxed)
+        {
+            return Err(crate::Error::PartitionDeleted);
+        }
+
+        let name = partition.name();
+
+        log::debug!("dump: exporting partition {name:?}");
+
+        writer.start_partition(name, &partition.tree())?;
+
+        for kv in partition.snapshot_at(instant).iter() {
+            let (key, value) = kv?;
+            writer.write_item(key, value)?;
+        }
+
+        item_count += writer.finish_partition()?;
+    }
+
+    writer.finish()?;
+
+    Ok(item_count)
+}
+
+/// Reads a dump into the keyspace.
+///
+/// Partitions that do not exist yet are created & bulk loaded,
+/// existing partitions are written to using batches.
+///
+/// Returns the amount of items imported.
+pub fn import<R: Read>(keyspace: &Keyspace, reader: R) -> crate::Result<u64> {
+    let mut reader = Reader::new(reader)?;
+    let mut item_count = 0;
+
+    while let Some(event) = reader.next_event()? {
+        let Event::PartitionStart {
+            name,
+            create_options,
+        } = event
+        else {
+            return Err(crate::Error::Dump(DumpError::UnexpectedMarker));
+        };
+
+        // NOTE: Get the seqno before creating the partition,
+        // so concurrent writes to the new partition shadow the imported items
+        let seqno = keyspace.seqno.next();
+
+        let (partition, is_new) = {
+            let mut partitions = keyspace.partitions.write().expect("lock is poisoned");
+
+            if let Some(partition) = keyspace.load_partition(&mut partitions, &name)? {
+                (partition, false)
+            } else {
+                let handle = PartitionHandle::create_new(keyspace, name.clone(), create_options)?;
+                partitions.insert(name, handle.clone());
+                (handle, true)
+            }
+        };
+
+        if is_new {
+            log::debug!("dump: bulk loading new partition {:?}", partition.name());
+
+            match bulk_load(&partition, &mut reader, seqno) {
+                Ok(count) => item_count += count,
+                Err(e) => {
+                    // NOTE: Don't leave a half-loaded partition behind
+                    if let Err(e) = keyspace.delete_partition(partition) {
+                        log::error!(
+                            "dump: failed to clean up partition after failed import: {e:?}"
+                        );
+                    }
+                    return Err(e);
+                }
+            }
+        } else {
+            log::debug!(
+                "dump: importing into existing partition {:?}",
+                partition.name()
+            );
+            item_count += batch_load(keyspace, &partition, &mut reader)?;
+        }
+    }
+
+    Ok(item_count)
+}
+
+/// Writes the items of a partition directly into disk segments.
+///
+/// The segments are only registered once the partition's checksum has been verified.
+fn bulk_load<R: Read>(
+    partition: &PartitionHandle,
+    reader: &mut Reader<R>,
+    seqno: SeqNo,
+) -> crate::Result<u64> {
+    let tree = &partition.tree();
+
+    let flush = |memtable: MemTable| -> crate::Result<Arc<lsm_tree::Segment>> {
+        let segment = lsm_tree::flush::flush_to_segment(lsm_tree::flush::Options {
+            tree_id: tree.id,
+            segment_id: tree.get_next_segment_id(),
+            memtable: Arc::new(memtable),
+            folder: tree.config.path.join(SEGMENTS_FOLDER),
+            block_size: tree.config.inner.block_size,
+            block_cache: tree.config.block_cache.clone(),
+            descriptor_table: tree.config.descriptor_table.clone(),
+        })?;
+
+        Ok(Arc::new(segment))
+    };
+
+    let mut segments = vec![];
+    let mut memtable = MemTable::default();
+    let mut item_count = 0;
+
+    loop {
+        match reader.next_event()? {
+            Some(Event::Item { key, value }) => {
+                let (_, memtable_size) =
+                    memtable.insert(Value::new(key, value, seqno, ValueType::Value));
+
+                item_count += 1;
+
+                if memtable_size >= BULK_LOAD_SEGMENT_SIZE {
+                    segments.push(flush(std::mem::take(&mut memtable))?);
+                }
+            }
+            Some(Event::PartitionEnd) => break,
+            _ => return Err(crate::Error::Dump(DumpError::UnexpectedMarker)),
+        }
+    }
+
+    if !memtable.is_empty() {
+        segments.push(flush(memtable)?);
+    }
+
+    if !segments.is_empty() {
+        // IMPORTANT: The segments are not covered by the journal,
+        // so the seqno needs to be persisted before they become visible
+        partition.seqno_marker.persist(seqno + 1)?;
+
+        tree.register_segments(&segments)?;
+        partition.compaction_manager.notify(partition.clone());
+    }
+
+    Ok(item_count)
+}
+
+/// Writes the items of a partition using regular write batches.
+fn batch_load<R: Read>(
+    keyspace: &Keyspace,
+    partition: &PartitionHandle,
+    reader: &mut Reader<R>,
+) -> crate::Result<u64> {
+    let mut batch = keyspace.batch();
+    let mut item_count = 0;
+
+    loop {
+        match reader.next_event()? {
+            Some(Event::Item { key, value }) => {
+                batch.insert(partition, key, value);
+
+                item_count += 1;
+
+                if batch.data.len() >= IMPORT_BATCH_SIZE {
+                    std::mem::replace(&mut batch, keyspace.batch()).commit()?;
+                }
+            }
+            Some(Event::PartitionEnd) => break,
+            _ => return Err(crate::Error::Dump(DumpError::UnexpectedMarker)),
+        }
+    }
+
+    batch.commit()?;
+
+    Ok(item_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn dump_marker_serialize_and_deserialize() -> crate::Result<()> {
+        let markers = [
+            Marker::PartitionStart {
+                name: "default".into(),
+                block_size: 4_096,
+                level_count: 7,
+                level_ratio: 8,
+            },
+            Marker::Item {
+                key: vec![1, 2, 3].into(),
+                value: vec![4; 100].into(),
+            },
+            Marker::PartitionEnd {
+                item_count: 1,
+                crc: 12_345,
+            },
+            Marker::End { partition_count: 1 },
+        ];
+
+        for marker in markers {
+            let mut serialized_data = Vec::new();
+            marker.serialize(&mut serialized_data)?;
+
+            let mut reader = &serialized_data[..];
+            assert_eq!(marker, Marker::deserialize(&mut reader)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_invalid_header() {
+        let result = Reader::new(&b"FJLXXX\0\x01"[..]);
+        assert!(matches!(
+            result,
+            Err(crate::Error::Dump(DumpError::InvalidHeader))
+        ));
+
+        let result = Reader::new(&b"FJLDMP\0\x09"[..]);
+        assert!(matches!(
+            result,
+            Err(crate::Error::Dump(DumpError::UnsupportedVersion(9)))
+        ));
+    }
+
+    #[test]
+    fn dump_truncated() -> crate::Result<()> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(DUMP_MAGIC);
+        bytes.write_u16::<BigEndian>(DUMP_FORMAT_VERSION)?;
+
+        let mut reader = Reader::new(&bytes[..])?;
+        assert!(matches!(
+            reader.next_event(),
+            Err(crate::Error::Dump(DumpError::Truncated))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_crc_mismatch() -> crate::Result<()> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(DUMP_MAGIC);
+        bytes.write_u16::<BigEndian>(DUMP_FORMAT_VERSION)?;
+
+        Marker::PartitionStart {
+            name: "default".into(),
+            block_size: 4_096,
+            level_count: 7,
+            level_ratio: 8,
+        }
+        .serialize(&mut bytes)?;
+
+        Marker::Item {
+            key: vec![1].into(),
+            value: vec![2].into(),
+        }
+        .serialize(&mut bytes)?;
+
+        Marker::PartitionEnd {
+            item_count: 1,
+            crc: 0,
+        }
+        .serialize(&mut bytes)?;
+
+        let mut reader = Reader::new(&bytes[..])?;
+        assert!(matches!(
+            reader.next_event()?,
+            Some(Event::PartitionStart { .. })
+        ));
+        assert!(matches!(reader.next_event()?, Some(Event::Item { .. })));
+        assert!(matches!(
+            reader.next_event(),
+            Err(crate::Error::Dump(DumpError::CrcCheck))
+        ));
+
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
-use crate::{journal::shard::RecoveryError as JournalRecoveryError, version::Version};
-use lsm_tree::{DeserializeError, SerializeError};
+use crate::{
+    dump::DumpError, journal::shard::RecoveryError as JournalRecoveryError,
+    replication::ReplicationError, version::Version,
+};
+use lsm_tree::{DeserializeError, SerializeError, UserValue};
 
 /// Errors that may occur in the storage engine
 #[derive(Debug)]
@@ -32,6 +35,32 @@ pub enum Error {
 
     /// Partition is deleted.
     PartitionDeleted,
+
+    /// A partition with the given name already exists.
+    PartitionExists,
+
+    /// Invalid or corrupted keyspace manifest
+    InvalidManifest,
+
+    /// Invalid or corrupted dump
+    Dump(DumpError),
+
+    /// Point-in-time recovery is not possible, because disk segments
+    /// already contain data at or after the requested instant
+    ///
+    /// Contains the highest sequence number found in disk segments.
+    RecoveryTargetUnreachable(crate::Instant),
+
+    /// Invalid or corrupted replication stream
+    Replication(ReplicationError),
+
+    /// A data block did not match its checksum
+    ChecksumMismatch,
+
+    /// A precondition of a conditional write did not hold
+    ///
+    /// Contains the current value of the key whose precondition failed.
+    PreconditionFailed(Option<UserValue>),
 }
 
 impl std::fmt::Display for Error {
@@ -4,9 +4,16 @@ pub const JOURNALS_FOLDER: &str = "journals";
 pub const SEGMENTS_FOLDER: &str = "segments";
 pub const PARTITIONS_FOLDER: &str = "partitions";
 pub const FJALL_MARKER: &str = "version";
+pub const SEQNO_MARKER: &str = "seqno";
+pub const KEYSPACE_MANIFEST: &str = "manifest";
+pub const LSM_MARKER: &str = "version";
 pub const PARTITION_DELETED_MARKER: &str = ".deleted";
+pub const PARTITION_UNJOURNALED_MARKER: &str = ".unjournaled";
 
-pub const FLUSH_PARTITIONS_LIST: &str = ".partitions";
+pub const FLUSH_PARTITIONS_LIST: &str = ".partition_ids";
+pub const LEGACY_FLUSH_PARTITIONS_LIST: &str = ".partitions";
+pub const JOURNAL_MANIFEST: &str = ".manifest";
+pub const LEGACY_JOURNAL_MANIFEST: &str = ".legacy_manifest";
 pub const FLUSH_MARKER: &str = ".flush";
 
 #[cfg(not(target_os = "windows"))]
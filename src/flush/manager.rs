@@ -1,9 +1,9 @@
 use super::queue::FlushQueue;
-use crate::{batch::PartitionKey, PartitionHandle};
-use lsm_tree::{MemTable, SegmentId};
+use crate::{manifest::PartitionId, PartitionHandle};
+use lsm_tree::{MemTable, SegmentId, Tree as LsmTree};
 use std::{
     collections::{HashMap, HashSet},
-    sync::Arc,
+    sync::{Arc, Condvar, Mutex},
 };
 
 pub struct Task {
@@ -15,11 +15,48 @@ pub struct Task {
 
     /// Partition
     pub(crate) partition: PartitionHandle,
+
+    /// Tree the memtable belongs to
+    ///
+    /// Once partitions are swapped, the partition handle refers to another tree.
+    pub(crate) tree: LsmTree,
 }
 
 impl std::fmt::Debug for Task {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "FlushTask {}:{}", self.partition.name, self.id)
+        write!(f, "FlushTask {:?}:{}", self.partition.name(), self.id)
+    }
+}
+
+/// Wakes up threads that wait for the flush worker
+///
+/// Counts the flush rounds that have completed, so a waiter cannot
+/// miss a round that completes between checking its condition and waiting.
+#[derive(Clone, Default)]
+pub struct FlushSignal(Arc<(Mutex<u64>, Condvar)>);
+
+impl FlushSignal {
+    /// Returns the amount of completed flush rounds
+    pub fn rounds(&self) -> u64 {
+        *self.0 .0.lock().expect("lock is poisoned")
+    }
+
+    /// Marks a flush round as completed, waking up all waiters
+    pub fn notify(&self) {
+        let (rounds, signal) = &*self.0;
+        *rounds.lock().expect("lock is poisoned") += 1;
+        signal.notify_all();
+    }
+
+    /// Blocks until another flush round has completed after the given one
+    pub fn wait(&self, round: u64) {
+        let (rounds, signal) = &*self.0;
+
+        let _rounds = signal
+            .wait_while(rounds.lock().expect("lock is poisoned"), |rounds| {
+                *rounds == round
+            })
+            .expect("lock is poisoned");
     }
 }
 
@@ -29,23 +66,35 @@ impl std::fmt::Debug for Task {
 /// containing some flush tasks.
 ///
 /// Each flush task references a sealed memtable and the given partition.
+///
+/// Queues are keyed by partition ID, so renaming a partition does not affect them.
 #[derive(Default)]
 #[allow(clippy::module_name_repetitions)]
 pub struct FlushManager {
-    pub(crate) queues: HashMap<PartitionKey, FlushQueue>,
+    pub(crate) queues: HashMap<PartitionId, FlushQueue>,
+
+    /// Notified every time flushed tasks are dequeued, or a flush round failed
+    pub(crate) signal: FlushSignal,
 }
 
 impl FlushManager {
-    /// Gets the names of partitions that have queued tasks
-    pub(crate) fn get_partitions_with_tasks(&self) -> HashSet<PartitionKey> {
+    /// Gets the IDs of partitions that have queued tasks
+    pub(crate) fn get_partitions_with_tasks(&self) -> HashSet<PartitionId> {
         self.queues
             .iter()
             .filter(|(_, v)| !v.is_empty())
             .map(|(k, _)| k)
-            .cloned()
+            .copied()
             .collect()
     }
 
+    /// Returns `true` if the partition has queued tasks
+    pub(crate) fn has_tasks(&self, partition_id: PartitionId) -> bool {
+        self.queues
+            .get(&partition_id)
+            .is_some_and(|queue| !queue.is_empty())
+    }
+
     /// Returns the amount of tasks queued
     pub(crate) fn queued_size(&self) -> u64 {
         self.queues.values().map(FlushQueue::size).sum::<u64>()
@@ -65,25 +114,25 @@ impl FlushManager {
         self.len() == 0
     }
 
-    pub(crate) fn remove_partition(&mut self, name: &str) {
-        self.queues.remove(name);
+    pub(crate) fn remove_partition(&mut self, partition_id: PartitionId) {
+        self.queues.remove(&partition_id);
     }
 
-    pub(crate) fn enqueue_task(&mut self, partition_name: PartitionKey, task: Task) {
+    pub(crate) fn enqueue_task(&mut self, partition_id: PartitionId, task: Task) {
         log::debug!(
-            "Enqueuing {partition_name}:{} for flushing ({} B)",
+            "Enqueuing {partition_id}:{} for flushing ({} B)",
             task.id,
             task.sealed_memtable.size()
         );
 
         self.queues
-            .entry(partition_name)
+            .entry(partition_id)
             .or_default()
             .enqueue(Arc::new(task));
     }
 
     /// Returns a list of tasks per partition.
-    pub(crate) fn collect_tasks(&mut self, limit: usize) -> HashMap<PartitionKey, Vec<Arc<Task>>> {
+    pub(crate) fn collect_tasks(&self, limit: usize) -> HashMap<PartitionId, Vec<Arc<Task>>> {
         let mut collected: HashMap<_, Vec<_>> = HashMap::default();
         let mut cnt = 0;
 
@@ -94,14 +143,14 @@ impl FlushManager {
         // we will never cover up a lower seqno of some other segment.
         // For this to work, all tasks need to be successful and atomically
         // applied (all-or-nothing).
-        'outer: for (partition_name, queue) in &self.queues {
+        'outer: for (partition_id, queue) in &self.queues {
             for item in queue.iter() {
                 if cnt == limit {
                     break 'outer;
                 }
 
                 collected
-                    .entry(partition_name.clone())
+                    .entry(*partition_id)
                     .or_default()
                     .push(item.clone());
 
@@ -112,7 +161,7 @@ impl FlushManager {
         collected
     }
 
-    pub(crate) fn dequeue_tasks(&mut self, partition_name: PartitionKey, cnt: usize) {
-        self.queues.entry(partition_name).or_default().dequeue(cnt);
+    pub(crate) fn dequeue_tasks(&mut self, partition_id: PartitionId, cnt: usize) {
+        self.queues.entry(partition_id).or_default().dequeue(cnt);
     }
 }
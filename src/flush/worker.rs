@@ -1,9 +1,10 @@
 use super::manager::{FlushManager, Task};
 use crate::{
-    batch::PartitionKey, compaction::manager::CompactionManager, file::SEGMENTS_FOLDER,
-    journal::manager::JournalManager, write_buffer_manager::WriteBufferManager, PartitionHandle,
+    compaction::manager::CompactionManager, file::SEGMENTS_FOLDER,
+    journal::manager::JournalManager, manifest::PartitionId,
+    write_buffer_manager::WriteBufferManager, PartitionHandle,
 };
-use lsm_tree::Segment;
+use lsm_tree::{Segment, Tree as LsmTree};
 use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
@@ -14,24 +15,26 @@ fn run_flush_worker(task: &Arc<Task>) -> crate::Result<Arc<Segment>> {
     use lsm_tree::flush::Options;
 
     let segment = lsm_tree::flush::flush_to_segment(Options {
-        tree_id: task.partition.tree.id,
+        tree_id: task.tree.id,
 
         // IMPORTANT: Segment has to get the task ID
         // otherwise segment ID and memtable ID will not line up
         segment_id: task.id,
 
         memtable: task.sealed_memtable.clone(),
-        folder: task.partition.tree.config.path.join(SEGMENTS_FOLDER),
-        block_size: task.partition.tree.config.inner.block_size,
-        block_cache: task.partition.tree.config.block_cache.clone(),
-        descriptor_table: task.partition.tree.config.descriptor_table.clone(),
+        folder: task.tree.config.path.join(SEGMENTS_FOLDER),
+        block_size: task.tree.config.inner.block_size,
+        block_cache: task.tree.config.block_cache.clone(),
+        descriptor_table: task.tree.config.descriptor_table.clone(),
     })?;
 
     Ok(Arc::new(segment))
 }
 
 struct MultiFlushResultItem {
+    partition_id: PartitionId,
     partition: PartitionHandle,
+    tree: LsmTree,
     created_segments: Vec<Arc<Segment>>,
 
     /// Size sum of sealed memtables that have been flushed
@@ -43,7 +46,7 @@ type MultiFlushResults = Vec<crate::Result<MultiFlushResultItem>>;
 /// Distributes tasks of multiple partitions over multiple worker threads.
 ///
 /// Each thread is responsible for the tasks of one partition.
-fn run_multi_flush(partitioned_tasks: &HashMap<PartitionKey, Vec<Arc<Task>>>) -> MultiFlushResults {
+fn run_multi_flush(partitioned_tasks: &HashMap<PartitionId, Vec<Arc<Task>>>) -> MultiFlushResults {
     log::debug!(
         "flush worker: spawning {} worker threads",
         partitioned_tasks.len()
@@ -53,21 +56,19 @@ fn run_multi_flush(partitioned_tasks: &HashMap<PartitionKey, Vec<Arc<Task>>>) ->
     #[allow(clippy::needless_collect)]
     let threads = partitioned_tasks
         .iter()
-        .map(|(partition_name, tasks)| {
-            let partition_name = partition_name.clone();
+        .map(|(partition_id, tasks)| {
+            let partition_id = *partition_id;
             let tasks = tasks.clone();
 
             std::thread::spawn(move || {
                 log::trace!(
-                    "flush thread: flushing {} memtables for partition {partition_name:?}",
+                    "flush thread: flushing {} memtables for partition {partition_id}",
                     tasks.len()
                 );
 
-                let partition = tasks
-                    .first()
-                    .expect("should always have at least one task")
-                    .partition
-                    .clone();
+                let first_task = tasks.first().expect("should always have at least one task");
+                let partition = first_task.partition.clone();
+                let tree = first_task.tree.clone();
 
                 let memtables_size: u64 = tasks
                     .iter()
@@ -87,7 +88,9 @@ fn run_multi_flush(partitioned_tasks: &HashMap<PartitionKey, Vec<Arc<Task>>>) ->
                     .collect::<crate::Result<Vec<_>>>()?;
 
                 Ok(MultiFlushResultItem {
+                    partition_id,
                     partition,
+                    tree,
                     created_segments,
                     size: memtables_size,
                 })
@@ -101,6 +104,45 @@ fn run_multi_flush(partitioned_tasks: &HashMap<PartitionKey, Vec<Arc<Task>>>) ->
         .collect::<Vec<_>>()
 }
 
+/// Flushes all sealed memtables of a partition, blocking the current thread
+///
+/// Used to flush non-journaled partitions on shutdown, because
+/// their memtables cannot be recovered from the journal.
+pub fn flush_partition_blocking(
+    flush_manager: &Arc<RwLock<FlushManager>>,
+    partition: &PartitionHandle,
+) -> crate::Result<()> {
+    let mut flush_manager = flush_manager.write().expect("lock is poisoned");
+
+    let tasks = flush_manager
+        .queues
+        .get(&partition.id())
+        .map(|queue| queue.iter().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    if tasks.is_empty() {
+        return Ok(());
+    }
+
+    log::debug!(
+        "flush: flushing {} memtables of partition {:?}",
+        tasks.len(),
+        partition.name()
+    );
+
+    let created_segments = tasks
+        .iter()
+        .map(run_flush_worker)
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    let first_task = tasks.first().expect("should always have at least one task");
+    first_task.tree.register_segments(&created_segments)?;
+    flush_manager.dequeue_tasks(partition.id(), created_segments.len());
+    flush_manager.signal.notify();
+
+    Ok(())
+}
+
 /// Runs flush logic.
 #[allow(clippy::too_many_lines)]
 pub fn run(
@@ -111,8 +153,9 @@ pub fn run(
     parallelism: usize,
 ) {
     log::debug!("flush worker: write locking flush manager");
-    let mut fm = flush_manager.write().expect("lock is poisoned");
+    let fm = flush_manager.write().expect("lock is poisoned");
     let partitioned_tasks = fm.collect_tasks(parallelism);
+    let signal = fm.signal.clone();
     drop(fm);
 
     let task_count = partitioned_tasks.iter().map(|x| x.1.len()).sum::<usize>();
@@ -125,18 +168,20 @@ pub fn run(
     for result in run_multi_flush(&partitioned_tasks) {
         match result {
             Ok(MultiFlushResultItem {
+                partition_id,
                 partition,
+                tree,
                 created_segments,
                 size: memtables_size,
             }) => {
                 // IMPORTANT: Flushed segments need to be applied *atomically* into the tree
                 // otherwise we could cover up an unwritten journal, which will result in data loss
-                if let Err(e) = partition.tree.register_segments(&created_segments) {
+                if let Err(e) = tree.register_segments(&created_segments) {
                     log::error!("Failed to register segments: {e:?}");
                 } else {
                     log::debug!("flush worker: write locking flush manager to submit results");
                     let mut flush_manager = flush_manager.write().expect("lock is poisoned");
-                    flush_manager.dequeue_tasks(partition.name.clone(), created_segments.len());
+                    flush_manager.dequeue_tasks(partition_id, created_segments.len());
 
                     write_buffer_manager.free(memtables_size);
                     compaction_manager.notify(partition);
@@ -157,5 +202,9 @@ pub fn run(
         log::error!("journal GC failed: {e:?}");
     };
 
+    // NOTE: Also notify if a flush failed, so waiters can check
+    // if the keyspace is poisoned, or retry
+    signal.notify();
+
     log::debug!("flush worker: fully done");
 }
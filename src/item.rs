@@ -0,0 +1,70 @@
+use crate::Instant;
+use lsm_tree::{UserValue, Value};
+
+/// A retained version of an item, as returned by [`crate::PartitionHandle::get_versions`]
+///
+/// # Examples
+///
+/// ```
+/// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+/// #
+/// # let folder = tempfile::tempdir()?;
+/// # let keyspace = Config::new(folder).open()?;
+/// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+/// partition.insert("a", "abc")?;
+/// partition.remove("a")?;
+///
+/// let versions = partition.get_versions("a", 0)?;
+/// assert_eq!(2, versions.len());
+///
+/// // Newest version first
+/// assert!(versions[0].is_tombstone());
+/// assert_eq!(b"abc", &**versions[1].value());
+/// assert!(versions[0].seqno() > versions[1].seqno());
+/// #
+/// # Ok::<(), fjall::Error>(())
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValueVersion {
+    value: UserValue,
+    seqno: Instant,
+    tombstone: bool,
+}
+
+impl ValueVersion {
+    /// Returns the value of the version.
+    ///
+    /// The value of a tombstone is empty.
+    #[must_use]
+    pub fn value(&self) -> &UserValue {
+        &self.value
+    }
+
+    /// Consumes the version, returning its value.
+    #[must_use]
+    pub fn into_value(self) -> UserValue {
+        self.value
+    }
+
+    /// Returns the instant the version was written at.
+    #[must_use]
+    pub fn seqno(&self) -> Instant {
+        self.seqno
+    }
+
+    /// Returns `true` if the version marks the item as deleted.
+    #[must_use]
+    pub fn is_tombstone(&self) -> bool {
+        self.tombstone
+    }
+}
+
+impl From<Value> for ValueVersion {
+    fn from(item: Value) -> Self {
+        Self {
+            tombstone: item.is_tombstone(),
+            value: item.value,
+            seqno: item.seqno,
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use super::{writer::PersistMode, Journal};
+use lsm_tree::{SeqNo, SequenceNumberCounter};
+use std::{
+    fs::File,
+    sync::{atomic::AtomicBool, Arc, Condvar, Mutex},
+};
+
+struct State {
+    /// File handle of the shard's active journal file
+    file: File,
+
+    /// Highest seqno that was written into the OS buffers of the shard file
+    written: Option<SeqNo>,
+
+    /// Every seqno below this watermark is synced using `fdatasync` (or better)
+    synced_data: SeqNo,
+
+    /// Every seqno below this watermark is synced using `fsync`
+    synced_all: SeqNo,
+
+    /// `true` if a leader is currently syncing the shard file
+    is_syncing: bool,
+}
+
+impl State {
+    fn is_synced(&self, seqno: SeqNo, mode: PersistMode) -> bool {
+        match mode {
+            PersistMode::SyncAll => self.synced_all > seqno,
+            PersistMode::SyncData => self.synced_data > seqno,
+            PersistMode::Buffer => true,
+        }
+    }
+
+    fn mark_synced(&mut self, watermark: SeqNo, mode: PersistMode) {
+        match mode {
+            PersistMode::SyncAll => {
+                self.synced_all = self.synced_all.max(watermark);
+                self.synced_data = self.synced_data.max(watermark);
+            }
+            PersistMode::SyncData => {
+                self.synced_data = self.synced_data.max(watermark);
+            }
+            PersistMode::Buffer => {}
+        }
+    }
+}
+
+/// Coalesces concurrent sync requests of writers into a single fsync per journal shard
+///
+/// The first writer that needs its write to be durable becomes the leader,
+/// and syncs the shard file for every write that has reached the OS buffers so far.
+/// Writers arriving in the meantime wait for the leader, and are done if
+/// their write was covered, otherwise one of them becomes the next leader.
+pub struct GroupCommit {
+    state: Mutex<State>,
+    signal: Condvar,
+}
+
+impl GroupCommit {
+    pub fn new(file: File) -> Self {
+        Self {
+            state: Mutex::new(State {
+                file,
+                written: None,
+                synced_data: 0,
+                synced_all: 0,
+                is_syncing: false,
+            }),
+            signal: Condvar::new(),
+        }
+    }
+
+    /// Registers a write that has been flushed into the OS buffers of the shard file
+    ///
+    /// Needs to be called while holding the shard lock.
+    pub fn mark_written(&self, seqno: SeqNo) {
+        let mut state = self.state.lock().expect("lock is poisoned");
+        state.written = Some(state.written.map_or(seqno, |prev| prev.max(seqno)));
+    }
+
+    /// Marks all writes up to the given seqno as synced
+    ///
+    /// Needs to be called while holding the shard lock, after the shard was synced.
+    pub fn mark_synced(&self, seqno: SeqNo, mode: PersistMode) {
+        let mut state = self.state.lock().expect("lock is poisoned");
+        state.mark_synced(seqno + 1, mode);
+        drop(state);
+
+        self.signal.notify_all();
+    }
+
+    /// Returns the watermark below which every seqno of the shard is synced using `fdatasync` (or better)
+    pub fn synced_data(&self) -> SeqNo {
+        self.state.lock().expect("lock is poisoned").synced_data
+    }
+
+    /// Replaces the shard file after a journal rotation
+    ///
+    /// Needs to be called while holding the shard lock, after the old file was synced.
+    pub fn set_file(&self, file: File) {
+        let mut state = self.state.lock().expect("lock is poisoned");
+        state.file = file;
+        drop(state);
+
+        self.signal.notify_all();
+    }
+
+    /// Blocks until the write with the given seqno is synced to disk
+    ///
+    /// The write needs to be registered using [`GroupCommit::mark_written`] before.
+    ///
+    /// Returns `true` if the calling thread synced the shard file as the leader.
+    pub fn sync(&self, seqno: SeqNo, mode: PersistMode) -> std::io::Result<bool> {
+        let mut state = self.state.lock().expect("lock is poisoned");
+        let mut is_leader = false;
+
+        loop {
+            if state.is_synced(seqno, mode) {
+                return Ok(is_leader);
+            }
+
+            if state.is_syncing {
+                state = self.signal.wait(state).expect("lock is poisoned");
+                continue;
+            }
+
+            // NOTE: Become the leader, and sync every write that is in the OS buffers
+            let Some(watermark) = state.written else {
+                // NOTE: Unreachable if the write was registered
+                return Ok(is_leader);
+            };
+
+            let file = state.file.try_clone()?;
+            state.is_syncing = true;
+            drop(state);
+
+            log::trace!("journal: group commit syncing up to seqno={watermark}");
+
+            let result = match mode {
+                PersistMode::SyncAll => file.sync_all(),
+                PersistMode::SyncData => file.sync_data(),
+                PersistMode::Buffer => Ok(()),
+            };
+
+            state = self.state.lock().expect("lock is poisoned");
+            state.is_syncing = false;
+
+            if result.is_ok() {
+                state.mark_synced(watermark + 1, mode);
+                is_leader = true;
+            }
+
+            self.signal.notify_all();
+
+            result?;
+        }
+    }
+}
+
+/// A write that needs to be synced before it is acknowledged
+pub struct PendingSync {
+    group_commit: Arc<GroupCommit>,
+    seqno: SeqNo,
+    mode: PersistMode,
+}
+
+impl PendingSync {
+    pub fn new(group_commit: Arc<GroupCommit>, seqno: SeqNo, mode: PersistMode) -> Self {
+        Self {
+            group_commit,
+            seqno,
+            mode,
+        }
+    }
+
+    /// Blocks until the write is synced to disk
+    ///
+    /// If the calling thread synced the shard as the leader, it advances
+    /// the journal's durable watermark, see [`Journal::advance_durable_watermark`].
+    ///
+    /// A failed sync poisons the keyspace, same as a failed [`crate::Keyspace::persist`].
+    pub fn wait(
+        self,
+        journal: &Journal,
+        seqno: &SequenceNumberCounter,
+        is_poisoned: &AtomicBool,
+    ) -> crate::Result<()> {
+        match self.group_commit.sync(self.seqno, self.mode) {
+            Ok(true) => {
+                journal.advance_durable_watermark(seqno.get());
+                Ok(())
+            }
+            Ok(false) => Ok(()),
+            Err(e) => {
+                is_poisoned.store(true, std::sync::atomic::Ordering::Release);
+                journal.wake_durable_waiters();
+                log::error!(
+                    "flush failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
+                );
+                Err(crate::Error::Poisoned)
+            }
+        }
+    }
+}
@@ -1,12 +1,18 @@
 use super::shard::JournalShard;
 use crate::{
-    batch::PartitionKey,
-    file::{fsync_directory, FLUSH_MARKER, FLUSH_PARTITIONS_LIST},
+    file::{fsync_directory, FLUSH_MARKER, FLUSH_PARTITIONS_LIST, JOURNAL_MANIFEST},
     journal::Journal,
+    manifest::PartitionId,
+    sharded::ShardGuard,
     PartitionHandle,
 };
 use lsm_tree::SeqNo;
-use std::{collections::HashMap, fs::File, io::Write, path::PathBuf, sync::RwLockWriteGuard};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 pub struct PartitionSeqNo {
     pub(crate) partition: PartitionHandle,
@@ -22,7 +28,7 @@ impl std::fmt::Debug for PartitionSeqNo {
 pub struct Item {
     pub(crate) path: PathBuf,
     pub(crate) size_in_bytes: u64,
-    pub(crate) partition_seqnos: HashMap<PartitionKey, PartitionSeqNo>,
+    pub(crate) partition_seqnos: HashMap<PartitionId, PartitionSeqNo>,
 }
 
 impl std::fmt::Debug for Item {
@@ -35,6 +41,52 @@ impl std::fmt::Debug for Item {
     }
 }
 
+/// Moves a fully flushed journal into the journal archive
+fn archive_journal(journal_path: &Path, archive_path: &Path) -> crate::Result<()> {
+    std::fs::create_dir_all(archive_path)?;
+
+    let journal_name = journal_path.file_name().expect("should have filename");
+    let target_path = archive_path.join(journal_name);
+
+    // NOTE: Renaming fails if the archive is on another file system, so fall back to copying
+    if let Err(e) = std::fs::rename(journal_path, &target_path) {
+        log::debug!("Could not rename journal into archive, copying instead: {e}");
+
+        fs_extra::dir::copy(
+            journal_path,
+            archive_path,
+            &fs_extra::dir::CopyOptions::new(),
+        )
+        .map_err(|e| std::io::Error::other(format!("{:?}", e.kind)))?;
+
+        for dirent in std::fs::read_dir(&target_path)? {
+            File::open(dirent?.path())?.sync_all()?;
+        }
+        fsync_directory(&target_path)?;
+
+        std::fs::remove_dir_all(journal_path)?;
+    }
+
+    // IMPORTANT: fsync folder on Unix
+    fsync_directory(archive_path)?;
+
+    Ok(())
+}
+
+/// Copies the keyspace manifest into a journal
+fn snapshot_manifest(manifest_path: &Path, target_path: &Path) -> crate::Result<()> {
+    // NOTE: The manifest is never modified in place (only replaced),
+    // so a hard link is a consistent snapshot
+    if let Err(e) = std::fs::hard_link(manifest_path, target_path) {
+        log::debug!("Could not link keyspace manifest into journal, copying instead: {e}");
+
+        std::fs::copy(manifest_path, target_path)?;
+        File::open(target_path)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
 // TODO: accessing journal manager shouldn't take RwLock... but changing its internals should
 
 /// The [`JournalManager`] keeps track of sealed journals that are being flushed.
@@ -45,16 +97,33 @@ pub struct JournalManager {
     active_path: PathBuf,
     items: Vec<Item>,
 
-    // TODO: should be taking into account active journal, which is preallocated...
+    /// Path of the keyspace manifest, which is copied into sealed journals
+    manifest_path: PathBuf,
+
+    /// If set, fully flushed journals are moved here instead of being deleted
+    archive_path: Option<PathBuf>,
+
+    /// Disk space of sealed journals
     disk_space_in_bytes: u64,
+
+    /// Preallocated disk space of the active journal
+    active_disk_space_in_bytes: u64,
 }
 
 impl JournalManager {
-    pub(crate) fn new<P: Into<PathBuf>>(path: P) -> Self {
+    pub(crate) fn new<P: Into<PathBuf>>(
+        path: P,
+        manifest_path: PathBuf,
+        archive_path: Option<PathBuf>,
+        active_disk_space_in_bytes: u64,
+    ) -> Self {
         Self {
             active_path: path.into(),
             items: Vec::with_capacity(10),
+            manifest_path,
+            archive_path,
             disk_space_in_bytes: 0,
+            active_disk_space_in_bytes,
         }
     }
 
@@ -63,6 +132,32 @@ impl JournalManager {
         self.items.push(item);
     }
 
+    /// Exchanges all references of two partition handles
+    ///
+    /// Used after the slots of the handles have been swapped,
+    /// so each journal entry refers to the handle that holds its partition ID.
+    pub(crate) fn swap_partitions(&mut self, a: &PartitionHandle, b: &PartitionHandle) {
+        for item in &mut self.items {
+            for partition_seqno in item.partition_seqnos.values_mut() {
+                if partition_seqno.partition == *a {
+                    partition_seqno.partition = b.clone();
+                } else if partition_seqno.partition == *b {
+                    partition_seqno.partition = a.clone();
+                }
+            }
+        }
+    }
+
+    /// Removes all references of a partition ID
+    ///
+    /// Used when the data of the partition ID has been dropped,
+    /// so it does not need to be flushed before journals can be evicted.
+    pub(crate) fn remove_partition(&mut self, partition_id: PartitionId) {
+        for item in &mut self.items {
+            item.partition_seqnos.remove(&partition_id);
+        }
+    }
+
     /// Returns the amount of journals
     pub(crate) fn journal_count(&self) -> usize {
         // NOTE: + 1 = active journal
@@ -75,7 +170,18 @@ impl JournalManager {
     }
 
     /// Returns the amount of bytes used on disk by journals
+    ///
+    /// The active journal is accounted for by its preallocated size.
     pub(crate) fn disk_space_used(&self) -> u64 {
+        self.disk_space_in_bytes
+            .saturating_add(self.active_disk_space_in_bytes)
+    }
+
+    /// Returns the amount of bytes used on disk by sealed journals
+    ///
+    /// Only sealed journals can be freed up by flushing, so this is
+    /// what the maximum journaling size is checked against.
+    pub(crate) fn sealed_disk_space_used(&self) -> u64 {
         self.disk_space_in_bytes
     }
 
@@ -87,7 +193,7 @@ impl JournalManager {
 
         if let Some(item) = self.items.first() {
             for item in item.partition_seqnos.values() {
-                let Some(partition_seqno) = item.partition.tree.get_segment_lsn() else {
+                let Some(partition_seqno) = item.partition.tree().get_segment_lsn() else {
                     items.push(item.partition.clone());
                     continue;
                 };
@@ -117,7 +223,7 @@ impl JournalManager {
                     .is_deleted
                     .load(std::sync::atomic::Ordering::Acquire)
                 {
-                    let Some(partition_seqno) = item.partition.tree.get_segment_lsn() else {
+                    let Some(partition_seqno) = item.partition.tree().get_segment_lsn() else {
                         continue 'outer;
                     };
 
@@ -136,8 +242,13 @@ impl JournalManager {
             // [2] Checking the seqno is safe because the queues inside the flush manager are FIFO.
             //
             // IMPORTANT: On recovery, the journals need to be flushed from oldest to newest.
-            log::trace!("Removing fully flushed journal at {:?}", item.path);
-            std::fs::remove_dir_all(&item.path)?;
+            if let Some(archive_path) = &self.archive_path {
+                log::trace!("Archiving fully flushed journal at {}", item.path.display());
+                archive_journal(&item.path, archive_path)?;
+            } else {
+                log::trace!("Removing fully flushed journal at {}", item.path.display());
+                std::fs::remove_dir_all(&item.path)?;
+            }
 
             self.disk_space_in_bytes = self.disk_space_in_bytes.saturating_sub(item.size_in_bytes);
             self.items.remove(idx);
@@ -148,8 +259,8 @@ impl JournalManager {
 
     pub(crate) fn rotate_journal(
         &mut self,
-        journal_lock: &mut [RwLockWriteGuard<'_, JournalShard>],
-        seqnos: HashMap<PartitionKey, PartitionSeqNo>,
+        journal_lock: &mut [ShardGuard<'_, JournalShard>],
+        seqnos: HashMap<PartitionId, PartitionSeqNo>,
     ) -> crate::Result<()> {
         let old_journal_path = self.active_path.clone();
 
@@ -157,11 +268,19 @@ impl JournalManager {
 
         let mut file = File::create(old_journal_path.join(FLUSH_PARTITIONS_LIST))?;
 
-        for (name, item) in &seqnos {
-            writeln!(file, "{name}:{}", item.lsn)?;
+        for (partition_id, item) in &seqnos {
+            writeln!(file, "{partition_id}:{}", item.lsn)?;
         }
         file.sync_all()?;
 
+        // NOTE: The journal references partitions by ID, so keep the names of all
+        // partitions that exist at this point, in case the journal is replayed
+        // into another keyspace (point-in-time recovery of a backup)
+        snapshot_manifest(
+            &self.manifest_path,
+            &old_journal_path.join(JOURNAL_MANIFEST),
+        )?;
+
         let marker = File::create(old_journal_path.join(FLUSH_MARKER))?;
         marker.sync_all()?;
 
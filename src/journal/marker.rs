@@ -1,4 +1,4 @@
-use crate::batch::PartitionKey;
+use crate::{batch::PartitionKey, manifest::PartitionId};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use lsm_tree::{
     serde::{Deserializable, Serializable},
@@ -19,16 +19,49 @@ const TRAILER_MAGIC: &[u8] = &[b'F', b'J', b'L', b'L', b'T', b'R', b'L', b'1'];
 /// - If a start marker is detected, while inside a batch, the batch is broken.
 #[derive(Debug, Eq, PartialEq)]
 pub enum Marker {
+    /// Start of a batch
     Start {
+        /// Amount of items that follow
         item_count: u32,
+
+        /// Sequence number of the batch
         seqno: SeqNo,
     },
+
+    /// A single item inside a batch, referencing its partition by name
+    ///
+    /// Written by journals created before partitions had IDs, and by replication streams,
+    /// because partition IDs are local to a keyspace.
     Item {
+        /// Partition the item belongs to
         partition: PartitionKey,
+
+        /// User key
         key: UserKey,
+
+        /// User value
         value: UserValue,
+
+        /// Value or tombstone
         value_type: ValueType,
     },
+
+    /// A single item inside a batch, referencing its partition by ID
+    ItemById {
+        /// ID of the partition the item belongs to
+        partition_id: PartitionId,
+
+        /// User key
+        key: UserKey,
+
+        /// User value
+        value: UserValue,
+
+        /// Value or tombstone
+        value_type: ValueType,
+    },
+
+    /// End of a batch, containing the CRC of the batch's items
     End(u32),
 }
 
@@ -36,18 +69,20 @@ pub enum Tag {
     Start = 0,
     Item = 1,
     End = 2,
+    ItemById = 3,
 }
 
 impl TryFrom<u8> for Tag {
     type Error = DeserializeError;
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
-        use Tag::{End, Item, Start};
+        use Tag::{End, Item, ItemById, Start};
 
         match value {
             0 => Ok(Start),
             1 => Ok(Item),
             2 => Ok(End),
+            3 => Ok(ItemById),
             _ => Err(DeserializeError::InvalidTag(("JournalMarkerTag", value))),
         }
     }
@@ -61,7 +96,7 @@ impl From<Tag> for u8 {
 
 impl Serializable for Marker {
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
-        use Marker::{End, Item, Start};
+        use Marker::{End, Item, ItemById, Start};
 
         match self {
             Start { item_count, seqno } => {
@@ -94,6 +129,27 @@ impl Serializable for Marker {
                 writer.write_u16::<BigEndian>(value.len() as u16)?;
                 writer.write_all(value)?;
             }
+            ItemById {
+                partition_id,
+                key,
+                value,
+                value_type,
+            } => {
+                writer.write_u8(Tag::ItemById.into())?;
+
+                writer.write_u8(u8::from(*value_type))?;
+                writer.write_u64::<BigEndian>(*partition_id)?;
+
+                // NOTE: Truncation is okay and actually needed
+                #[allow(clippy::cast_possible_truncation)]
+                writer.write_u16::<BigEndian>(key.len() as u16)?;
+                writer.write_all(key)?;
+
+                // NOTE: Truncation is okay and actually needed
+                #[allow(clippy::cast_possible_truncation)]
+                writer.write_u16::<BigEndian>(value.len() as u16)?;
+                writer.write_all(value)?;
+            }
             End(val) => {
                 writer.write_u8(Tag::End.into())?;
                 writer.write_u32::<BigEndian>(*val)?;
@@ -142,6 +198,27 @@ impl Deserializable for Marker {
                     value_type,
                 })
             }
+            Tag::ItemById => {
+                let value_type = reader.read_u8()?.into();
+                let partition_id = reader.read_u64::<BigEndian>()?;
+
+                // Read key
+                let key_len = reader.read_u16::<BigEndian>()?;
+                let mut key = vec![0; key_len.into()];
+                reader.read_exact(&mut key)?;
+
+                // Read value
+                let value_len = reader.read_u16::<BigEndian>()?;
+                let mut value = vec![0; value_len as usize];
+                reader.read_exact(&mut value)?;
+
+                Ok(Self::ItemById {
+                    partition_id,
+                    key: key.into(),
+                    value: value.into(),
+                    value_type,
+                })
+            }
             Tag::End => {
                 let crc = reader.read_u32::<BigEndian>()?;
 
@@ -186,6 +263,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_serialize_and_deserialize_item_by_id() -> crate::Result<()> {
+        let item = Marker::ItemById {
+            partition_id: 5,
+            key: vec![1, 2, 3].into(),
+            value: vec![4, 5].into(),
+            value_type: ValueType::Tombstone,
+        };
+
+        // Serialize
+        let mut serialized_data = Vec::new();
+        item.serialize(&mut serialized_data)?;
+
+        // Deserialize
+        let mut reader = &serialized_data[..];
+        let deserialized_item = Marker::deserialize(&mut reader)?;
+
+        assert_eq!(item, deserialized_item);
+
+        Ok(())
+    }
+
     #[test]
     fn test_invalid_deserialize() {
         let invalid_data = [Tag::Start as u8; 1]; // Should be followed by a u32
@@ -1,7 +1,8 @@
+pub mod group_commit;
 pub mod manager;
-mod marker;
+pub mod marker;
 pub mod partition_manifest;
-mod reader;
+pub mod reader;
 pub mod shard;
 pub mod writer;
 
@@ -9,67 +10,128 @@ use self::{
     shard::{JournalShard, RecoveryMode},
     writer::PersistMode,
 };
-use crate::{batch::PartitionKey, file::fsync_directory, sharded::Sharded};
-use lsm_tree::MemTable;
+use crate::{
+    file::fsync_directory,
+    manifest::PartitionId,
+    sharded::{ShardGuard, Sharded},
+};
+use lsm_tree::{MemTable, SeqNo};
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
-    sync::{RwLock, RwLockWriteGuard},
+    sync::{Condvar, Mutex, RwLock},
 };
 
-const SHARD_COUNT: u8 = 4;
+/// Default amount of journal shards
+pub const DEFAULT_SHARD_COUNT: u8 = 4;
 
 fn get_shard_path<P: AsRef<Path>>(base: P, idx: u8) -> PathBuf {
     base.as_ref().join(idx.to_string())
 }
 
+/// Lists the shard files of a journal, sorted by shard index
+///
+/// The shard count is not stored, because it may change between
+/// restarts, so every file named by a shard index is a shard.
+fn list_shards(path: &Path) -> crate::Result<Vec<PathBuf>> {
+    let mut shards = vec![];
+
+    for dirent in std::fs::read_dir(path)? {
+        let dirent = dirent?;
+
+        let Some(idx) = dirent
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<u8>().ok())
+        else {
+            // NOTE: Skip markers of sealed journals
+            continue;
+        };
+
+        shards.push((idx, dirent.path()));
+    }
+
+    shards.sort_by_key(|(idx, _)| *idx);
+
+    Ok(shards.into_iter().map(|(_, path)| path).collect())
+}
+
 pub struct Journal {
     pub path: PathBuf,
     pub shards: Sharded<JournalShard>,
+
+    /// Every seqno below this watermark is synced to disk
+    durable_watermark: Mutex<SeqNo>,
+    durable_signal: Condvar,
 }
 
 impl Journal {
     pub fn recover_memtables<P: AsRef<Path>>(
         path: P,
-        whitelist: Option<&[PartitionKey]>,
+        whitelist: Option<&[PartitionId]>,
+        resolve_name: &dyn Fn(&str) -> Option<PartitionId>,
+        recovery_mode: RecoveryMode,
+    ) -> crate::Result<HashMap<PartitionId, MemTable>> {
+        Self::recover_memtables_filtered(
+            path,
+            &|partition_id, _| {
+                whitelist.map_or(true, |whitelist| whitelist.contains(&partition_id))
+            },
+            resolve_name,
+            recovery_mode,
+        )
+    }
+
+    /// Recovers the items of a journal, only keeping items for which
+    /// the filter (given the partition ID & batch seqno) returns `true`
+    ///
+    /// Items written before partitions had IDs are mapped to an ID using `resolve_name`.
+    pub fn recover_memtables_filtered<P: AsRef<Path>>(
+        path: P,
+        filter: &dyn Fn(PartitionId, SeqNo) -> bool,
+        resolve_name: &dyn Fn(&str) -> Option<PartitionId>,
         recovery_mode: RecoveryMode,
-    ) -> crate::Result<HashMap<PartitionKey, MemTable>> {
+    ) -> crate::Result<HashMap<PartitionId, MemTable>> {
         let path = path.as_ref();
         let mut memtables = HashMap::new();
 
-        for idx in 0..SHARD_COUNT {
-            let shard_path = get_shard_path(path, idx);
-
-            if shard_path.exists() {
-                JournalShard::recover_and_repair(
-                    shard_path,
-                    &mut memtables,
-                    whitelist,
-                    recovery_mode,
-                )?;
-                log::trace!("Recovered journal shard");
-            } else {
-                log::trace!("Journal shard file does not exist (yet)");
-            }
+        for shard_path in list_shards(path)? {
+            JournalShard::recover_and_repair(
+                shard_path,
+                &mut memtables,
+                filter,
+                resolve_name,
+                recovery_mode,
+            )?;
+            log::trace!("Recovered journal shard");
         }
 
         Ok(memtables)
     }
 
+    /// Recovers the active journal
+    ///
+    /// All existing shard files are recovered, even if the journal was
+    /// written with a different shard count. Superfluous shard files are
+    /// not written to anymore, but are kept until the journal is evicted.
     pub fn recover<P: AsRef<Path>>(
         path: P,
+        resolve_name: &dyn Fn(&str) -> Option<PartitionId>,
         recovery_mode: RecoveryMode,
-    ) -> crate::Result<(Self, HashMap<PartitionKey, MemTable>)> {
+        shard_count: u8,
+        preallocated_bytes: u64,
+    ) -> crate::Result<(Self, HashMap<PartitionId, MemTable>)> {
         let path = path.as_ref();
         log::debug!("Recovering journal from {path:?}");
 
-        let memtables = Self::recover_memtables(path, None, recovery_mode)?;
+        let memtables = Self::recover_memtables(path, None, resolve_name, recovery_mode)?;
 
-        let shards = (0..SHARD_COUNT)
+        let shards = (0..shard_count)
             .map(|idx| {
-                Ok(RwLock::new(JournalShard::from_file(get_shard_path(
-                    path, idx,
-                ))?))
+                Ok(RwLock::new(JournalShard::from_file(
+                    get_shard_path(path, idx),
+                    preallocated_bytes,
+                )?))
             })
             .collect::<crate::Result<Vec<_>>>()?;
 
@@ -77,6 +139,8 @@ impl Journal {
             Self {
                 shards: Sharded::new(shards),
                 path: path.to_path_buf(),
+                durable_watermark: Mutex::default(),
+                durable_signal: Condvar::new(),
             },
             memtables,
         ))
@@ -84,7 +148,7 @@ impl Journal {
 
     pub fn rotate<P: AsRef<Path>>(
         path: P,
-        shards: &mut [RwLockWriteGuard<'_, JournalShard>],
+        shards: &mut [ShardGuard<'_, JournalShard>],
     ) -> crate::Result<()> {
         let path = path.as_ref();
 
@@ -102,16 +166,21 @@ impl Journal {
         Ok(())
     }
 
-    pub fn create_new<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+    pub fn create_new<P: AsRef<Path>>(
+        path: P,
+        shard_count: u8,
+        preallocated_bytes: u64,
+    ) -> crate::Result<Self> {
         let path = path.as_ref();
 
         std::fs::create_dir_all(path)?;
 
-        let shards = (0..SHARD_COUNT)
+        let shards = (0..shard_count)
             .map(|idx| {
-                Ok(RwLock::new(JournalShard::create_new(get_shard_path(
-                    path, idx,
-                ))?))
+                Ok(RwLock::new(JournalShard::create_new(
+                    get_shard_path(path, idx),
+                    preallocated_bytes,
+                )?))
             })
             .collect::<crate::Result<Vec<_>>>()?;
 
@@ -121,24 +190,110 @@ impl Journal {
         Ok(Self {
             shards: Sharded::new(shards),
             path: path.to_path_buf(),
+            durable_watermark: Mutex::default(),
+            durable_signal: Condvar::new(),
         })
     }
 
-    pub(crate) fn get_writer(&self) -> RwLockWriteGuard<'_, JournalShard> {
+    pub(crate) fn get_writer(&self) -> ShardGuard<'_, JournalShard> {
         let mut shard = self.shards.write_one();
         shard.should_sync = true;
         shard
     }
 
+    /// Remembers that a partition has data in the active journal, up to the given seqno
+    ///
+    /// Needed for data that ends up in the active journal without being written
+    /// through a journal shard, e.g. memtables recovered from the active journal.
+    pub(crate) fn mark_dirty(&self, partition_id: PartitionId, seqno: SeqNo) {
+        self.shards.write_one().mark_dirty(partition_id, seqno);
+    }
+
     pub fn flush(&self, mode: PersistMode) -> crate::Result<()> {
+        let mut last_seqno = None;
+
         for mut shard in self.shards.full_lock().expect("lock is poisoned") {
             if shard.should_sync {
-                shard.writer.flush(mode)?;
-                shard.should_sync = false;
+                shard.flush(mode)?;
+
+                // IMPORTANT: Buffering does not make the shard durable,
+                // so it still needs to be synced by the next flush
+                if mode != PersistMode::Buffer {
+                    shard.should_sync = false;
+                }
+            }
+
+            last_seqno = last_seqno.max(shard.writer.last_seqno);
+        }
+
+        // NOTE: Every seqno is assigned while holding a shard lock,
+        // so after syncing all shards, every write up to the
+        // highest written seqno is durable
+        if mode != PersistMode::Buffer {
+            if let Some(seqno) = last_seqno {
+                let mut watermark = self.durable_watermark.lock().expect("lock is poisoned");
+                *watermark = (*watermark).max(seqno + 1);
+                drop(watermark);
+
+                self.durable_signal.notify_all();
             }
         }
+
         Ok(())
     }
+
+    /// Advances the durable watermark after a shard was synced by a group commit
+    ///
+    /// `next_seqno` needs to be read from the seqno counter before calling this.
+    /// Shards are locked one at a time, so no write can be in flight in a shard while
+    /// it is checked, and writes starting afterwards get a seqno of at least `next_seqno`.
+    pub(crate) fn advance_durable_watermark(&self, next_seqno: SeqNo) {
+        let mut durable = next_seqno;
+
+        for shard in self.shards.lock_each() {
+            let synced = shard.group_commit.synced_data();
+
+            // NOTE: A shard with unsynced writes holds back the watermark
+            if shard.writer.last_seqno.is_some_and(|seqno| seqno >= synced) {
+                durable = durable.min(synced);
+            }
+        }
+
+        let mut watermark = self.durable_watermark.lock().expect("lock is poisoned");
+
+        if durable > *watermark {
+            *watermark = durable;
+            drop(watermark);
+
+            self.durable_signal.notify_all();
+        }
+    }
+
+    /// Blocks until the write with the given seqno is durable, or the check returns `true`
+    ///
+    /// Returns `true` if the write is durable.
+    pub(crate) fn wait_for_durable(&self, seqno: SeqNo, cancel: &dyn Fn() -> bool) -> bool {
+        let mut watermark = self.durable_watermark.lock().expect("lock is poisoned");
+
+        while *watermark <= seqno {
+            if cancel() {
+                return false;
+            }
+
+            watermark = self
+                .durable_signal
+                .wait(watermark)
+                .expect("lock is poisoned");
+        }
+
+        true
+    }
+
+    /// Wakes up threads waiting for durability, e.g. after the keyspace was poisoned
+    pub(crate) fn wake_durable_waiters(&self) {
+        let _lock = self.durable_watermark.lock().expect("lock is poisoned");
+        self.durable_signal.notify_all();
+    }
 }
 
 #[cfg(test)]
@@ -157,18 +312,31 @@ mod tests {
         let shard_path = dir.path().join("0");
 
         let values = [
-            &BatchItem::new("default", *b"abc", *b"def", ValueType::Value),
-            &BatchItem::new("default", *b"yxc", *b"ghj", ValueType::Value),
+            (
+                0,
+                &BatchItem::new("default", *b"abc", *b"def", ValueType::Value),
+            ),
+            (
+                0,
+                &BatchItem::new("default", *b"yxc", *b"ghj", ValueType::Value),
+            ),
         ];
 
         {
-            let mut shard = JournalShard::create_new(&shard_path)?;
+            let mut shard =
+                JournalShard::create_new(&shard_path, writer::DEFAULT_PRE_ALLOCATED_BYTES)?;
             shard.writer.write_batch(&values, 0)?;
         }
 
         {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
-            let memtable = memtables.get("default").expect("should exist");
+            let (_, memtables) = Journal::recover(
+                &dir,
+                &|_| None,
+                RecoveryMode::TolerateCorruptTail,
+                DEFAULT_SHARD_COUNT,
+                writer::DEFAULT_PRE_ALLOCATED_BYTES,
+            )?;
+            let memtable = memtables.get(&0).expect("should exist");
             assert_eq!(memtable.len(), values.len());
         }
 
@@ -180,8 +348,14 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
-            let memtable = memtables.get("default").expect("should exist");
+            let (_, memtables) = Journal::recover(
+                &dir,
+                &|_| None,
+                RecoveryMode::TolerateCorruptTail,
+                DEFAULT_SHARD_COUNT,
+                writer::DEFAULT_PRE_ALLOCATED_BYTES,
+            )?;
+            let memtable = memtables.get(&0).expect("should exist");
 
             // Should recover all items
             assert_eq!(memtable.len(), values.len());
@@ -195,8 +369,14 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
-            let memtable = memtables.get("default").expect("should exist");
+            let (_, memtables) = Journal::recover(
+                &dir,
+                &|_| None,
+                RecoveryMode::TolerateCorruptTail,
+                DEFAULT_SHARD_COUNT,
+                writer::DEFAULT_PRE_ALLOCATED_BYTES,
+            )?;
+            let memtable = memtables.get(&0).expect("should exist");
 
             // Should recover all items
             assert_eq!(memtable.len(), values.len());
@@ -211,18 +391,31 @@ mod tests {
         let shard_path = dir.path().join("0");
 
         let values = [
-            &BatchItem::new("default", *b"abc", *b"def", ValueType::Value),
-            &BatchItem::new("default", *b"yxc", *b"ghj", ValueType::Value),
+            (
+                0,
+                &BatchItem::new("default", *b"abc", *b"def", ValueType::Value),
+            ),
+            (
+                0,
+                &BatchItem::new("default", *b"yxc", *b"ghj", ValueType::Value),
+            ),
         ];
 
         {
-            let mut shard = JournalShard::create_new(&shard_path)?;
+            let mut shard =
+                JournalShard::create_new(&shard_path, writer::DEFAULT_PRE_ALLOCATED_BYTES)?;
             shard.writer.write_batch(&values, 0)?;
         }
 
         {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
-            let memtable = memtables.get("default").expect("should exist");
+            let (_, memtables) = Journal::recover(
+                &dir,
+                &|_| None,
+                RecoveryMode::TolerateCorruptTail,
+                DEFAULT_SHARD_COUNT,
+                writer::DEFAULT_PRE_ALLOCATED_BYTES,
+            )?;
+            let memtable = memtables.get(&0).expect("should exist");
 
             assert_eq!(memtable.len(), values.len());
         }
@@ -239,8 +432,14 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
-            let memtable = memtables.get("default").expect("should exist");
+            let (_, memtables) = Journal::recover(
+                &dir,
+                &|_| None,
+                RecoveryMode::TolerateCorruptTail,
+                DEFAULT_SHARD_COUNT,
+                writer::DEFAULT_PRE_ALLOCATED_BYTES,
+            )?;
+            let memtable = memtables.get(&0).expect("should exist");
 
             // Should recover all items
             assert_eq!(memtable.len(), values.len());
@@ -258,8 +457,14 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
-            let memtable = memtables.get("default").expect("should exist");
+            let (_, memtables) = Journal::recover(
+                &dir,
+                &|_| None,
+                RecoveryMode::TolerateCorruptTail,
+                DEFAULT_SHARD_COUNT,
+                writer::DEFAULT_PRE_ALLOCATED_BYTES,
+            )?;
+            let memtable = memtables.get(&0).expect("should exist");
 
             // Should recover all items
             assert_eq!(memtable.len(), values.len());
@@ -274,18 +479,31 @@ mod tests {
         let shard_path = dir.path().join("0");
 
         let values = [
-            &BatchItem::new("default", *b"abc", *b"def", ValueType::Value),
-            &BatchItem::new("default", *b"yxc", *b"ghj", ValueType::Value),
+            (
+                0,
+                &BatchItem::new("default", *b"abc", *b"def", ValueType::Value),
+            ),
+            (
+                0,
+                &BatchItem::new("default", *b"yxc", *b"ghj", ValueType::Value),
+            ),
         ];
 
         {
-            let mut shard = JournalShard::create_new(&shard_path)?;
+            let mut shard =
+                JournalShard::create_new(&shard_path, writer::DEFAULT_PRE_ALLOCATED_BYTES)?;
             shard.writer.write_batch(&values, 0)?;
         }
 
         {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
-            let memtable = memtables.get("default").expect("should exist");
+            let (_, memtables) = Journal::recover(
+                &dir,
+                &|_| None,
+                RecoveryMode::TolerateCorruptTail,
+                DEFAULT_SHARD_COUNT,
+                writer::DEFAULT_PRE_ALLOCATED_BYTES,
+            )?;
+            let memtable = memtables.get(&0).expect("should exist");
 
             assert_eq!(memtable.len(), values.len());
         }
@@ -298,8 +516,14 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
-            let memtable = memtables.get("default").expect("should exist");
+            let (_, memtables) = Journal::recover(
+                &dir,
+                &|_| None,
+                RecoveryMode::TolerateCorruptTail,
+                DEFAULT_SHARD_COUNT,
+                writer::DEFAULT_PRE_ALLOCATED_BYTES,
+            )?;
+            let memtable = memtables.get(&0).expect("should exist");
 
             // Should recover all items
             assert_eq!(memtable.len(), values.len());
@@ -313,8 +537,14 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
-            let memtable = memtables.get("default").expect("should exist");
+            let (_, memtables) = Journal::recover(
+                &dir,
+                &|_| None,
+                RecoveryMode::TolerateCorruptTail,
+                DEFAULT_SHARD_COUNT,
+                writer::DEFAULT_PRE_ALLOCATED_BYTES,
+            )?;
+            let memtable = memtables.get(&0).expect("should exist");
 
             // Should recover all items
             assert_eq!(memtable.len(), values.len());
@@ -329,18 +559,31 @@ mod tests {
         let shard_path = dir.path().join("0");
 
         let values = [
-            &BatchItem::new("default", *b"abc", *b"def", ValueType::Value),
-            &BatchItem::new("default", *b"yxc", *b"ghj", ValueType::Value),
+            (
+                0,
+                &BatchItem::new("default", *b"abc", *b"def", ValueType::Value),
+            ),
+            (
+                0,
+                &BatchItem::new("default", *b"yxc", *b"ghj", ValueType::Value),
+            ),
         ];
 
         {
-            let mut shard = JournalShard::create_new(&shard_path)?;
+            let mut shard =
+                JournalShard::create_new(&shard_path, writer::DEFAULT_PRE_ALLOCATED_BYTES)?;
             shard.writer.write_batch(&values, 0)?;
         }
 
         {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
-            let memtable = memtables.get("default").expect("should exist");
+            let (_, memtables) = Journal::recover(
+                &dir,
+                &|_| None,
+                RecoveryMode::TolerateCorruptTail,
+                DEFAULT_SHARD_COUNT,
+                writer::DEFAULT_PRE_ALLOCATED_BYTES,
+            )?;
+            let memtable = memtables.get(&0).expect("should exist");
 
             assert_eq!(memtable.len(), values.len());
         }
@@ -348,8 +591,8 @@ mod tests {
         // Mangle journal
         {
             let mut file = std::fs::OpenOptions::new().append(true).open(&shard_path)?;
-            Marker::Item {
-                partition: "default".into(),
+            Marker::ItemById {
+                partition_id: 0,
                 key: "zzz".as_bytes().into(),
                 value: "".as_bytes().into(),
                 value_type: ValueType::Tombstone,
@@ -360,8 +603,14 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
-            let memtable = memtables.get("default").expect("should exist");
+            let (_, memtables) = Journal::recover(
+                &dir,
+                &|_| None,
+                RecoveryMode::TolerateCorruptTail,
+                DEFAULT_SHARD_COUNT,
+                writer::DEFAULT_PRE_ALLOCATED_BYTES,
+            )?;
+            let memtable = memtables.get(&0).expect("should exist");
 
             // Should recover all items
             assert_eq!(memtable.len(), values.len());
@@ -370,8 +619,8 @@ mod tests {
         // Mangle journal
         for _ in 0..5 {
             let mut file = std::fs::OpenOptions::new().append(true).open(&shard_path)?;
-            Marker::Item {
-                partition: "default".into(),
+            Marker::ItemById {
+                partition_id: 0,
                 key: "zzz".as_bytes().into(),
                 value: "".as_bytes().into(),
                 value_type: ValueType::Tombstone,
@@ -382,8 +631,14 @@ mod tests {
         }
 
         for _ in 0..10 {
-            let (_, memtables) = Journal::recover(&dir, RecoveryMode::TolerateCorruptTail)?;
-            let memtable = memtables.get("default").expect("should exist");
+            let (_, memtables) = Journal::recover(
+                &dir,
+                &|_| None,
+                RecoveryMode::TolerateCorruptTail,
+                DEFAULT_SHARD_COUNT,
+                writer::DEFAULT_PRE_ALLOCATED_BYTES,
+            )?;
+            let memtable = memtables.get(&0).expect("should exist");
 
             // Should recover all items
             assert_eq!(memtable.len(), values.len());
@@ -391,4 +646,33 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_recover_items_by_partition_name() -> crate::Result<()> {
+        let dir = tempdir()?;
+
+        let values = [
+            &BatchItem::new("default", *b"abc", *b"def", ValueType::Value),
+            &BatchItem::new("unknown", *b"yxc", *b"ghj", ValueType::Value),
+        ];
+
+        // NOTE: Journals written before partitions had IDs reference partitions by name
+        {
+            let mut file = std::fs::File::create(dir.path().join("0"))?;
+            writer::write_batch(&mut file, &values, 0)?;
+            file.sync_all()?;
+        }
+
+        let memtables = Journal::recover_memtables(
+            &dir,
+            None,
+            &|name| (name == "default").then_some(7),
+            RecoveryMode::TolerateCorruptTail,
+        )?;
+
+        assert_eq!(1, memtables.len());
+        assert_eq!(1, memtables.get(&7).expect("should exist").len());
+
+        Ok(())
+    }
 }
@@ -15,19 +15,48 @@ use std::{
 pub struct JournalShardReader {
     reader: BufReader<File>,
     last_valid_pos: u64,
+    read_only: bool,
 }
 
 impl JournalShardReader {
+    /// Opens a journal shard, truncating it to the last valid position if needed
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
     pub fn new<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
         let file = OpenOptions::new().read(true).write(true).open(path)?;
 
         Ok(Self {
             reader: BufReader::new(file),
             last_valid_pos: 0,
+            read_only: false,
+        })
+    }
+
+    /// Opens a journal shard without write access
+    ///
+    /// A corrupt tail will end the iteration, but will not be truncated,
+    /// which allows inspecting journals of a keyspace that is not opened.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn read_only<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let file = OpenOptions::new().read(true).open(path)?;
+
+        Ok(Self {
+            reader: BufReader::new(file),
+            last_valid_pos: 0,
+            read_only: true,
         })
     }
 
     fn truncate_file(&mut self, pos: u64) -> crate::Result<()> {
+        if self.read_only {
+            return Ok(());
+        }
+
         log::debug!("truncating log to {pos}");
         self.reader.get_mut().set_len(pos)?;
         self.reader.get_mut().sync_all()?;
@@ -1,8 +1,12 @@
-use super::{marker::Marker, writer::Writer as JournalWriter};
-use crate::batch::{item::Item as BatchItem, PartitionKey};
+use super::{
+    group_commit::{GroupCommit, PendingSync},
+    marker::Marker,
+    writer::{PersistMode, Writer as JournalWriter},
+};
 use crate::journal::reader::JournalShardReader;
+use crate::manifest::PartitionId;
 use lsm_tree::{serde::Serializable, MemTable, SeqNo};
-use std::{collections::HashMap, fs::OpenOptions, path::Path};
+use std::{collections::HashMap, fs::OpenOptions, path::Path, sync::Arc};
 
 /// Recovery mode to use
 ///
@@ -45,26 +49,93 @@ pub enum RecoveryError {
 pub struct JournalShard {
     pub(crate) writer: JournalWriter,
     pub(crate) should_sync: bool,
+    pub(crate) group_commit: Arc<GroupCommit>,
+
+    /// Partitions that have data in the active journal file, with their highest seqno
+    ///
+    /// Used to build the partition manifest when the journal is sealed,
+    /// without having to scan all partitions.
+    pub(crate) dirty_partitions: HashMap<PartitionId, SeqNo>,
 }
 
 impl JournalShard {
     pub fn rotate<P: AsRef<Path>>(&mut self, path: P) -> crate::Result<()> {
+        // IMPORTANT: Sync the old journal file, because writers
+        // may be waiting for their writes to become durable
+        self.flush(PersistMode::SyncAll)?;
+
         self.should_sync = false;
-        self.writer.rotate(path)
+        self.writer.rotate(path)?;
+        self.group_commit.set_file(self.writer.file_handle()?);
+        self.dirty_partitions.clear();
+
+        Ok(())
     }
 
-    pub fn create_new<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+    fn from_writer(writer: JournalWriter) -> crate::Result<Self> {
+        let group_commit = Arc::new(GroupCommit::new(writer.file_handle()?));
+
         Ok(Self {
-            writer: JournalWriter::create_new(path)?,
+            writer,
             should_sync: bool::default(),
+            group_commit,
+            dirty_partitions: HashMap::default(),
         })
     }
 
-    pub fn from_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
-        Ok(Self {
-            writer: JournalWriter::from_file(path)?,
-            should_sync: bool::default(),
-        })
+    pub fn create_new<P: AsRef<Path>>(path: P, preallocated_bytes: u64) -> crate::Result<Self> {
+        Self::from_writer(JournalWriter::create_new(path, preallocated_bytes)?)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P, preallocated_bytes: u64) -> crate::Result<Self> {
+        Self::from_writer(JournalWriter::from_file(path, preallocated_bytes)?)
+    }
+
+    /// Remembers that a partition has data in the active journal file, up to the given seqno
+    pub(crate) fn mark_dirty(&mut self, partition_id: PartitionId, seqno: SeqNo) {
+        self.dirty_partitions
+            .entry(partition_id)
+            .and_modify(|lsn| *lsn = (*lsn).max(seqno))
+            .or_insert(seqno);
+    }
+
+    /// Makes a written batch durable according to the given durability
+    ///
+    /// Needs to be called right after writing the batch. Returns the pending
+    /// sync the writer needs to wait for (after releasing the shard lock), if any.
+    pub(crate) fn prepare_durability(
+        &mut self,
+        seqno: SeqNo,
+        durability: Option<PersistMode>,
+    ) -> std::io::Result<Option<PendingSync>> {
+        let Some(mode) = durability else {
+            return Ok(None);
+        };
+
+        self.writer.flush(PersistMode::Buffer)?;
+
+        if mode == PersistMode::Buffer {
+            return Ok(None);
+        }
+
+        self.group_commit.mark_written(seqno);
+
+        Ok(Some(PendingSync::new(
+            self.group_commit.clone(),
+            seqno,
+            mode,
+        )))
+    }
+
+    /// Flushes the journal shard, and wakes up writers waiting for their writes to be synced
+    pub(crate) fn flush(&mut self, mode: PersistMode) -> std::io::Result<()> {
+        self.writer.flush(mode)?;
+
+        if let Some(seqno) = self.writer.last_seqno {
+            self.group_commit.mark_synced(seqno, mode);
+        }
+
+        Ok(())
     }
 
     fn truncate_to<P: AsRef<Path>>(path: P, last_valid_pos: u64) -> crate::Result<()> {
@@ -77,12 +148,16 @@ impl JournalShard {
 
     /// Recovers a journal shard and writes the items into the given memtable
     ///
+    /// Items that reference their partition by name (written before partitions had IDs)
+    /// are mapped to partition IDs using `resolve_name`. Items of unknown partitions are skipped.
+    ///
     /// Will truncate the file to the position of the last valid batch
     #[allow(clippy::too_many_lines)]
     pub fn recover_and_repair<P: AsRef<Path>>(
         path: P,
-        memtables: &mut HashMap<PartitionKey, MemTable>,
-        whitelist: Option<&[PartitionKey]>,
+        memtables: &mut HashMap<PartitionId, MemTable>,
+        filter: &dyn Fn(PartitionId, SeqNo) -> bool,
+        resolve_name: &dyn Fn(&str) -> Option<PartitionId>,
         _recovery_mode: RecoveryMode, // TODO:
     ) -> crate::Result<()> {
         use crate::Error::JournalRecovery;
@@ -96,7 +171,7 @@ impl JournalShard {
         let mut batch_seqno = SeqNo::default();
         let mut last_valid_pos = 0;
 
-        let mut items: Vec<BatchItem> = vec![];
+        let mut items: Vec<(PartitionId, lsm_tree::Value)> = vec![];
 
         'a: for item in recoverer {
             let (journal_file_pos, item) = item?;
@@ -146,39 +221,17 @@ impl JournalShard {
                     // NOTE: Clippy says into_iter() is better
                     // but in this case probably not
                     #[allow(clippy::iter_with_drain)]
-                    for item in items.drain(..) {
-                        if let Some(whitelist) = whitelist {
-                            if !whitelist.contains(&item.partition) {
-                                continue;
-                            }
+                    for (partition_id, value) in items.drain(..) {
+                        if !filter(partition_id, batch_seqno) {
+                            continue;
                         }
 
-                        let memtable = memtables.entry(item.partition).or_default();
-
-                        let value = lsm_tree::Value {
-                            key: item.key,
-                            value: item.value,
-                            seqno: batch_seqno,
-                            value_type: item.value_type,
-                        };
-
-                        memtable.insert(value);
+                        memtables.entry(partition_id).or_default().insert(value);
                     }
 
                     last_valid_pos = journal_file_pos;
                 }
-                Marker::Item {
-                    partition,
-                    key,
-                    value,
-                    value_type,
-                } => {
-                    let item = Marker::Item {
-                        partition: partition.clone(),
-                        key: key.clone(),
-                        value: value.clone(),
-                        value_type,
-                    };
+                item @ (Marker::Item { .. } | Marker::ItemById { .. }) => {
                     let mut bytes = Vec::with_capacity(100);
                     item.serialize(&mut bytes)?;
 
@@ -200,12 +253,38 @@ impl JournalShard {
 
                     batch_counter -= 1;
 
-                    items.push(BatchItem {
-                        partition,
-                        key,
-                        value,
-                        value_type,
-                    });
+                    let (partition_id, key, value, value_type) = match item {
+                        Marker::Item {
+                            partition,
+                            key,
+                            value,
+                            value_type,
+                        } => {
+                            let Some(partition_id) = resolve_name(&partition) else {
+                                log::trace!("Skipping item of unknown partition {partition:?}");
+                                continue;
+                            };
+
+                            (partition_id, key, value, value_type)
+                        }
+                        Marker::ItemById {
+                            partition_id,
+                            key,
+                            value,
+                            value_type,
+                        } => (partition_id, key, value, value_type),
+                        Marker::Start { .. } | Marker::End(_) => continue,
+                    };
+
+                    items.push((
+                        partition_id,
+                        lsm_tree::Value {
+                            key,
+                            value,
+                            seqno: batch_seqno,
+                            value_type,
+                        },
+                    ));
                 }
             }
         }
@@ -1,21 +1,28 @@
 use super::marker::Marker;
-use crate::batch::item::Item as BatchItem;
+use crate::{batch::item::Item as BatchItem, manifest::PartitionId};
 use lsm_tree::{serde::Serializable, SeqNo, SerializeError};
 use std::{
     fs::{File, OpenOptions},
-    io::{BufWriter, Write},
+    io::{BufWriter, Seek, SeekFrom, Write},
     path::Path,
 };
 
-pub const PRE_ALLOCATED_BYTES: u64 = 8 * 1_024 * 1_024;
+/// Default amount of bytes that are preallocated for each journal shard file
+pub const DEFAULT_PRE_ALLOCATED_BYTES: u64 = 8 * 1_024 * 1_024;
 
 pub struct Writer {
     file: BufWriter<File>,
+
+    /// Seqno of the last batch that was written
+    pub(crate) last_seqno: Option<SeqNo>,
+
+    /// Amount of bytes that are preallocated for new journal files
+    preallocated_bytes: u64,
 }
 
 /// Writes a batch start marker to the journal
-fn write_start(
-    writer: &mut BufWriter<File>,
+fn write_start<W: Write>(
+    writer: &mut W,
     item_count: u32,
     seqno: SeqNo,
 ) -> Result<usize, SerializeError> {
@@ -27,7 +34,7 @@ fn write_start(
 }
 
 /// Writes a batch end marker to the journal
-fn write_end(writer: &mut BufWriter<File>, crc: u32) -> Result<usize, SerializeError> {
+fn write_end<W: Write>(writer: &mut W, crc: u32) -> Result<usize, SerializeError> {
     let mut bytes = Vec::new();
     Marker::End(crc).serialize(&mut bytes)?;
 
@@ -59,43 +66,65 @@ pub enum PersistMode {
 
 impl Writer {
     pub fn rotate<P: AsRef<Path>>(&mut self, path: P) -> crate::Result<()> {
+        // NOTE: Cut off the unused preallocated space, so the sealed journal
+        // only takes up as much disk space as its data
+        self.file.flush()?;
+        let file = self.file.get_mut();
+        let len = file.stream_position()?;
+        file.set_len(len)?;
+
         let file = File::create(&path)?;
-        file.set_len(PRE_ALLOCATED_BYTES)?;
+        file.set_len(self.preallocated_bytes)?;
 
         self.file = BufWriter::new(file);
 
         Ok(())
     }
 
-    pub fn create_new<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+    pub fn create_new<P: AsRef<Path>>(path: P, preallocated_bytes: u64) -> crate::Result<Self> {
         let path = path.as_ref();
         let file = File::create(path)?;
-        file.set_len(PRE_ALLOCATED_BYTES)?;
+        file.set_len(preallocated_bytes)?;
 
         Ok(Self {
             file: BufWriter::new(file),
+            last_seqno: None,
+            preallocated_bytes,
         })
     }
 
-    pub fn from_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+    pub fn from_file<P: AsRef<Path>>(path: P, preallocated_bytes: u64) -> crate::Result<Self> {
         let path = path.as_ref();
 
         if !path.try_exists()? {
             let file = OpenOptions::new().create_new(true).write(true).open(path)?;
-            file.set_len(PRE_ALLOCATED_BYTES)?;
+            file.set_len(preallocated_bytes)?;
 
             return Ok(Self {
                 file: BufWriter::new(file),
+                last_seqno: None,
+                preallocated_bytes,
             });
         }
 
-        let file = OpenOptions::new().append(true).open(path)?;
+        let mut file = OpenOptions::new().append(true).open(path)?;
+
+        // NOTE: Appending does not move the cursor before the first write,
+        // but the cursor is used to find the end of the data when rotating
+        file.seek(SeekFrom::End(0))?;
 
         Ok(Self {
             file: BufWriter::new(file),
+            last_seqno: None,
+            preallocated_bytes,
         })
     }
 
+    /// Returns a new handle to the journal file
+    pub(crate) fn file_handle(&self) -> std::io::Result<File> {
+        self.file.get_ref().try_clone()
+    }
+
     /// Flushes the journal file
     ///
     /// # Panics
@@ -112,39 +141,80 @@ impl Writer {
     }
 
     /// Appends a single item wrapped in a batch to the journal
-    pub(crate) fn write(&mut self, item: &BatchItem, seqno: SeqNo) -> crate::Result<usize> {
-        self.write_batch(&[item], seqno)
+    pub(crate) fn write(
+        &mut self,
+        partition_id: PartitionId,
+        item: &BatchItem,
+        seqno: SeqNo,
+    ) -> crate::Result<usize> {
+        self.write_batch(&[(partition_id, item)], seqno)
     }
 
-    pub fn write_batch(&mut self, items: &[&BatchItem], seqno: SeqNo) -> crate::Result<usize> {
-        // NOTE: entries.len() is surely never > u32::MAX
-        #[allow(clippy::cast_possible_truncation)]
-        let item_count = items.len() as u32;
+    /// Appends a batch to the journal, referencing the partitions by ID
+    pub fn write_batch(
+        &mut self,
+        items: &[(PartitionId, &BatchItem)],
+        seqno: SeqNo,
+    ) -> crate::Result<usize> {
+        let markers = items.iter().map(|(partition_id, item)| Marker::ItemById {
+            partition_id: *partition_id,
+            key: item.key.clone(),
+            value: item.value.clone(),
+            value_type: item.value_type,
+        });
+
+        let bytes_written = write_markers(&mut self.file, markers, seqno)?;
+        self.last_seqno = Some(seqno);
+        Ok(bytes_written)
+    }
+}
 
-        let mut hasher = crc32fast::Hasher::new();
-        let mut byte_count = 0;
+/// Writes a batch (start marker, items, end marker) into the given writer,
+/// referencing the partitions by name
+///
+/// Returns the amount of bytes written.
+pub fn write_batch<W: Write>(
+    writer: &mut W,
+    items: &[&BatchItem],
+    seqno: SeqNo,
+) -> crate::Result<usize> {
+    let markers = items.iter().map(|item| Marker::Item {
+        partition: item.partition.clone(),
+        key: item.key.clone(),
+        value: item.value.clone(),
+        value_type: item.value_type,
+    });
+
+    write_markers(writer, markers, seqno)
+}
 
-        byte_count += write_start(&mut self.file, item_count, seqno)?;
+/// Writes the given item markers wrapped in a batch into the given writer
+fn write_markers<W: Write, I: ExactSizeIterator<Item = Marker>>(
+    writer: &mut W,
+    items: I,
+    seqno: SeqNo,
+) -> crate::Result<usize> {
+    // NOTE: entries.len() is surely never > u32::MAX
+    #[allow(clippy::cast_possible_truncation)]
+    let item_count = items.len() as u32;
 
-        for item in items {
-            let item = Marker::Item {
-                partition: item.partition.clone(),
-                key: item.key.clone(),
-                value: item.value.clone(),
-                value_type: item.value_type,
-            };
-            let mut bytes = Vec::new();
-            item.serialize(&mut bytes)?;
+    let mut hasher = crc32fast::Hasher::new();
+    let mut byte_count = 0;
 
-            self.file.write_all(&bytes)?;
+    byte_count += write_start(writer, item_count, seqno)?;
 
-            hasher.update(&bytes);
-            byte_count += bytes.len();
-        }
+    for item in items {
+        let mut bytes = Vec::new();
+        item.serialize(&mut bytes)?;
 
-        let crc = hasher.finalize();
-        byte_count += write_end(&mut self.file, crc)?;
+        writer.write_all(&bytes)?;
 
-        Ok(byte_count)
+        hasher.update(&bytes);
+        byte_count += bytes.len();
     }
+
+    let crc = hasher.finalize();
+    byte_count += write_end(writer, crc)?;
+
+    Ok(byte_count)
 }
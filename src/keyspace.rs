@@ -2,47 +2,65 @@ use crate::{
     batch::{Batch, PartitionKey},
     compaction::manager::CompactionManager,
     config::Config,
-    file::{
-        fsync_directory, FJALL_MARKER, FLUSH_MARKER, JOURNALS_FOLDER, PARTITIONS_FOLDER,
-        PARTITION_DELETED_MARKER,
-    },
-    flush::manager::FlushManager,
-    journal::{manager::JournalManager, shard::RecoveryMode, writer::PersistMode, Journal},
+    file::{fsync_directory, FJALL_MARKER, FLUSH_MARKER, JOURNALS_FOLDER, PARTITIONS_FOLDER},
+    flush::{manager::FlushManager, worker::flush_partition_blocking},
+    journal::{manager::JournalManager, writer::PersistMode, Journal},
+    manifest::{KeyspaceManifest, Manifest, PartitionEntry, PartitionId},
     monitor::Monitor,
+    obsolete_trees::ObsoleteTrees,
     partition::name::is_valid_partition_name,
-    recovery::{recover_partitions, recover_sealed_memtables},
+    partition::{multi_get, PartitionHandleInner},
+    recovery::{
+        recover_partition, recover_partitions, recover_point_in_time, recover_sealed_memtables,
+    },
+    replication::ReplicationSink,
+    seqno_marker::SeqnoMarker,
     version::Version,
     write_buffer_manager::WriteBufferManager,
     PartitionCreateOptions, PartitionHandle,
 };
-use lsm_tree::{MemTable, SequenceNumberCounter};
+use lsm_tree::{MemTable, SequenceNumberCounter, UserValue};
 use std::{
     collections::HashMap,
-    fs::File,
     path::Path,
     sync::{
-        atomic::{AtomicBool, AtomicUsize},
-        Arc, RwLock,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize},
+        Arc, RwLock, Weak,
     },
 };
 use std_semaphore::Semaphore;
 
 pub type Partitions = HashMap<PartitionKey, PartitionHandle>;
 
+/// Partitions that exist on disk, but are not loaded
+///
+/// If a closed partition's handle is still alive somewhere,
+/// it is reused when the partition is loaded again.
+pub type UnloadedPartitions = HashMap<PartitionKey, Weak<PartitionHandleInner>>;
+
 #[allow(clippy::module_name_repetitions)]
 pub struct KeyspaceInner {
-    /// Dictionary of all partitions
+    /// Dictionary of all loaded partitions
     pub(crate) partitions: Arc<RwLock<Partitions>>,
 
+    /// Partitions that are not loaded yet, or have been closed
+    pub(crate) unloaded_partitions: Arc<RwLock<UnloadedPartitions>>,
+
     /// Journal (write-ahead-log/WAL)
     pub(crate) journal: Arc<Journal>,
 
     /// Keyspace configuration
     pub(crate) config: Config,
 
+    /// Maps partition IDs to partition names & metadata
+    pub(crate) manifest: Arc<KeyspaceManifest>,
+
     /// Current sequence number
     pub(crate) seqno: SequenceNumberCounter,
 
+    /// Persists an upper bound of the seqnos of all (possibly unloaded) partitions
+    pub(crate) seqno_marker: Arc<SeqnoMarker>,
+
     /// Caps write buffer size by flushing
     /// memtables to disk segments
     pub(crate) flush_manager: Arc<RwLock<FlushManager>>,
@@ -69,6 +87,15 @@ pub struct KeyspaceInner {
 
     /// True if fsync failed
     pub(crate) is_poisoned: Arc<AtomicBool>,
+
+    /// Ships committed batches to a follower
+    pub(crate) replication: ReplicationSink,
+
+    /// Instant up to which batches of a primary have been applied
+    pub(crate) applied_instant: AtomicU64,
+
+    /// Folders of detached trees (e.g. of cleared partitions) that are removed once unused
+    pub(crate) obsolete_trees: Arc<ObsoleteTrees>,
 }
 
 impl Drop for KeyspaceInner {
@@ -98,6 +125,48 @@ impl Drop for KeyspaceInner {
             self.compaction_manager.notify_empty();
         }
 
+        // NOTE: Closed partitions may still be written to, if someone holds a handle
+        let partitions = self
+            .partitions
+            .read()
+            .expect("lock is poisoned")
+            .values()
+            .cloned()
+            .chain(
+                self.unloaded_partitions
+                    .read()
+                    .expect("lock is poisoned")
+                    .values()
+                    .filter_map(Weak::upgrade)
+                    .map(PartitionHandle),
+            )
+            .collect::<Vec<_>>();
+
+        // IMPORTANT: Non-journaled partitions cannot recover their memtables
+        // from the journal, so they need to be flushed
+        for partition in &partitions {
+            if partition.is_journaled()
+                || partition
+                    .is_deleted
+                    .load(std::sync::atomic::Ordering::Acquire)
+            {
+                continue;
+            }
+
+            if let Err(e) = partition
+                .rotate_memtable()
+                .and_then(|_| flush_partition_blocking(&self.flush_manager, partition))
+            {
+                log::error!(
+                    "Failed to flush non-journaled partition {:?} on drop: {e:?}",
+                    partition.name()
+                );
+            }
+        }
+
+        // NOTE: Detached trees that are still in use are removed on recovery
+        self.obsolete_trees.cleanup();
+
         self.config.descriptor_table.clear();
     }
 }
@@ -212,7 +281,28 @@ impl Keyspace {
             .map(PartitionHandle::disk_space)
             .sum::<u64>();
 
-        journal_size + partitions_size
+        let partitions_folder = self.config.path.join(PARTITIONS_FOLDER);
+
+        let unloaded_partitions_size = self
+            .unloaded_partitions
+            .read()
+            .expect("lock is poisoned")
+            .keys()
+            .filter_map(|name| self.manifest.find(name))
+            .map(|(id, _)| {
+                let path = partitions_folder.join(id.to_string());
+
+                fs_extra::dir::get_size(&path).unwrap_or_else(|e| {
+                    log::error!(
+                        "Failed to get disk space of unloaded partition at {}: {e:?}",
+                        path.display()
+                    );
+                    0
+                })
+            })
+            .sum::<u64>();
+
+        journal_size + partitions_size + unloaded_partitions_size
     }
 
     /// Flushes the active journal to OS buffers. The durability depends on the [`PersistMode`]
@@ -247,6 +337,7 @@ impl Keyspace {
         if let Err(e) = self.journal.flush(mode) {
             self.is_poisoned
                 .store(true, std::sync::atomic::Ordering::Release);
+            self.journal.wake_durable_waiters();
             log::error!(
                 "flush failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
             );
@@ -255,6 +346,52 @@ impl Keyspace {
         Ok(())
     }
 
+    /// Blocks until the write with the given seqno is durable.
+    ///
+    /// A write becomes durable once the fsync thread, an explicit
+    /// [`Keyspace::persist`] (using [`PersistMode::SyncData`] or [`PersistMode::SyncAll`]),
+    /// or a write that requested durability (see [`crate::WriteOptions::durability`]),
+    /// has synced the journal past it.
+    ///
+    /// The seqno needs to be returned by a write, e.g. [`crate::Batch::commit`];
+    /// it is not a snapshot instant, which is one past the seqno of the last visible write.
+    /// If the fsync thread is disabled, and the journal is never synced,
+    /// this function blocks forever.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, PersistMode};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// let keyspace = Config::new(folder).open()?;
+    /// let items = keyspace.open_partition("my_items", PartitionCreateOptions::default())?;
+    ///
+    /// let mut batch = keyspace.batch();
+    /// batch.insert(&items, "a", "hello");
+    /// let seqno = batch.commit()?;
+    ///
+    /// keyspace.persist(PersistMode::SyncAll)?;
+    /// keyspace.wait_for_durable(seqno)?;
+    /// #
+    /// # Ok::<_, fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if the keyspace was poisoned because a sync failed.
+    pub fn wait_for_durable(&self, seqno: crate::Instant) -> crate::Result<()> {
+        let is_durable = self.journal.wait_for_durable(seqno, &|| {
+            self.is_poisoned.load(std::sync::atomic::Ordering::Acquire)
+        });
+
+        if is_durable {
+            Ok(())
+        } else {
+            Err(crate::Error::Poisoned)
+        }
+    }
+
     /// Opens a keyspace in the given directory.
     ///
     /// # Errors
@@ -322,38 +459,388 @@ impl Keyspace {
     ///
     /// Will return `Err` if an IO error occurs.
     pub fn delete_partition(&self, handle: PartitionHandle) -> crate::Result<()> {
-        let partition_path = handle.path();
-
-        let file = File::create(partition_path.join(PARTITION_DELETED_MARKER))?;
-        file.sync_all()?;
+        // NOTE: The name may have been reused by another partition already
+        if handle.is_deleted.load(std::sync::atomic::Ordering::Acquire) {
+            return Ok(());
+        }
 
-        // IMPORTANT: fsync folder on Unix
-        fsync_directory(&partition_path)?;
+        // NOTE: Unregistering the partition deletes it atomically,
+        // its folder is removed once the last handle is dropped, or on recovery
+        self.manifest.remove(handle.id())?;
 
         handle
             .is_deleted
             .store(true, std::sync::atomic::Ordering::Release);
 
         // IMPORTANT: Care, locks partitions map
-        self.compaction_manager.remove_partition(&handle.name);
+        self.compaction_manager.remove_partition(handle.id());
 
         self.flush_manager
             .write()
             .expect("lock is poisoned")
-            .remove_partition(&handle.name);
+            .remove_partition(handle.id());
 
-        self.partitions
+        let mut partitions = self.partitions.write().expect("lock is poisoned");
+        let mut unloaded_partitions = self.unloaded_partitions.write().expect("lock is poisoned");
+
+        // NOTE: Get the name while holding the locks, so a concurrent rename is not missed
+        let name = handle.name();
+        partitions.remove(&name);
+        unloaded_partitions.remove(&name);
+
+        Ok(())
+    }
+
+    /// Renames a partition.
+    ///
+    /// The partition's data is not copied or moved, so renaming is cheap
+    /// regardless of the partition size. The rename is atomic: After a crash,
+    /// the partition either has its old or its new name.
+    ///
+    /// Existing handles of the partition stay valid, and observe the new name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let partition = keyspace.open_partition("users_v2", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    ///
+    /// keyspace.rename_partition(&partition, "users")?;
+    /// assert_eq!("users", &*partition.name());
+    /// assert!(!keyspace.partition_exists("users_v2"));
+    ///
+    /// let renamed = keyspace.open_partition("users", PartitionCreateOptions::default())?;
+    /// assert!(renamed.contains_key("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionExists`](crate::Error::PartitionExists) if a partition
+    /// with the new name already exists, and [`Error::PartitionDeleted`](crate::Error::PartitionDeleted)
+    /// if the partition has been deleted.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new partition name is invalid.
+    pub fn rename_partition(&self, handle: &PartitionHandle, new_name: &str) -> crate::Result<()> {
+        assert!(is_valid_partition_name(new_name));
+
+        // IMPORTANT: Lock both partition maps, so the partition cannot
+        // be loaded, closed or created under the new name concurrently
+        let mut partitions = self.partitions.write().expect("lock is poisoned");
+        let mut unloaded_partitions = self.unloaded_partitions.write().expect("lock is poisoned");
+
+        if handle.is_deleted.load(std::sync::atomic::Ordering::Acquire) {
+            return Err(crate::Error::PartitionDeleted);
+        }
+
+        let old_name = handle.name();
+
+        if &*old_name == new_name {
+            return Ok(());
+        }
+
+        if partitions.contains_key(new_name) || unloaded_partitions.contains_key(new_name) {
+            return Err(crate::Error::PartitionExists);
+        }
+
+        let new_name: PartitionKey = new_name.into();
+
+        // NOTE: Everything on disk (partition folder, journals) references the partition by ID,
+        // so persisting the new name in the manifest is what atomically renames the partition
+        self.manifest.rename(handle.id(), new_name.clone())?;
+
+        if let Some(partition) = partitions.remove(&old_name) {
+            partitions.insert(new_name.clone(), partition);
+        }
+
+        if let Some(partition) = unloaded_partitions.remove(&old_name) {
+            unloaded_partitions.insert(new_name.clone(), partition);
+        }
+
+        log::debug!("Renamed partition {old_name:?} to {new_name:?}");
+
+        *handle.name.write().expect("lock is poisoned") = new_name;
+
+        Ok(())
+    }
+
+    /// Atomically exchanges the contents of two partitions.
+    ///
+    /// No data is copied or moved, so swapping is cheap regardless of the partition sizes.
+    /// After a crash, either both or neither of the partitions are swapped.
+    ///
+    /// Partition handles stay bound to their names, so existing handles
+    /// observe the new contents. The partitions' settings (e.g. the maximum memtable size)
+    /// stay with the handles as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let live = keyspace.open_partition("index", PartitionCreateOptions::default())?;
+    /// live.insert("a", "old")?;
+    ///
+    /// let rebuilt = keyspace.open_partition("index_rebuild", PartitionCreateOptions::default())?;
+    /// rebuilt.insert("a", "new")?;
+    ///
+    /// keyspace.swap_partitions(&live, &rebuilt)?;
+    /// assert_eq!(Some("new".as_bytes().into()), live.get("a")?);
+    /// assert_eq!(Some("old".as_bytes().into()), rebuilt.get("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionDeleted`](crate::Error::PartitionDeleted)
+    /// if either partition has been deleted.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a lock is poisoned.
+    pub fn swap_partitions(&self, a: &PartitionHandle, b: &PartitionHandle) -> crate::Result<()> {
+        if a == b {
+            return Ok(());
+        }
+
+        // IMPORTANT: Lock the journal, so no writes or memtable rotations
+        // can happen while the slots are exchanged
+        let _journal_lock = self.journal.shards.full_lock().expect("lock is poisoned");
+        let mut journal_manager = self.journal_manager.write().expect("lock is poisoned");
+        let _partitions = self.partitions.write().expect("lock is poisoned");
+        let _unloaded_partitions = self.unloaded_partitions.write().expect("lock is poisoned");
+
+        if a.is_deleted.load(std::sync::atomic::Ordering::Acquire)
+            || b.is_deleted.load(std::sync::atomic::Ordering::Acquire)
+        {
+            return Err(crate::Error::PartitionDeleted);
+        }
+
+        // NOTE: The partition folders and journals reference partitions by ID,
+        // so exchanging the names in the manifest is what atomically swaps the partitions
+        self.manifest.swap(a.id(), b.id())?;
+
+        std::mem::swap(
+            &mut *a.slot.write().expect("lock is poisoned"),
+            &mut *b.slot.write().expect("lock is poisoned"),
+        );
+
+        journal_manager.swap_partitions(a, b);
+
+        log::debug!("Swapped partitions {:?} and {:?}", a.name(), b.name());
+
+        Ok(())
+    }
+
+    /// Atomically replaces the contents of a partition with another partition.
+    ///
+    /// The source partition takes the place of the target partition, and is deleted
+    /// under its own name. The target's old data is removed, once the source handle is dropped.
+    ///
+    /// Existing handles of the target observe the new contents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let live = keyspace.open_partition("index", PartitionCreateOptions::default())?;
+    /// live.insert("a", "old")?;
+    ///
+    /// let rebuilt = keyspace.open_partition("index_rebuild", PartitionCreateOptions::default())?;
+    /// rebuilt.insert("a", "new")?;
+    ///
+    /// keyspace.replace_partition(&live, rebuilt)?;
+    /// assert_eq!(Some("new".as_bytes().into()), live.get("a")?);
+    /// assert!(!keyspace.partition_exists("index_rebuild"));
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionDeleted`](crate::Error::PartitionDeleted)
+    /// if either partition has been deleted.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a lock is poisoned.
+    pub fn replace_partition(
+        &self,
+        target: &PartitionHandle,
+        source: PartitionHandle,
+    ) -> crate::Result<()> {
+        if *target == source {
+            return Ok(());
+        }
+
+        let old_target_id = {
+            // IMPORTANT: Lock the journal, so no writes or memtable rotations
+            // can happen while the slots are exchanged
+            let _journal_lock = self.journal.shards.full_lock().expect("lock is poisoned");
+            let mut journal_manager = self.journal_manager.write().expect("lock is poisoned");
+            let mut partitions = self.partitions.write().expect("lock is poisoned");
+            let mut unloaded_partitions =
+                self.unloaded_partitions.write().expect("lock is poisoned");
+
+            if target.is_deleted.load(std::sync::atomic::Ordering::Acquire)
+                || source.is_deleted.load(std::sync::atomic::Ordering::Acquire)
+            {
+                return Err(crate::Error::PartitionDeleted);
+            }
+
+            let old_target_id = target.id();
+
+            // NOTE: Unregistering the target's old ID deletes its data atomically,
+            // and the source's data takes its name in the same manifest write
+            self.manifest.replace(old_target_id, source.id())?;
+
+            std::mem::swap(
+                &mut *target.slot.write().expect("lock is poisoned"),
+                &mut *source.slot.write().expect("lock is poisoned"),
+            );
+
+            journal_manager.swap_partitions(target, &source);
+
+            // NOTE: The source handle now holds the target's old data,
+            // whose folder is removed once the last handle is dropped, or on recovery
+            source
+                .is_deleted
+                .store(true, std::sync::atomic::Ordering::Release);
+
+            let source_name = source.name();
+            partitions.remove(&source_name);
+            unloaded_partitions.remove(&source_name);
+
+            log::debug!(
+                "Replaced partition {:?} with {source_name:?}",
+                target.name()
+            );
+
+            old_target_id
+        };
+
+        // IMPORTANT: Care, locks partitions map
+        self.compaction_manager.remove_partition(old_target_id);
+
+        self.flush_manager
             .write()
             .expect("lock is poisoned")
-            .remove(&handle.name);
+            .remove_partition(old_target_id);
+
+        // NOTE: The target's old data is removed once the last source handle is dropped
+        drop(source);
 
         Ok(())
     }
 
+    /// Creates a new partition as a copy-on-write clone of another partition.
+    ///
+    /// The new partition initially shares the source's immutable disk segments
+    /// (using hard links), so no data is rewritten, and contains every item
+    /// that was written to the source before the fork.
+    /// After that, both partitions diverge independently.
+    ///
+    /// Settings of the source (e.g. the maximum memtable size) are copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let prod = keyspace.open_partition("prod", PartitionCreateOptions::default())?;
+    /// prod.insert("a", "abc")?;
+    ///
+    /// let staging = keyspace.fork_partition(&prod, "staging")?;
+    /// assert!(staging.contains_key("a")?);
+    ///
+    /// staging.remove("a")?;
+    /// assert!(prod.contains_key("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionExists`](crate::Error::PartitionExists) if a partition
+    /// with the new name already exists, and [`Error::PartitionDeleted`](crate::Error::PartitionDeleted)
+    /// if the source partition has been deleted.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new partition name is invalid.
+    pub fn fork_partition(
+        &self,
+        source: &PartitionHandle,
+        new_name: &str,
+    ) -> crate::Result<PartitionHandle> {
+        assert!(is_valid_partition_name(new_name));
+
+        if self.partition_exists(new_name) {
+            return Err(crate::Error::PartitionExists);
+        }
+
+        let new_name: PartitionKey = new_name.into();
+        let handle = PartitionHandle::create_fork(self, source, new_name.clone())?;
+
+        let mut partitions = self.partitions.write().expect("lock is poisoned");
+        let unloaded_partitions = self.unloaded_partitions.read().expect("lock is poisoned");
+
+        // NOTE: The name may have been taken while forking
+        let result =
+            if partitions.contains_key(&new_name) || unloaded_partitions.contains_key(&new_name) {
+                Err(crate::Error::PartitionExists)
+            } else {
+                // IMPORTANT: Register the partition after the tree is initialized,
+                // registering it is what atomically creates the partition
+                self.manifest.insert(
+                    handle.id(),
+                    PartitionEntry {
+                        name: new_name.clone(),
+                        journaled: handle.is_journaled(),
+                    },
+                )
+            };
+
+        if let Err(e) = result {
+            // NOTE: The folder of the unregistered partition is removed once the handle is dropped
+            handle
+                .is_deleted
+                .store(true, std::sync::atomic::Ordering::Release);
+
+            return Err(e);
+        }
+
+        partitions.insert(new_name, handle.clone());
+
+        Ok(handle)
+    }
+
     /// Creates or opens a keyspace partition.
     ///
-    /// Partition names can be up to 255 characters long, can not be empty and
-    /// can only contain alphanumerics, underscore (`_`) and dash (`-`).
+    /// Partition names can be any UTF-8 string up to 255 bytes long, and can not be empty.
     ///
     /// # Errors
     ///
@@ -371,31 +858,141 @@ impl Keyspace {
 
         let mut partitions = self.partitions.write().expect("lock is poisoned");
 
-        Ok(if let Some(partition) = partitions.get(name) {
-            partition.clone()
+        let name: PartitionKey = name.into();
+
+        Ok(
+            if let Some(partition) = self.load_partition(&mut partitions, &name)? {
+                partition
+            } else {
+                let handle = PartitionHandle::create_new(self, name.clone(), create_options)?;
+                partitions.insert(name, handle.clone());
+
+                handle
+            },
+        )
+    }
+
+    /// Returns the loaded partition, loading it from disk if it is unloaded.
+    ///
+    /// Returns `None` if the partition does not exist.
+    pub(crate) fn load_partition(
+        &self,
+        partitions: &mut Partitions,
+        name: &PartitionKey,
+    ) -> crate::Result<Option<PartitionHandle>> {
+        if let Some(partition) = partitions.get(name) {
+            return Ok(Some(partition.clone()));
+        }
+
+        let mut unloaded_partitions = self.unloaded_partitions.write().expect("lock is poisoned");
+
+        let Some(weak) = unloaded_partitions.get(name) else {
+            return Ok(None);
+        };
+
+        // NOTE: If the partition was closed, but is still referenced somewhere,
+        // reuse it, so we never have two trees on the same folder
+        let partition = if let Some(inner) = weak.upgrade() {
+            log::debug!("Reopening closed partition {name:?}");
+            PartitionHandle(inner)
         } else {
-            let name: PartitionKey = name.into();
+            log::debug!("Loading partition {name:?}");
+            recover_partition(self, name, None)?
+        };
 
-            let handle = PartitionHandle::create_new(self, name.clone(), create_options)?;
-            partitions.insert(name, handle.clone());
+        unloaded_partitions.remove(name);
+        partitions.insert(name.clone(), partition.clone());
 
-            handle
-        })
+        Ok(Some(partition))
     }
 
-    /// Returns the amount of partitions
+    /// Closes the partition, freeing its memory and file descriptors.
+    ///
+    /// The partition's memtable is flushed, and the partition
+    /// is loaded again on the next [`Keyspace::open_partition`].
+    ///
+    /// The partition is only unloaded once every handle to it has been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    ///
+    /// keyspace.close_partition(partition)?;
+    /// assert!(keyspace.partition_exists("default"));
+    ///
+    /// let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// assert!(partition.contains_key("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a lock is poisoned.
+    pub fn close_partition(&self, handle: PartitionHandle) -> crate::Result<()> {
+        if handle.is_deleted.load(std::sync::atomic::Ordering::Acquire) {
+            return Err(crate::Error::PartitionDeleted);
+        }
+
+        {
+            let mut partitions = self.partitions.write().expect("lock is poisoned");
+
+            let name = handle.name();
+
+            if partitions.remove(&name).is_none() {
+                // NOTE: Already closed
+                return Ok(());
+            }
+
+            self.unloaded_partitions
+                .write()
+                .expect("lock is poisoned")
+                .insert(name, Arc::downgrade(&handle.0));
+        }
+
+        log::debug!("Closing partition {:?}", handle.name());
+
+        // NOTE: Flush the memtable, so the partition can be unloaded
+        // without keeping data in the journal alive
+        handle.rotate_memtable()?;
+
+        handle.wait_for_flush()?;
+
+        self.compaction_manager.remove_partition(handle.id());
+
+        // NOTE: The partition is unloaded once the last handle is dropped
+        drop(handle);
+
+        Ok(())
+    }
+
+    /// Returns the amount of partitions, including unloaded partitions
     #[must_use]
     pub fn partition_count(&self) -> usize {
-        self.partitions.read().expect("lock is poisoned").len()
+        let partitions = self.partitions.read().expect("lock is poisoned");
+        let unloaded_partitions = self.unloaded_partitions.read().expect("lock is poisoned");
+        partitions.len() + unloaded_partitions.len()
     }
 
-    /// Gets a list of all partition names in the keyspace
+    /// Gets a list of all partition names in the keyspace, including unloaded partitions
     #[must_use]
     pub fn list_partitions(&self) -> Vec<PartitionKey> {
-        self.partitions
-            .read()
-            .expect("lock is poisoned")
+        let partitions = self.partitions.read().expect("lock is poisoned");
+        let unloaded_partitions = self.unloaded_partitions.read().expect("lock is poisoned");
+
+        partitions
             .keys()
+            .chain(unloaded_partitions.keys())
             .cloned()
             .collect()
     }
@@ -421,6 +1018,71 @@ impl Keyspace {
             .read()
             .expect("lock is poisoned")
             .contains_key(name)
+            || self
+                .unloaded_partitions
+                .read()
+                .expect("lock is poisoned")
+                .contains_key(name)
+    }
+
+    /// Retrieves multiple items, possibly from different partitions.
+    ///
+    /// All keys are read from the same point in time. The keys of each partition are looked up
+    /// together, visiting each of its memtables and segments once (see [`PartitionHandle::multi_get`]),
+    /// and the values are returned in the order of the given keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let users = keyspace.open_partition("users", PartitionCreateOptions::default())?;
+    /// let orders = keyspace.open_partition("orders", PartitionCreateOptions::default())?;
+    /// users.insert("a", "user")?;
+    /// orders.insert("a", "order")?;
+    ///
+    /// let items = keyspace.multi_get(&[(&orders, "a"), (&users, "a"), (&users, "b")])?;
+    /// assert_eq!(
+    ///     vec![Some("order".as_bytes().into()), Some("user".as_bytes().into()), None],
+    ///     items,
+    /// );
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn multi_get<K: AsRef<[u8]>>(
+        &self,
+        keys: &[(&PartitionHandle, K)],
+    ) -> crate::Result<Vec<Option<UserValue>>> {
+        let instant = self.instant();
+
+        // NOTE: There are only a few partitions, so a list is fine
+        let mut lookups = Vec::<(&PartitionHandle, Vec<_>)>::new();
+
+        for (idx, (partition, key)) in keys.iter().enumerate() {
+            let lookup = (key.as_ref(), idx);
+
+            if let Some((_, partition_lookups)) = lookups.iter_mut().find(|(p, _)| p == partition) {
+                partition_lookups.push(lookup);
+            } else {
+                lookups.push((partition, vec![lookup]));
+            }
+        }
+
+        let mut results = Vec::with_capacity(keys.len());
+
+        for (partition, lookups) in lookups {
+            // NOTE: Keep the instant alive, so compaction does not drop versions that are read
+            let _snapshot = partition.snapshot_at(instant);
+            multi_get::get_at(partition, lookups, instant, &mut results)?;
+        }
+
+        Ok(multi_get::into_input_order(results))
     }
 
     /// Gets the current sequence number.
@@ -466,6 +1128,182 @@ impl Keyspace {
         self.seqno.get()
     }
 
+    /// Writes a dump of the given partitions into the given writer, returning the amount of items written.
+    ///
+    /// All partitions are dumped at the same point in time, so the dump is consistent
+    /// across partitions. Partition names and creation options are stored in the dump.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let partition1 = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let partition2 = keyspace.open_partition("another", PartitionCreateOptions::default())?;
+    ///
+    /// partition1.insert("a", "abc")?;
+    /// partition2.insert("b", "abc")?;
+    ///
+    /// let mut dump = vec![];
+    /// assert_eq!(2, keyspace.export(&[partition1, partition2], &mut dump)?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or a partition is deleted.
+    pub fn export<W: std::io::Write>(
+        &self,
+        partitions: &[PartitionHandle],
+        writer: W,
+    ) -> crate::Result<u64> {
+        crate::dump::export(partitions, self.instant(), writer)
+    }
+
+    /// Reads a dump created by [`Keyspace::export`] or [`PartitionHandle::export`]
+    /// into the keyspace, returning the amount of items imported.
+    ///
+    /// Partitions that do not exist yet are created using the creation options
+    /// stored in the dump, and bulk loaded directly into disk segments,
+    /// bypassing the journal. Partitions that already exist keep their options,
+    /// and the items are written using regular write batches.
+    ///
+    /// The dump is checksummed: if a corruption is detected, a partition created
+    /// by the import is removed again. An existing partition may have been partially
+    /// written to at that point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    ///
+    /// let mut dump = vec![];
+    /// keyspace.export(&[partition], &mut dump)?;
+    ///
+    /// # let folder = tempfile::tempdir()?;
+    /// let new_keyspace = Config::new(folder).open()?;
+    /// assert_eq!(1, new_keyspace.import(&*dump)?);
+    /// assert!(new_keyspace.partition_exists("default"));
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the dump is invalid.
+    pub fn import<R: std::io::Read>(&self, reader: R) -> crate::Result<u64> {
+        crate::dump::import(self, reader)
+    }
+
+    /// Starts shipping every batch committed from now on to the given writer,
+    /// returning the instant from which on batches are shipped.
+    ///
+    /// Batches are shipped with their sequence numbers, in sequence number order,
+    /// and can be applied to a follower keyspace using [`Keyspace::replicate_from`].
+    /// The follower needs to start from the same state as the primary at the
+    /// returned instant, e.g. both keyspaces being empty, or the follower being a copy
+    /// of the primary's folder, taken while the primary was closed.
+    ///
+    /// Writes block on the writer, so it should be fast, e.g. a [`crate::replication::pipe`].
+    /// If the writer fails, replication is stopped, but writes keep succeeding.
+    ///
+    /// Partition deletions are not replicated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// let (writer, reader) = fjall::replication::pipe();
+    ///
+    /// let instant = keyspace.replicate_to(writer);
+    /// assert_eq!(keyspace.instant(), instant);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the journal lock is poisoned.
+    pub fn replicate_to<W: std::io::Write + Send + 'static>(&self, writer: W) -> crate::Instant {
+        // NOTE: Lock all journal shards, so no batch is committed while the sink is installed
+        let _journal_lock = self.journal.shards.full_lock().expect("lock is poisoned");
+
+        self.replication.attach(Box::new(writer));
+
+        self.instant()
+    }
+
+    /// Stops shipping batches, closing the writer given to [`Keyspace::replicate_to`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the journal lock is poisoned.
+    pub fn stop_replication(&self) {
+        let _journal_lock = self.journal.shards.full_lock().expect("lock is poisoned");
+        self.replication.detach();
+    }
+
+    /// Applies batches shipped by a primary keyspace (see [`Keyspace::replicate_to`])
+    /// until the reader is exhausted, returning the amount of batches applied.
+    ///
+    /// Batches keep the sequence numbers they were given by the primary, so snapshots
+    /// on the follower see the same state as the primary at the same instant.
+    /// Batches below [`Keyspace::applied_instant`] were already applied and are skipped.
+    /// Partitions that do not exist yet are created using default options.
+    ///
+    /// The follower should not be written to otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let primary = Config::new(folder).open()?;
+    /// # let folder = tempfile::tempdir()?;
+    /// # let follower = Config::new(folder).open()?;
+    /// let (writer, reader) = fjall::replication::pipe();
+    /// primary.replicate_to(writer);
+    ///
+    /// let partition = primary.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// primary.stop_replication();
+    ///
+    /// assert_eq!(1, follower.replicate_from(reader)?);
+    ///
+    /// let partition = follower.open_partition("default", PartitionCreateOptions::default())?;
+    /// assert!(partition.contains_key("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the stream is invalid.
+    pub fn replicate_from<R: std::io::Read>(&self, reader: R) -> crate::Result<u64> {
+        crate::replication::apply(self, reader)
+    }
+
+    /// Returns the instant up to which batches of a primary keyspace have been applied.
+    ///
+    /// Comparing this to the primary's [`Keyspace::instant`] gives the replication lag.
+    #[must_use]
+    pub fn applied_instant(&self) -> crate::Instant {
+        self.applied_instant
+            .load(std::sync::atomic::Ordering::Acquire)
+    }
+
     fn check_version<P: AsRef<Path>>(path: P) -> crate::Result<()> {
         let bytes = std::fs::read(path.as_ref().join(FJALL_MARKER))?;
 
@@ -484,12 +1322,13 @@ impl Keyspace {
     #[allow(clippy::type_complexity)]
     fn find_active_journal<P: AsRef<Path>>(
         path: P,
-        recovery_mode: RecoveryMode,
+        config: &Config,
+        manifest: &KeyspaceManifest,
     ) -> crate::Result<(
         lsm_tree::SegmentId,
-        Option<(Journal, HashMap<PartitionKey, MemTable>)>,
+        Option<(Journal, HashMap<PartitionId, MemTable>)>,
     )> {
-        let mut journal = None;
+        let mut active_journal = None;
         let mut max_journal_id = 0;
 
         for dirent in std::fs::read_dir(path)? {
@@ -504,11 +1343,31 @@ impl Keyspace {
 
             max_journal_id = max_journal_id.max(journal_id);
 
-            if !dirent.path().join(FLUSH_MARKER).try_exists()? {
-                journal = Some(Journal::recover(dirent.path(), recovery_mode)?);
+            // NOTE: If there are multiple unsealed journals (e.g. after an interrupted
+            // point-in-time recovery), the newest one is the active journal
+            if !dirent.path().join(FLUSH_MARKER).try_exists()?
+                && active_journal
+                    .as_ref()
+                    .map_or(true, |(id, _)| journal_id > *id)
+            {
+                active_journal = Some((journal_id, dirent.path()));
             }
         }
 
+        let journal = active_journal
+            .map(|(_, path)| {
+                let legacy_snapshot = Manifest::read_legacy_snapshot(&path)?;
+
+                Journal::recover(
+                    path,
+                    &|name| manifest.resolve_legacy_name(legacy_snapshot.as_ref(), name),
+                    config.journal_recovery_mode,
+                    config.journal_shard_count,
+                    config.journal_preallocated_bytes,
+                )
+            })
+            .transpose()?;
+
         Ok((max_journal_id, journal))
     }
 
@@ -517,22 +1376,32 @@ impl Keyspace {
     #[doc(hidden)]
     pub fn recover(config: Config) -> crate::Result<Self> {
         log::info!("Recovering keyspace at {:?}", config.path);
-        let recovery_mode = config.journal_recovery_mode;
 
         // Check version
         Self::check_version(&config.path)?;
 
+        // Roll back to an earlier point in time, if requested
+        if let Some(instant) = config.recover_until {
+            recover_point_in_time(&config, instant)?;
+        }
+
+        // NOTE: The manifest is needed to map partition names of older journals to IDs
+        let manifest = KeyspaceManifest::recover(&config.path)?;
+
         // Get active journal if it exists
         let journals_folder = config.path.join(JOURNALS_FOLDER);
         let (max_journal_id, active_journal) =
-            Self::find_active_journal(&journals_folder, recovery_mode)?;
+            Self::find_active_journal(&journals_folder, &config, &manifest)?;
 
         let (journal, mut memtables) = if let Some((journal, memtables)) = active_journal {
             log::debug!("Recovered active journal at {:?}", journal.path);
             (journal, memtables)
         } else {
-            let journal =
-                Journal::create_new(journals_folder.join((max_journal_id + 1).to_string()))?;
+            let journal = Journal::create_new(
+                journals_folder.join((max_journal_id + 1).to_string()),
+                config.journal_shard_count,
+                config.journal_preallocated_bytes,
+            )?;
 
             let memtables = HashMap::default();
             (journal, memtables)
@@ -541,14 +1410,27 @@ impl Keyspace {
         let journal = Arc::new(journal);
         let journal_path = journal.path.clone();
 
-        let journal_manager = JournalManager::new(journal_path);
+        let journal_manager = JournalManager::new(
+            journal_path,
+            manifest.path(),
+            config.journal_archive_path.clone(),
+            config.active_journal_size(),
+        );
+
+        // NOTE: If there is no seqno marker, the keyspace was created before partitions
+        // were loaded lazily, so we need to open all partitions to restore the seqno
+        let persisted_seqno = SeqnoMarker::read(&config.path)?;
+        let seqno_marker = SeqnoMarker::new(&config.path, persisted_seqno.unwrap_or_default());
 
         // Construct (empty) keyspace, then fill back with partition data
         let inner = KeyspaceInner {
             config,
+            manifest: Arc::new(manifest),
             journal,
             partitions: Arc::new(RwLock::new(Partitions::with_capacity(10))),
-            seqno: SequenceNumberCounter::default(),
+            unloaded_partitions: Arc::default(),
+            seqno: SequenceNumberCounter::new(persisted_seqno.unwrap_or_default()),
+            seqno_marker: Arc::new(seqno_marker),
             flush_manager: Arc::default(),
             journal_manager: Arc::new(RwLock::new(journal_manager)),
             flush_semaphore: Arc::new(Semaphore::new(0)),
@@ -557,16 +1439,29 @@ impl Keyspace {
             active_background_threads: Arc::default(),
             write_buffer_manager: WriteBufferManager::default(),
             is_poisoned: Arc::default(),
+            replication: ReplicationSink::default(),
+            applied_instant: AtomicU64::default(),
+            obsolete_trees: Arc::default(),
         };
 
         let keyspace = Self(Arc::new(inner));
 
         // Recover partitions
-        recover_partitions(&keyspace, &mut memtables)?;
+        recover_partitions(&keyspace, &mut memtables, persisted_seqno.is_none())?;
 
         // Recover sealed memtables by walking through old journals
         recover_sealed_memtables(&keyspace)?;
 
+        if persisted_seqno.is_none() {
+            keyspace.seqno_marker.write(keyspace.instant())?;
+        }
+
+        // NOTE: Everything that was recovered counts as applied,
+        // so a follower can resume replicating after a restart
+        keyspace
+            .applied_instant
+            .store(keyspace.instant(), std::sync::atomic::Ordering::Release);
+
         Ok(keyspace)
     }
 
@@ -587,24 +1482,45 @@ impl Keyspace {
         std::fs::create_dir_all(&partition_folder_path)?;
 
         let active_journal_path = journal_folder_path.join("0");
-        let journal = Journal::create_new(&active_journal_path)?;
+        let journal = Journal::create_new(
+            &active_journal_path,
+            config.journal_shard_count,
+            config.journal_preallocated_bytes,
+        )?;
         let journal = Arc::new(journal);
 
+        let manifest = KeyspaceManifest::create_new(&path)?;
+
+        let journal_manager = JournalManager::new(
+            active_journal_path,
+            manifest.path(),
+            config.journal_archive_path.clone(),
+            config.active_journal_size(),
+        );
+
         let inner = KeyspaceInner {
             config,
+            manifest: Arc::new(manifest),
             journal,
             partitions: Arc::new(RwLock::new(Partitions::with_capacity(10))),
+            unloaded_partitions: Arc::default(),
             seqno: SequenceNumberCounter::default(),
+            seqno_marker: Arc::new(SeqnoMarker::new(&path, 0)),
             flush_manager: Arc::default(),
-            journal_manager: Arc::new(RwLock::new(JournalManager::new(active_journal_path))),
+            journal_manager: Arc::new(RwLock::new(journal_manager)),
             flush_semaphore: Arc::new(Semaphore::new(0)),
             compaction_manager: CompactionManager::default(),
             stop_signal: lsm_tree::stop_signal::StopSignal::default(),
             active_background_threads: Arc::default(),
             write_buffer_manager: WriteBufferManager::default(),
             is_poisoned: Arc::default(),
+            replication: ReplicationSink::default(),
+            applied_instant: AtomicU64::default(),
+            obsolete_trees: Arc::default(),
         };
 
+        inner.seqno_marker.write(0)?;
+
         // NOTE: Lastly, fsync .fjall marker, which contains the version
         // -> the keyspace is fully initialized
         let mut file = std::fs::File::create(marker_path)?;
@@ -653,6 +1569,7 @@ impl Keyspace {
                 log::trace!("fsync thread: fsycing journal");
                 if let Err(e) = journal.flush(PersistMode::SyncAll) {
                     is_poisoned.store(true, std::sync::atomic::Ordering::Release);
+                    journal.wake_durable_waiters();
                     log::error!(
                         "flush failed, which is a FATAL, and possibly hardware-related, failure: {e:?}"
                     );
@@ -689,7 +1606,8 @@ impl Keyspace {
     /// Should NOT be called when there is a flush worker active already!!!
     #[doc(hidden)]
     pub fn force_flush(&self) {
-        let parallelism = self.config.flush_workers_count;
+        // NOTE: Tests disable the flush worker, so use at least one thread
+        let parallelism = self.config.flush_workers_count.max(1);
 
         crate::flush::worker::run(
             &self.flush_manager,
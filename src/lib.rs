@@ -78,20 +78,34 @@
 #![warn(clippy::expect_used)]
 #![allow(clippy::missing_const_for_fn)]
 
+#[cfg(feature = "async")]
+mod async_api;
+
 mod batch;
 
 /// Contains compaction strategies
 pub mod compaction;
 
 mod config;
+mod cursor;
+mod dump;
 mod error;
 mod file;
 mod flush;
+mod item;
 mod journal;
 mod keyspace;
+mod manifest;
 mod monitor;
+mod obsolete_trees;
 mod partition;
+mod read_options;
 mod recovery;
+
+/// Contains transports for replicating from a primary to a follower keyspace
+pub mod replication;
+
+mod seqno_marker;
 mod sharded;
 
 #[cfg(feature = "single_writer_tx")]
@@ -99,27 +113,46 @@ mod tx;
 
 mod version;
 mod write_buffer_manager;
+mod write_options;
 
 pub use {
     batch::Batch,
     config::Config,
+    cursor::Cursor,
+    dump::DumpError,
     error::{Error, Result},
+    item::ValueVersion,
     journal::{shard::RecoveryError, writer::PersistMode},
     keyspace::Keyspace,
     partition::{config::CreateOptions as PartitionCreateOptions, PartitionHandle},
+    read_options::ReadOptions,
+    replication::ReplicationError,
+    write_options::WriteOptions,
 };
 
+#[cfg(feature = "async")]
+pub use async_api::{
+    batch::AsyncBatch, keyspace::AsyncKeyspace, partition::AsyncPartitionHandle, stream::KvStream,
+};
+
+#[doc(hidden)]
+pub use journal::{marker::Marker as JournalMarker, reader::JournalShardReader};
+
 #[cfg(feature = "single_writer_tx")]
 pub use tx::{
     keyspace::{TransactionalKeyspace, TxKeyspace},
     partition::TransactionalPartitionHandle,
     read_tx::ReadTransaction,
-    write_tx::WriteTransaction,
+    write_tx::{Savepoint, WriteTransaction},
 };
 
 /// Alias for [`PartitionHandle`]
 pub type Partition = PartitionHandle;
 
+/// Alias for [`AsyncPartitionHandle`]
+#[cfg(feature = "async")]
+pub type AsyncPartition = AsyncPartitionHandle;
+
 /// Alias for [`TransactionalPartitionHandle`]
 #[cfg(feature = "single_writer_tx")]
 pub type TxPartition = TransactionalPartitionHandle;
@@ -0,0 +1,622 @@
+use crate::{
+    batch::PartitionKey,
+    file::{
+        fsync_directory, JOURNALS_FOLDER, KEYSPACE_MANIFEST, LEGACY_JOURNAL_MANIFEST, LSM_MARKER,
+        PARTITIONS_FOLDER, PARTITION_DELETED_MARKER, PARTITION_UNJOURNALED_MARKER,
+    },
+    version::Version,
+};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+/// Numeric partition ID
+///
+/// Partitions are identified by their ID on disk (folder names, journals),
+/// so the partition name is not restricted by the file system.
+pub type PartitionId = u64;
+
+const FLAG_JOURNALED: u8 = 1;
+
+/// Metadata of a partition, as stored in the keyspace manifest
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartitionEntry {
+    /// Partition name
+    pub name: PartitionKey,
+
+    /// If `false`, writes skip the journal
+    pub journaled: bool,
+}
+
+/// Contents of the keyspace manifest
+///
+/// The manifest is the source of truth for which partitions exist:
+/// A partition is created once it is registered in the manifest,
+/// and deleted once it is removed from it.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Manifest {
+    /// ID that is assigned to the next partition
+    pub(crate) next_partition_id: PartitionId,
+
+    /// Registered partitions
+    pub(crate) partitions: BTreeMap<PartitionId, PartitionEntry>,
+}
+
+impl Manifest {
+    /// Finds the partition with the given name
+    pub fn find(&self, name: &str) -> Option<PartitionId> {
+        self.partitions
+            .iter()
+            .find(|(_, entry)| &*entry.name == name)
+            .map(|(id, _)| *id)
+    }
+
+    fn encode(&self) -> std::io::Result<Vec<u8>> {
+        let mut bytes = vec![];
+
+        Version::V1.write_file_header(&mut bytes)?;
+        bytes.write_u64::<BigEndian>(self.next_partition_id)?;
+
+        // NOTE: There are surely never > u32::MAX partitions
+        #[allow(clippy::cast_possible_truncation)]
+        bytes.write_u32::<BigEndian>(self.partitions.len() as u32)?;
+
+        for (id, entry) in &self.partitions {
+            bytes.write_u64::<BigEndian>(*id)?;
+            bytes.write_u8(if entry.journaled { FLAG_JOURNALED } else { 0 })?;
+
+            // NOTE: Partition names are at most 255 bytes long
+            #[allow(clippy::cast_possible_truncation)]
+            bytes.write_u8(entry.name.len() as u8)?;
+            bytes.write_all(entry.name.as_bytes())?;
+        }
+
+        let crc = crc32fast::hash(&bytes);
+        bytes.write_u32::<BigEndian>(crc)?;
+
+        Ok(bytes)
+    }
+
+    fn decode(bytes: &[u8]) -> crate::Result<Self> {
+        let Some(split) = bytes.len().checked_sub(std::mem::size_of::<u32>()) else {
+            return Err(crate::Error::InvalidManifest);
+        };
+        let (content, mut crc) = bytes.split_at(split);
+
+        if crc32fast::hash(content) != crc.read_u32::<BigEndian>()? {
+            log::error!("Keyspace manifest checksum check failed");
+            return Err(crate::Error::InvalidManifest);
+        }
+
+        let Some(Version::V1) = Version::parse_file_header(content) else {
+            return Err(crate::Error::InvalidManifest);
+        };
+
+        let mut reader = content
+            .get(usize::from(Version::len())..)
+            .ok_or(crate::Error::InvalidManifest)?;
+
+        let read = |reader: &mut &[u8]| -> std::io::Result<Self> {
+            let next_partition_id = reader.read_u64::<BigEndian>()?;
+            let partition_count = reader.read_u32::<BigEndian>()?;
+
+            let mut partitions = BTreeMap::new();
+
+            for _ in 0..partition_count {
+                let id = reader.read_u64::<BigEndian>()?;
+                let flags = reader.read_u8()?;
+
+                let name_len = reader.read_u8()?;
+                let mut name = vec![0; name_len.into()];
+                reader.read_exact(&mut name)?;
+
+                let name = String::from_utf8(name)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+                partitions.insert(
+                    id,
+                    PartitionEntry {
+                        name: name.into(),
+                        journaled: flags & FLAG_JOURNALED != 0,
+                    },
+                );
+            }
+
+            Ok(Self {
+                next_partition_id,
+                partitions,
+            })
+        };
+
+        read(&mut reader).map_err(|e| {
+            log::error!("Invalid keyspace manifest: {e:?}");
+            crate::Error::InvalidManifest
+        })
+    }
+
+    /// Reads a manifest file
+    pub fn read_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let mut bytes = vec![];
+        File::open(path)?.read_to_end(&mut bytes)?;
+        Self::decode(&bytes)
+    }
+
+    /// Reads the manifest of the keyspace in the given folder
+    ///
+    /// Returns `None` if the keyspace was created before the manifest existed.
+    fn read<P: AsRef<Path>>(folder: P) -> crate::Result<Option<Self>> {
+        let path = folder.as_ref().join(KEYSPACE_MANIFEST);
+
+        if !path.try_exists()? {
+            return Ok(None);
+        }
+
+        Self::read_file(path).map(Some)
+    }
+
+    /// Reads the snapshot of partition names of a journal that was written before partitions had IDs
+    ///
+    /// Returns `None` if the journal has no snapshot.
+    pub fn read_legacy_snapshot(journal_path: &Path) -> crate::Result<Option<Self>> {
+        let path = journal_path.join(LEGACY_JOURNAL_MANIFEST);
+
+        if !path.try_exists()? {
+            return Ok(None);
+        }
+
+        Self::read_file(path).map(Some)
+    }
+
+    /// Atomically replaces the manifest of the keyspace in the given folder
+    pub fn write<P: AsRef<Path>>(&self, folder: P) -> crate::Result<()> {
+        self.write_file(folder.as_ref(), KEYSPACE_MANIFEST)
+    }
+
+    /// Atomically replaces the manifest file with the given name in the given folder
+    fn write_file(&self, folder: &Path, file_name: &str) -> crate::Result<()> {
+        let path = folder.join(file_name);
+        let tmp_path = folder.join(format!("{file_name}.tmp"));
+
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&self.encode()?)?;
+        file.flush()?;
+        file.sync_all()?;
+
+        // IMPORTANT: Rename, so the manifest is replaced atomically
+        std::fs::rename(&tmp_path, path)?;
+
+        // IMPORTANT: fsync folder on Unix
+        fsync_directory(folder)?;
+
+        Ok(())
+    }
+
+    /// Builds a manifest from the partition folders of a keyspace
+    /// that was created before the manifest existed
+    ///
+    /// Those partition folders are named by the partition name.
+    fn from_legacy_partitions(partitions_folder: &Path) -> crate::Result<Self> {
+        let mut first_id = 0;
+        let mut legacy_partitions = vec![];
+
+        for dirent in std::fs::read_dir(partitions_folder)? {
+            let dirent = dirent?;
+            let path = dirent.path();
+
+            let name = dirent.file_name();
+            let name = name.to_str().expect("should be valid partition name");
+
+            // IMPORTANT: IDs need to be higher than every numeric folder name,
+            // so moving a legacy folder to its ID never collides with another folder
+            if let Ok(id) = name.parse::<PartitionId>() {
+                first_id = first_id.max(id.saturating_add(1));
+            }
+
+            // NOTE: Deleted & uninitialized partitions are not migrated,
+            // their folders are removed afterwards
+            if path.join(PARTITION_DELETED_MARKER).try_exists()?
+                || !path.join(LSM_MARKER).try_exists()?
+            {
+                continue;
+            }
+
+            let journaled = !path.join(PARTITION_UNJOURNALED_MARKER).try_exists()?;
+
+            legacy_partitions.push(PartitionEntry {
+                name: name.into(),
+                journaled,
+            });
+        }
+
+        legacy_partitions.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut manifest = Self {
+            next_partition_id: first_id,
+            partitions: BTreeMap::new(),
+        };
+
+        for entry in legacy_partitions {
+            log::debug!(
+                "Migrating partition {:?} to ID {}",
+                entry.name,
+                manifest.next_partition_id
+            );
+
+            manifest
+                .partitions
+                .insert(manifest.next_partition_id, entry);
+            manifest.next_partition_id += 1;
+        }
+
+        Ok(manifest)
+    }
+
+    /// Recovers the manifest of the keyspace in the given folder
+    ///
+    /// Keyspaces created before the manifest existed are migrated.
+    /// Partition folders that are not registered (deleted, or not fully created partitions)
+    /// are removed.
+    pub fn recover<P: AsRef<Path>>(folder: P) -> crate::Result<Self> {
+        let folder = folder.as_ref();
+        let partitions_folder = folder.join(PARTITIONS_FOLDER);
+
+        let manifest = if let Some(manifest) = Self::read(folder)? {
+            manifest
+        } else {
+            log::info!(
+                "Migrating keyspace at {} to partition IDs",
+                folder.display()
+            );
+
+            let manifest = Self::from_legacy_partitions(&partitions_folder)?;
+
+            // IMPORTANT: Journals written before the migration reference partitions by name,
+            // so store the current names in each of them, before a partition can be renamed
+            let journals_folder = folder.join(JOURNALS_FOLDER);
+
+            if journals_folder.try_exists()? {
+                for dirent in std::fs::read_dir(journals_folder)? {
+                    manifest.write_file(&dirent?.path(), LEGACY_JOURNAL_MANIFEST)?;
+                }
+            }
+
+            manifest.write(folder)?;
+            manifest
+        };
+
+        // NOTE: Move legacy partition folders, the migration may have been interrupted
+        for (id, entry) in &manifest.partitions {
+            let path = partitions_folder.join(id.to_string());
+
+            if path.try_exists()? {
+                continue;
+            }
+
+            let legacy_path = partitions_folder.join(&*entry.name);
+
+            if legacy_path.try_exists()? {
+                log::debug!(
+                    "Moving partition folder {} to {}",
+                    legacy_path.display(),
+                    path.display()
+                );
+                std::fs::rename(legacy_path, path)?;
+            }
+        }
+
+        for dirent in std::fs::read_dir(&partitions_folder)? {
+            let dirent = dirent?;
+            let folder_name = dirent.file_name();
+
+            let is_registered = folder_name
+                .to_str()
+                .and_then(|name| {
+                    name.parse::<PartitionId>()
+                        .ok()
+                        .filter(|id| id.to_string() == name)
+                })
+                .is_some_and(|id| manifest.partitions.contains_key(&id));
+
+            if !is_registered {
+                log::debug!("Deleting unregistered partition folder {folder_name:?}");
+                std::fs::remove_dir_all(dirent.path())?;
+            }
+        }
+
+        // IMPORTANT: fsync folder on Unix
+        fsync_directory(&partitions_folder)?;
+
+        Ok(manifest)
+    }
+}
+
+/// Keyspace manifest, which maps partition IDs to partition names & metadata
+///
+/// Every change is written to disk atomically.
+#[allow(clippy::module_name_repetitions)]
+pub struct KeyspaceManifest {
+    folder: PathBuf,
+    inner: RwLock<Manifest>,
+}
+
+impl KeyspaceManifest {
+    /// Writes an empty manifest for a new keyspace
+    pub fn create_new<P: AsRef<Path>>(folder: P) -> crate::Result<Self> {
+        let manifest = Manifest::default();
+        manifest.write(&folder)?;
+
+        Ok(Self {
+            folder: folder.as_ref().to_path_buf(),
+            inner: RwLock::new(manifest),
+        })
+    }
+
+    /// Recovers the manifest of an existing keyspace, see [`Manifest::recover`]
+    pub fn recover<P: AsRef<Path>>(folder: P) -> crate::Result<Self> {
+        Ok(Self {
+            folder: folder.as_ref().to_path_buf(),
+            inner: RwLock::new(Manifest::recover(&folder)?),
+        })
+    }
+
+    /// Returns the path of the manifest file
+    pub fn path(&self) -> PathBuf {
+        self.folder.join(KEYSPACE_MANIFEST)
+    }
+
+    /// Returns the metadata of the partition with the given ID
+    pub fn get(&self, id: PartitionId) -> Option<PartitionEntry> {
+        self.inner
+            .read()
+            .expect("lock is poisoned")
+            .partitions
+            .get(&id)
+            .cloned()
+    }
+
+    /// Finds the partition with the given name
+    pub fn find(&self, name: &str) -> Option<(PartitionId, PartitionEntry)> {
+        let manifest = self.inner.read().expect("lock is poisoned");
+
+        manifest.find(name).and_then(|id| {
+            manifest
+                .partitions
+                .get(&id)
+                .map(|entry| (id, entry.clone()))
+        })
+    }
+
+    /// Finds the partition that a journal written before partitions had IDs references by name
+    ///
+    /// If the journal has a snapshot of the partition names (see [`Manifest::read_legacy_snapshot`]),
+    /// it is used, so partitions that have been renamed since are still resolved correctly.
+    pub fn resolve_legacy_name(
+        &self,
+        legacy_snapshot: Option<&Manifest>,
+        name: &str,
+    ) -> Option<PartitionId> {
+        legacy_snapshot.map_or_else(
+            || self.find(name).map(|(id, _)| id),
+            |snapshot| snapshot.find(name),
+        )
+    }
+
+    /// Returns all registered partitions
+    pub fn list(&self) -> Vec<(PartitionId, PartitionEntry)> {
+        self.inner
+            .read()
+            .expect("lock is poisoned")
+            .partitions
+            .iter()
+            .map(|(id, entry)| (*id, entry.clone()))
+            .collect()
+    }
+
+    /// Reserves an ID for a new partition
+    ///
+    /// The ID is only persisted once the partition is registered. If the partition
+    /// is never registered (e.g. because of a crash), the ID may be reused after recovery.
+    pub fn allocate_id(&self) -> PartitionId {
+        let mut manifest = self.inner.write().expect("lock is poisoned");
+        let id = manifest.next_partition_id;
+        manifest.next_partition_id += 1;
+        id
+    }
+
+    /// Registers a partition, which atomically creates it
+    pub fn insert(&self, id: PartitionId, entry: PartitionEntry) -> crate::Result<()> {
+        let mut manifest = self.inner.write().expect("lock is poisoned");
+
+        let prev = manifest.partitions.insert(id, entry);
+
+        if let Err(e) = manifest.write(&self.folder) {
+            if let Some(prev) = prev {
+                manifest.partitions.insert(id, prev);
+            } else {
+                manifest.partitions.remove(&id);
+            }
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Changes the name of a registered partition
+    pub fn rename(&self, id: PartitionId, name: PartitionKey) -> crate::Result<()> {
+        let mut manifest = self.inner.write().expect("lock is poisoned");
+
+        let Some(entry) = manifest.partitions.get_mut(&id) else {
+            return Err(crate::Error::PartitionDeleted);
+        };
+        let prev = std::mem::replace(&mut entry.name, name);
+
+        if let Err(e) = manifest.write(&self.folder) {
+            if let Some(entry) = manifest.partitions.get_mut(&id) {
+                entry.name = prev;
+            }
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Exchanges the names of two registered partitions
+    pub fn swap(&self, a: PartitionId, b: PartitionId) -> crate::Result<()> {
+        let mut manifest = self.inner.write().expect("lock is poisoned");
+
+        let (Some(a_name), Some(b_name)) = (
+            manifest.partitions.get(&a).map(|x| x.name.clone()),
+            manifest.partitions.get(&b).map(|x| x.name.clone()),
+        ) else {
+            return Err(crate::Error::PartitionDeleted);
+        };
+
+        let set_names = |manifest: &mut Manifest, a_name: PartitionKey, b_name: PartitionKey| {
+            if let Some(entry) = manifest.partitions.get_mut(&a) {
+                entry.name = a_name;
+            }
+            if let Some(entry) = manifest.partitions.get_mut(&b) {
+                entry.name = b_name;
+            }
+        };
+
+        set_names(&mut manifest, b_name.clone(), a_name.clone());
+
+        if let Err(e) = manifest.write(&self.folder) {
+            set_names(&mut manifest, a_name, b_name);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Gives the name of the target partition to the source partition,
+    /// and unregisters the target partition
+    pub fn replace(&self, target: PartitionId, source: PartitionId) -> crate::Result<()> {
+        let mut manifest = self.inner.write().expect("lock is poisoned");
+
+        if !manifest.partitions.contains_key(&source) {
+            return Err(crate::Error::PartitionDeleted);
+        }
+
+        let Some(prev_target) = manifest.partitions.remove(&target) else {
+            return Err(crate::Error::PartitionDeleted);
+        };
+
+        let prev_source_name = manifest
+            .partitions
+            .get_mut(&source)
+            .map(|entry| std::mem::replace(&mut entry.name, prev_target.name.clone()));
+
+        if let Err(e) = manifest.write(&self.folder) {
+            if let (Some(entry), Some(name)) =
+                (manifest.partitions.get_mut(&source), prev_source_name)
+            {
+                entry.name = name;
+            }
+            manifest.partitions.insert(target, prev_target);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Registers a partition under a new ID, which atomically drops the data of its old ID
+    pub fn reassign(&self, old_id: PartitionId, new_id: PartitionId) -> crate::Result<()> {
+        let mut manifest = self.inner.write().expect("lock is poisoned");
+
+        let Some(entry) = manifest.partitions.remove(&old_id) else {
+            return Err(crate::Error::PartitionDeleted);
+        };
+        manifest.partitions.insert(new_id, entry);
+
+        if let Err(e) = manifest.write(&self.folder) {
+            if let Some(entry) = manifest.partitions.remove(&new_id) {
+                manifest.partitions.insert(old_id, entry);
+            }
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Unregisters a partition, which atomically deletes it
+    pub fn remove(&self, id: PartitionId) -> crate::Result<()> {
+        let mut manifest = self.inner.write().expect("lock is poisoned");
+
+        let Some(prev) = manifest.partitions.remove(&id) else {
+            return Ok(());
+        };
+
+        if let Err(e) = manifest.write(&self.folder) {
+            manifest.partitions.insert(id, prev);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn manifest_serde_round_trip() -> crate::Result<()> {
+        let mut manifest = Manifest {
+            next_partition_id: 3,
+            partitions: BTreeMap::new(),
+        };
+        manifest.partitions.insert(
+            0,
+            PartitionEntry {
+                name: "default".into(),
+                journaled: true,
+            },
+        );
+        manifest.partitions.insert(
+            2,
+            PartitionEntry {
+                name: "größe: 10 / ☃".into(),
+                journaled: false,
+            },
+        );
+
+        let bytes = manifest.encode()?;
+        assert_eq!(manifest, Manifest::decode(&bytes)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn manifest_checksum_mismatch() -> crate::Result<()> {
+        let mut manifest = Manifest::default();
+        manifest.partitions.insert(
+            0,
+            PartitionEntry {
+                name: "default".into(),
+                journaled: true,
+            },
+        );
+
+        let mut bytes = manifest.encode()?;
+        *bytes.get_mut(10).expect("should exist") ^= 1;
+
+        assert!(matches!(
+            Manifest::decode(&bytes),
+            Err(crate::Error::InvalidManifest)
+        ));
+        assert!(matches!(
+            Manifest::decode(&[]),
+            Err(crate::Error::InvalidManifest)
+        ));
+
+        Ok(())
+    }
+}
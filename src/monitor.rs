@@ -1,6 +1,6 @@
 use crate::{
     config::Config as KeyspaceConfig, flush::manager::FlushManager,
-    journal::manager::JournalManager, keyspace::Partitions,
+    journal::manager::JournalManager, keyspace::Partitions, obsolete_trees::ObsoleteTrees,
     write_buffer_manager::WriteBufferManager, Keyspace,
 };
 use std::sync::{Arc, RwLock};
@@ -12,6 +12,7 @@ pub struct Monitor {
     pub(crate) journal_manager: Arc<RwLock<JournalManager>>,
     pub(crate) write_buffer_manager: WriteBufferManager,
     pub(crate) partitions: Arc<RwLock<Partitions>>,
+    pub(crate) obsolete_trees: Arc<ObsoleteTrees>,
 }
 
 impl Monitor {
@@ -22,14 +23,17 @@ impl Monitor {
             keyspace_config: keyspace.config.clone(),
             write_buffer_manager: keyspace.write_buffer_manager.clone(),
             partitions: keyspace.partitions.clone(),
+            obsolete_trees: keyspace.obsolete_trees.clone(),
         }
     }
 
     pub fn run(&self) -> bool {
         let mut idle = true;
 
+        self.obsolete_trees.cleanup();
+
         let journal_manager = self.journal_manager.read().expect("lock is poisoned");
-        let size = journal_manager.disk_space_used();
+        let size = journal_manager.sealed_disk_space_used();
 
         if size as f64 > (self.keyspace_config.max_journaling_size_in_bytes as f64 * 0.5) {
             idle = false;
@@ -43,7 +47,7 @@ impl Monitor {
 
             // NOTE: Don't try to flush partitions that are already enqueued in the flush manager
             // to prevent a flush storm once the threshold is reached
-            let partition_names_with_queued_tasks = self
+            let partition_ids_with_queued_tasks = self
                 .flush_manager
                 .read()
                 .expect("lock is poisoned")
@@ -51,15 +55,15 @@ impl Monitor {
 
             let partitions = partitions
                 .into_iter()
-                .filter(|x| !partition_names_with_queued_tasks.contains(&x.name));
+                .filter(|x| !partition_ids_with_queued_tasks.contains(&x.id()));
 
             for partition in partitions {
-                log::debug!("monitor: JM rotating {:?}", partition.name);
+                log::debug!("monitor: JM rotating {:?}", partition.name());
 
                 if let Err(e) = partition.rotate_memtable() {
                     log::error!(
                         "monitor: memtable rotation failed for {:?}: {e:?}",
-                        partition.name
+                        partition.name()
                     );
                 };
             }
@@ -110,12 +114,12 @@ impl Monitor {
                 .collect::<Vec<_>>();
 
             partitions.sort_by(|a, b| {
-                b.tree
+                b.tree()
                     .active_memtable_size()
-                    .cmp(&a.tree.active_memtable_size())
+                    .cmp(&a.tree().active_memtable_size())
             });
 
-            let partitions_names_with_queued_tasks = self
+            let partition_ids_with_queued_tasks = self
                 .flush_manager
                 .read()
                 .expect("lock is poisoned")
@@ -123,10 +127,10 @@ impl Monitor {
 
             let partitions = partitions
                 .into_iter()
-                .filter(|x| !partitions_names_with_queued_tasks.contains(&x.name));
+                .filter(|x| !partition_ids_with_queued_tasks.contains(&x.id()));
 
             for partition in partitions {
-                log::debug!("monitor: WB rotating {:?}", partition.name);
+                log::debug!("monitor: WB rotating {:?}", partition.name());
 
                 match partition.rotate_memtable() {
                     Ok(rotated) => {
@@ -137,7 +141,7 @@ impl Monitor {
                     Err(e) => {
                         log::error!(
                             "monitor: memtable rotation failed for {:?}: {e:?}",
-                            partition.name
+                            partition.name()
                         );
                     }
                 };
@@ -0,0 +1,56 @@
+use lsm_tree::{levels::LevelManifest, Tree as LsmTree};
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock, Weak},
+};
+
+/// Keeps track of trees that have been detached from their partition (e.g. by clearing it)
+///
+/// Iterators, snapshots, flushes and compactions may still read from or write to a detached tree,
+/// so its folder is only removed once the last reference to the tree has been dropped.
+///
+/// Folders that are left over after a crash are not registered in the manifest anymore,
+/// so they are removed on recovery.
+#[derive(Default)]
+pub struct ObsoleteTrees(Mutex<Vec<(Weak<RwLock<LevelManifest>>, PathBuf)>>);
+
+impl ObsoleteTrees {
+    /// Registers a detached tree, whose folder is removed once the tree is not used anymore
+    pub fn register(&self, tree: &LsmTree) {
+        // NOTE: Every handle, iterator and snapshot of a tree holds its level manifest
+        let levels = Arc::downgrade(&tree.levels);
+
+        self.0
+            .lock()
+            .expect("lock is poisoned")
+            .push((levels, tree.config.path.clone()));
+    }
+
+    /// Removes the folders of detached trees that are not used anymore
+    ///
+    /// Returns the amount of removed folders.
+    pub fn cleanup(&self) -> usize {
+        let mut trees = self.0.lock().expect("lock is poisoned");
+        let len = trees.len();
+
+        trees.retain(|(levels, path)| {
+            if levels.strong_count() > 0 {
+                return true;
+            }
+
+            log::debug!("Removing folder of detached tree at {}", path.display());
+
+            // NOTE: If this fails, the folder is removed on recovery, because it is not registered anymore
+            if let Err(e) = std::fs::remove_dir_all(path) {
+                log::error!(
+                    "Failed to cleanup detached tree's folder at {}: {e}",
+                    path.display()
+                );
+            }
+
+            false
+        });
+
+        len - trees.len()
+    }
+}
@@ -0,0 +1,192 @@
+use super::{
+    read::{internal_bounds, memtable_range, sealed_memtables, Bounds},
+    PartitionHandle, PartitionSlot,
+};
+use lsm_tree::{
+    segment::{block_index::block_handle::KeyedBlockHandle, value_block::CachePolicy},
+    Segment,
+};
+use std::ops::{
+    Bound::{Excluded, Included, Unbounded},
+    RangeBounds,
+};
+
+/// Estimated size and item count of a key range
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Estimate {
+    /// Uncompressed size in bytes
+    pub size: u64,
+
+    /// Amount of items, including old versions and tombstones
+    pub count: u64,
+}
+
+/// Returns `true` if a block holding keys in `(lo, hi]` may contain keys inside the bounds
+///
+/// If `lo` is `None`, the block has no lower limit.
+fn block_overlaps(lo: Option<&[u8]>, hi: &[u8], bounds: &Bounds) -> bool {
+    let starts_before_end = match &bounds.0 {
+        Included(key) => **key <= *hi,
+        Excluded(key) => **key < *hi,
+        Unbounded => true,
+    };
+
+    starts_before_end && !block_is_after(lo, bounds)
+}
+
+/// Returns `true` if a block starting after `lo` only contains keys past the bounds
+fn block_is_after(lo: Option<&[u8]>, bounds: &Bounds) -> bool {
+    match (lo, &bounds.1) {
+        (Some(lo), Included(key) | Excluded(key)) => **key <= *lo,
+        _ => false,
+    }
+}
+
+/// Returns `true` if a block ending at `hi` does not contain keys past the bounds
+fn block_ends_inside(hi: &[u8], bounds: &Bounds) -> bool {
+    match &bounds.1 {
+        Included(key) => *hi <= **key,
+        Excluded(key) => *hi < **key,
+        Unbounded => true,
+    }
+}
+
+/// Estimates the part of a segment inside the bounds, using its block index
+///
+/// Only index blocks that cover the bounds are loaded; data blocks are never read.
+fn estimate_segment(segment: &Segment, bounds: &Bounds) -> lsm_tree::Result<Estimate> {
+    let metadata = &segment.metadata;
+    let (min, max) = &*metadata.key_range;
+
+    if bounds.contains(min) && bounds.contains(max) {
+        return Ok(Estimate {
+            size: metadata.uncompressed_size,
+            count: metadata.item_count,
+        });
+    }
+
+    // NOTE: Data blocks are written back-to-back, so the blocks overlapping
+    // the bounds span the file from `start` to `end`
+    let mut start = None;
+    let mut end = None;
+
+    let block_index = &segment.block_index;
+    let mut index_block_handle = Some(block_index.get_first_index_block_handle());
+    let mut lo: Option<&[u8]> = None;
+
+    while let Some(handle) = index_block_handle {
+        if block_is_after(lo, bounds) {
+            if start.is_some() {
+                let index_block = block_index.load_index_block(handle, CachePolicy::Write)?;
+                end = index_block.items.first().map(|handle| handle.offset);
+            }
+            break;
+        }
+
+        // NOTE: Once the start is known, index blocks that end inside the bounds
+        // do not need to be loaded, as all their data blocks overlap
+        if start.is_some() && block_ends_inside(&handle.end_key, bounds) {
+            lo = Some(&handle.end_key);
+            index_block_handle = block_index.get_next_index_block_handle(handle);
+            continue;
+        }
+
+        if block_overlaps(lo, &handle.end_key, bounds) {
+            let index_block = block_index.load_index_block(handle, CachePolicy::Write)?;
+            let mut data_lo = lo;
+
+            for KeyedBlockHandle { end_key, offset } in &*index_block.items {
+                if block_overlaps(data_lo, end_key, bounds) {
+                    start.get_or_insert(*offset);
+                } else if start.is_some() {
+                    end = Some(*offset);
+                    break;
+                }
+
+                data_lo = Some(end_key);
+            }
+
+            if end.is_some() {
+                break;
+            }
+        }
+
+        lo = Some(&handle.end_key);
+        index_block_handle = block_index.get_next_index_block_handle(handle);
+    }
+
+    let Some(start) = start else {
+        return Ok(Estimate::default());
+    };
+
+    if metadata.file_size == 0 {
+        return Ok(Estimate::default());
+    }
+
+    // NOTE: The file size only covers the data blocks
+    let end = end.unwrap_or(metadata.file_size);
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    let scale = |total: u64| {
+        ((end - start) as f64 / metadata.file_size as f64 * total as f64).ceil() as u64
+    };
+
+    Ok(Estimate {
+        size: scale(metadata.uncompressed_size),
+        count: scale(metadata.item_count),
+    })
+}
+
+/// Estimates the size and item count of a key range of a partition.
+///
+/// Segments are estimated from their metadata and block index,
+/// memtables are counted, as they are kept in memory.
+pub fn estimate(
+    partition: &PartitionHandle,
+    slot: &PartitionSlot,
+    bounds: &Bounds,
+) -> crate::Result<Estimate> {
+    let mut estimate = Estimate::default();
+
+    // NOTE: Lock order: flush manager -> levels -> active memtable
+    let flush_manager = partition.flush_manager.read().expect("lock is poisoned");
+    let levels = slot.tree.levels.read().expect("lock is poisoned");
+
+    let memtables = sealed_memtables(&flush_manager, slot, &levels);
+
+    let segments = levels
+        .iter()
+        .filter(|segment| segment.metadata.key_range.overlaps_with_bounds(bounds))
+        .collect::<Vec<_>>();
+
+    drop(levels);
+    drop(flush_manager);
+
+    for segment in segments {
+        let segment_estimate = estimate_segment(&segment, bounds)?;
+        estimate.size += segment_estimate.size;
+        estimate.count += segment_estimate.count;
+    }
+
+    let bounds = internal_bounds(bounds);
+
+    for memtable in &memtables {
+        for item in memtable_range(memtable, &bounds) {
+            estimate.size += item.size() as u64;
+            estimate.count += 1;
+        }
+    }
+
+    let active_memtable = slot.tree.lock_active_memtable();
+
+    for item in memtable_range(&active_memtable, &bounds) {
+        estimate.size += item.size() as u64;
+        estimate.count += 1;
+    }
+
+    Ok(estimate)
+}
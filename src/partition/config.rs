@@ -17,6 +17,11 @@ pub struct CreateOptions {
     ///
     /// A level target size is: `max_memtable_size * level_ratio.pow(#level + 1)`
     pub(crate) level_ratio: u8,
+
+    /// If `false`, writes to the partition skip the journal
+    ///
+    /// Once set for a partition, this property is not considered in the future.
+    pub(crate) journaled: bool,
 }
 
 impl Default for CreateOptions {
@@ -27,6 +32,7 @@ impl Default for CreateOptions {
             block_size: default_tree_config.inner.block_size,
             level_count: default_tree_config.inner.level_count,
             level_ratio: default_tree_config.level_ratio,
+            journaled: true,
         }
     }
 }
@@ -76,4 +82,21 @@ impl CreateOptions {
         self.level_count = n;
         self
     }
+
+    /// Sets whether writes to the partition are journaled.
+    ///
+    /// Writes to a non-journaled partition skip the journal, so they
+    /// are cheaper and do not count towards the maximum journaling size.
+    /// However, after a crash, the partition recovers to the state of
+    /// its last flushed segments. On a clean shutdown, the partition's
+    /// memtables are flushed, so no data is lost.
+    ///
+    /// Useful for caches or derived indexes, which can be rebuilt.
+    ///
+    /// Default = true
+    #[must_use]
+    pub fn journaled(mut self, journaled: bool) -> Self {
+        self.journaled = journaled;
+        self
+    }
 }
@@ -0,0 +1,153 @@
+use super::{read::MemtableReaders, PartitionHandle, PartitionSlot};
+use crate::file::{fsync_directory, SEGMENTS_FOLDER};
+use lsm_tree::{file::LEVELS_MANIFEST_FILE, Segment, Tree as LsmTree};
+use std::{collections::HashSet, path::Path, sync::Arc};
+
+/// Links (or copies, if linking is not supported) a segment file
+fn link_segment(source: &Path, target: &Path) -> std::io::Result<()> {
+    if let Err(e) = std::fs::hard_link(source, target) {
+        log::debug!(
+            "Could not link segment file {}, copying instead: {e}",
+            source.display()
+        );
+
+        std::fs::copy(source, target)?;
+        std::fs::File::open(target)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
+/// Creates a copy-on-write clone of the partition's tree in the given folder
+///
+/// The clone shares the immutable segments of the source,
+/// and contains every write that was sealed when the fork was started.
+///
+/// Returns the slot of the clone, using the given partition ID.
+pub fn fork_tree(
+    source: &PartitionHandle,
+    id: crate::manifest::PartitionId,
+    path: &Path,
+) -> crate::Result<PartitionSlot> {
+    let config = source.tree().config.clone();
+
+    // NOTE: Initialize the folder structure (marker, config, segments folder) of the clone
+    drop(
+        lsm_tree::Config::new(path)
+            .descriptor_table(config.descriptor_table.clone())
+            .block_cache(config.block_cache.clone())
+            .block_size(config.inner.block_size)
+            .level_count(config.inner.level_count)
+            .level_ratio(config.level_ratio)
+            .open()?,
+    );
+
+    // IMPORTANT: Lock the journal, so no writes can be sealed into
+    // the source's memtables while we gather them
+    log::trace!("fork: acquiring full write lock");
+    let mut journal = source.journal.shards.full_lock().expect("lock is poisoned");
+
+    if source.is_deleted.load(std::sync::atomic::Ordering::Acquire) {
+        return Err(crate::Error::PartitionDeleted);
+    }
+
+    // NOTE: Seal the active memtable, so the fork point is made up of
+    // segments and sealed memtables only, which are immutable
+    source.rotate_memtable_locked(&mut journal)?;
+
+    let source_slot = source.slot();
+    let source_tree = source_slot.tree.clone();
+
+    // NOTE: Lock order: flush manager -> levels
+    let flush_manager = source.flush_manager.read().expect("lock is poisoned");
+
+    // IMPORTANT: Keep the levels locked until all segments are linked,
+    // so they are not removed by a concurrent compaction
+    let levels = source_tree.levels.read().expect("lock is poisoned");
+
+    let segment_ids = levels
+        .iter()
+        .map(|segment| segment.metadata.id)
+        .collect::<HashSet<_>>();
+
+    // NOTE: A memtable may have been flushed and registered as a segment already,
+    // while its flush task is not dequeued yet
+    let sealed_memtables = flush_manager
+        .queues
+        .get(&source_slot.id)
+        .map(|queue| {
+            queue
+                .iter()
+                .filter(|task| !segment_ids.contains(&task.id))
+                .map(|task| task.sealed_memtable.clone())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    drop(flush_manager);
+    drop(journal);
+
+    let source_segments_folder = source_tree.config.path.join(SEGMENTS_FOLDER);
+    let segments_folder = path.join(SEGMENTS_FOLDER);
+
+    for segment_id in &segment_ids {
+        link_segment(
+            &source_segments_folder.join(segment_id.to_string()),
+            &segments_folder.join(segment_id.to_string()),
+        )?;
+    }
+    fsync_directory(&segments_folder)?;
+
+    // NOTE: Copy the level manifest, so the clone has the same level structure
+    let level_manifest = std::fs::read(source_tree.config.path.join(LEVELS_MANIFEST_FILE))?;
+    drop(levels);
+
+    lsm_tree::file::rewrite_atomic(path.join(LEVELS_MANIFEST_FILE), &level_manifest)?;
+    fsync_directory(path)?;
+
+    let tree = lsm_tree::Config::new(path)
+        .descriptor_table(config.descriptor_table.clone())
+        .block_cache(config.block_cache)
+        .open()?;
+
+    flush_sealed_memtables(&tree, sealed_memtables)?;
+
+    Ok(PartitionSlot {
+        id,
+        is_journaled: source_slot.is_journaled,
+        tree,
+        write_lock: Arc::default(),
+        memtable_readers: MemtableReaders::default(),
+    })
+}
+
+/// Flushes the sealed memtables of the source into segments of the clone
+fn flush_sealed_memtables(
+    tree: &LsmTree,
+    sealed_memtables: Vec<Arc<lsm_tree::MemTable>>,
+) -> crate::Result<()> {
+    use lsm_tree::flush::Options;
+
+    let segments = sealed_memtables
+        .into_iter()
+        .map(|memtable| {
+            let segment = lsm_tree::flush::flush_to_segment(Options {
+                tree_id: tree.id,
+                segment_id: tree.get_next_segment_id(),
+                memtable,
+                folder: tree.config.path.join(SEGMENTS_FOLDER),
+                block_size: tree.config.inner.block_size,
+                block_cache: tree.config.block_cache.clone(),
+                descriptor_table: tree.config.descriptor_table.clone(),
+            })?;
+
+            Ok(Arc::new(segment))
+        })
+        .collect::<crate::Result<Vec<Arc<Segment>>>>()?;
+
+    // NOTE: The sealed memtables are newer than every segment,
+    // so they belong into the first level
+    tree.register_segments(&segments)?;
+
+    Ok(())
+}
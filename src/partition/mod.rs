@@ -1,56 +1,104 @@
 pub mod config;
+mod approximate;
+mod fork;
+pub mod multi_get;
 pub mod name;
+pub mod read;
+pub mod write_delay;
 
 use crate::{
-    batch::{item::Item as BatchItem, PartitionKey},
+    batch::{
+        condition::{Expected, Precondition},
+        item::Item as BatchItem,
+        PartitionKey,
+    },
     compaction::manager::CompactionManager,
     config::Config as KeyspaceConfig,
     file::PARTITIONS_FOLDER,
     flush::manager::{FlushManager, Task as FlushTask},
     journal::{
+        group_commit::PendingSync,
         manager::{JournalManager, PartitionSeqNo},
+        shard::JournalShard,
         Journal,
     },
-    keyspace::Partitions,
+    keyspace::{Partitions, UnloadedPartitions},
+    manifest::{KeyspaceManifest, PartitionEntry, PartitionId},
+    obsolete_trees::ObsoleteTrees,
+    replication::ReplicationSink,
+    seqno_marker::SeqnoMarker,
+    sharded::ShardGuard,
     write_buffer_manager::WriteBufferManager,
-    Keyspace,
+    Cursor, Instant, Keyspace, PersistMode, ReadOptions, ValueVersion, WriteOptions,
 };
 use config::CreateOptions;
 use lsm_tree::{
-    compaction::CompactionStrategy, SequenceNumberCounter, Snapshot, Tree as LsmTree, UserKey,
-    UserValue,
+    compaction::CompactionStrategy, range::prefix_to_range, SequenceNumberCounter, Snapshot,
+    Tree as LsmTree, UserKey, UserValue,
+};
+use read::{
+    get_latest, sealed_memtables, to_owned_bounds, Bounds, MemtableReaders, PendingWrites, ReadView,
 };
 use std::{
     collections::HashMap,
-    ops::RangeBounds,
+    ops::{Bound::Included, RangeBounds},
     path::PathBuf,
     sync::{
         atomic::{AtomicBool, AtomicU32},
-        Arc, RwLock,
+        Arc, RwLock, Weak,
     },
     time::Duration,
 };
 use std_semaphore::Semaphore;
+use write_delay::{wait_blocking, WriteDelay};
+
+/// The on-disk partition (LSM-tree) a handle refers to
+///
+/// Swapping partitions exchanges the slots of their handles,
+/// so existing handles observe the new contents.
+#[derive(Clone)]
+pub struct PartitionSlot {
+    /// Partition ID, which identifies the partition on disk
+    pub(crate) id: PartitionId,
+
+    /// If `false`, writes skip the journal
+    pub(crate) is_journaled: bool,
+
+    pub(crate) tree: LsmTree,
+
+    /// Shared by plain writes while assigning their seqno and inserting into the memtable,
+    /// and locked exclusively by writes with preconditions, so no plain write can be
+    /// in flight between checking a precondition and applying the conditional write
+    pub(crate) write_lock: Arc<RwLock<()>>,
+
+    /// Iterators over the active memtable, which receive it once it is sealed
+    pub(crate) memtable_readers: MemtableReaders,
+}
 
 #[allow(clippy::module_name_repetitions)]
 pub struct PartitionHandleInner {
-    /// Partition name
-    pub name: PartitionKey,
+    /// Partition name, which changes when the partition is renamed
+    pub(crate) name: RwLock<PartitionKey>,
 
     pub(crate) keyspace_config: KeyspaceConfig,
+    pub(crate) manifest: Arc<KeyspaceManifest>,
     pub(crate) flush_manager: Arc<RwLock<FlushManager>>,
     pub(crate) journal_manager: Arc<RwLock<JournalManager>>,
     pub(crate) flush_semaphore: Arc<Semaphore>,
     pub(crate) journal: Arc<Journal>,
     pub(crate) partitions: Arc<RwLock<Partitions>>,
+    pub(crate) unloaded_partitions: Arc<RwLock<UnloadedPartitions>>,
     pub(crate) compaction_manager: CompactionManager,
     pub(crate) seqno: SequenceNumberCounter,
+    pub(crate) seqno_marker: Arc<SeqnoMarker>,
     pub(crate) write_buffer_manager: WriteBufferManager,
     pub(crate) is_deleted: AtomicBool,
     pub(crate) is_poisoned: Arc<AtomicBool>,
+    pub(crate) replication: ReplicationSink,
+    pub(crate) obsolete_trees: Arc<ObsoleteTrees>,
 
-    #[doc(hidden)]
-    pub tree: LsmTree,
+    /// The on-disk partition this handle currently refers to
+    pub(crate) slot: RwLock<PartitionSlot>,
 
     /// Maximum size of this partition's memtable
     pub(crate) max_memtable_size: AtomicU32,
@@ -61,7 +109,14 @@ pub struct PartitionHandleInner {
 impl Drop for PartitionHandleInner {
     fn drop(&mut self) {
         if self.is_deleted.load(std::sync::atomic::Ordering::Acquire) {
-            let path = self.tree.config.path.clone();
+            let path = self
+                .slot
+                .read()
+                .expect("lock is poisoned")
+                .tree
+                .config
+                .path
+                .clone();
 
             if let Err(e) = std::fs::remove_dir_all(&path) {
                 log::error!("Failed to cleanup deleted partition's folder at {path:?}: {e}");
@@ -94,7 +149,7 @@ impl std::ops::Deref for PartitionHandle {
 
 impl PartialEq for PartitionHandle {
     fn eq(&self, other: &Self) -> bool {
-        self.name == other.name
+        Arc::ptr_eq(&self.0, &other.0)
     }
 }
 
@@ -102,11 +157,43 @@ impl Eq for PartitionHandle {}
 
 impl std::hash::Hash for PartitionHandle {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        state.write(self.name.as_bytes());
+        Arc::as_ptr(&self.0).hash(state);
     }
 }
 
 impl PartitionHandle {
+    /// Returns the partition name
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name lock is poisoned.
+    #[must_use]
+    pub fn name(&self) -> PartitionKey {
+        self.name.read().expect("lock is poisoned").clone()
+    }
+
+    /// Returns the underlying LSM-tree
+    #[doc(hidden)]
+    #[must_use]
+    pub fn tree(&self) -> LsmTree {
+        self.slot.read().expect("lock is poisoned").tree.clone()
+    }
+
+    /// Returns the on-disk partition this handle currently refers to
+    pub(crate) fn slot(&self) -> PartitionSlot {
+        self.slot.read().expect("lock is poisoned").clone()
+    }
+
+    /// Returns the partition ID
+    pub(crate) fn id(&self) -> PartitionId {
+        self.slot.read().expect("lock is poisoned").id
+    }
+
+    /// Returns `false` if writes skip the journal
+    pub(crate) fn is_journaled(&self) -> bool {
+        self.slot.read().expect("lock is poisoned").is_journaled
+    }
+
     /// Sets the compaction strategy
     ///
     /// Default = Levelled
@@ -130,9 +217,15 @@ impl PartitionHandle {
         name: PartitionKey,
         config: CreateOptions,
     ) -> crate::Result<Self> {
-        log::debug!("Creating partition {name}");
+        let id = keyspace.manifest.allocate_id();
 
-        let path = keyspace.config.path.join(PARTITIONS_FOLDER).join(&*name);
+        log::debug!("Creating partition {name:?} with ID {id}");
+
+        let path = keyspace
+            .config
+            .path
+            .join(PARTITIONS_FOLDER)
+            .join(id.to_string());
 
         let tree = lsm_tree::Config::new(path)
             .descriptor_table(keyspace.config.descriptor_table.clone())
@@ -142,9 +235,85 @@ impl PartitionHandle {
             .level_ratio(config.level_ratio)
             .open()?;
 
-        Ok(Self(Arc::new(PartitionHandleInner {
+        // IMPORTANT: Register the partition after the tree is initialized,
+        // registering it is what atomically creates the partition
+        keyspace.manifest.insert(
+            id,
+            PartitionEntry {
+                name: name.clone(),
+                journaled: config.journaled,
+            },
+        )?;
+
+        Ok(Self::from_slot(
+            keyspace,
             name,
+            PartitionSlot {
+                id,
+                is_journaled: config.journaled,
+                tree,
+                write_lock: Arc::default(),
+                memtable_readers: MemtableReaders::default(),
+            },
+        ))
+    }
+
+    /// Creates a copy-on-write clone of the source partition
+    ///
+    /// The clone is not registered in the keyspace manifest yet.
+    pub(crate) fn create_fork(
+        keyspace: &Keyspace,
+        source: &Self,
+        name: PartitionKey,
+    ) -> crate::Result<Self> {
+        let id = keyspace.manifest.allocate_id();
+
+        log::debug!(
+            "Forking partition {:?} into {name:?} with ID {id}",
+            source.name()
+        );
+
+        let path = keyspace
+            .config
+            .path
+            .join(PARTITIONS_FOLDER)
+            .join(id.to_string());
+
+        let slot = match fork::fork_tree(source, id, &path) {
+            Ok(slot) => slot,
+            Err(e) => {
+                if let Err(e) = std::fs::remove_dir_all(&path) {
+                    log::error!("Failed to cleanup unused partition folder: {e}");
+                }
+                return Err(e);
+            }
+        };
+
+        let handle = Self::from_slot(keyspace, name, slot);
+
+        handle.set_compaction_strategy(
+            source
+                .compaction_strategy
+                .read()
+                .expect("lock is poisoned")
+                .clone(),
+        );
+        handle.set_max_memtable_size(
+            source
+                .max_memtable_size
+                .load(std::sync::atomic::Ordering::Acquire),
+        );
+
+        Ok(handle)
+    }
+
+    /// Creates a handle that refers to the given on-disk partition
+    fn from_slot(keyspace: &Keyspace, name: PartitionKey, slot: PartitionSlot) -> Self {
+        Self(Arc::new(PartitionHandleInner {
+            name: RwLock::new(name),
+            manifest: keyspace.manifest.clone(),
             partitions: keyspace.partitions.clone(),
+            unloaded_partitions: keyspace.unloaded_partitions.clone(),
             keyspace_config: keyspace.config.clone(),
             flush_manager: keyspace.flush_manager.clone(),
             flush_semaphore: keyspace.flush_semaphore.clone(),
@@ -152,19 +321,22 @@ impl PartitionHandle {
             journal: keyspace.journal.clone(),
             compaction_manager: keyspace.compaction_manager.clone(),
             seqno: keyspace.seqno.clone(),
-            tree,
+            seqno_marker: keyspace.seqno_marker.clone(),
+            obsolete_trees: keyspace.obsolete_trees.clone(),
             compaction_strategy: RwLock::new(Arc::new(super::compaction::Levelled::default())),
             max_memtable_size: (8 * 1_024 * 1_024).into(),
             write_buffer_manager: keyspace.write_buffer_manager.clone(),
             is_deleted: AtomicBool::default(),
             is_poisoned: keyspace.is_poisoned.clone(),
-        })))
+            replication: keyspace.replication.clone(),
+            slot: RwLock::new(slot),
+        }))
     }
 
     /// Returns the underlying LSM-tree's path
     #[must_use]
     pub fn path(&self) -> PathBuf {
-        self.tree.config.path.clone()
+        self.tree().config.path.clone()
     }
 
     /// Returns the disk space usage of this partition
@@ -183,7 +355,7 @@ impl PartitionHandle {
     /// ```
     #[must_use]
     pub fn disk_space(&self) -> u64 {
-        self.tree.disk_space()
+        self.tree().disk_space()
     }
 
     /// Returns an iterator that scans through the entire partition.
@@ -212,7 +384,7 @@ impl PartitionHandle {
     #[must_use]
     #[allow(clippy::iter_not_returning_iterator)]
     pub fn iter(&self) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
-        self.tree.iter().map(|item| Ok(item?))
+        self.tree().iter().map(|item| Ok(item?))
     }
 
     /// Returns an iterator over a range of items.
@@ -242,7 +414,7 @@ impl PartitionHandle {
         &'a self,
         range: R,
     ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
-        self.tree.range(range).map(|item| Ok(item?))
+        self.tree().range(range).map(|item| Ok(item?))
     }
 
     /// Returns an iterator over a prefixed set of items.
@@ -272,7 +444,105 @@ impl PartitionHandle {
         &'a self,
         prefix: K,
     ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
-        self.tree.prefix(prefix).map(|item| Ok(item?))
+        self.tree().prefix(prefix).map(|item| Ok(item?))
+    }
+
+    /// Returns an iterator over a range of items, using the given read options.
+    ///
+    /// Avoid using full or unbounded ranges as they may scan a lot of items (unless limited).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, ReadOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("f", "abc")?;
+    /// partition.insert("g", "abc")?;
+    ///
+    /// let options = ReadOptions::default().fill_cache(false);
+    /// assert_eq!(2, partition.range_with_options("a"..="f", options).count());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn range_with_options<'a, K: AsRef<[u8]> + 'a, R: RangeBounds<K> + 'a>(
+        &'a self,
+        range: R,
+        options: ReadOptions,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
+        self.create_range_with_options(to_owned_bounds(&range), None, options)
+    }
+
+    /// Returns an iterator over a prefixed set of items, using the given read options.
+    ///
+    /// Avoid using an empty prefix as it may scan a lot of items (unless limited).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, ReadOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("ab", "abc")?;
+    /// partition.insert("abc", "abc")?;
+    ///
+    /// let options = ReadOptions::default().verify_checksums(true);
+    /// assert_eq!(2, partition.prefix_with_options("ab", options).count());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn prefix_with_options<'a, K: AsRef<[u8]> + 'a>(
+        &'a self,
+        prefix: K,
+        options: ReadOptions,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
+        self.create_range_with_options(prefix_to_range(prefix.as_ref()), None, options)
+    }
+
+    /// Creates an iterator over a range of items, reading at the given instant
+    /// (or the options' snapshot), and merging in the given uncommitted writes.
+    pub(crate) fn create_range_with_options(
+        &self,
+        bounds: Bounds,
+        pending: Option<PendingWrites>,
+        options: ReadOptions,
+    ) -> Box<dyn DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>>> {
+        let slot = self.slot();
+        let seqno = options.snapshot;
+
+        // NOTE: Keep old versions from being evicted while the iterator is alive
+        let snapshot = seqno.map(|seqno| slot.tree.snapshot(seqno));
+
+        // NOTE: Rolled back writes need to be skipped, which only our own reader can do
+        let iter: Box<dyn DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>>> =
+            if options.needs_block_reader() || pending.is_some() {
+                let view = ReadView::new(self, &slot, &bounds);
+                Box::new(read::create_range(view, &bounds, seqno, pending, options))
+            } else {
+                Box::new(
+                    slot.tree
+                        .create_range(&bounds, seqno, None)
+                        .map(|item| Ok(item?)),
+                )
+            };
+
+        Box::new(iter.inspect(move |_| {
+            let _ = &snapshot;
+        }))
     }
 
     /// Approximates the amount of items in the partition.
@@ -304,7 +574,80 @@ impl PartitionHandle {
     /// ```
     #[must_use]
     pub fn approximate_len(&self) -> u64 {
-        self.tree.approximate_len()
+        self.tree().approximate_len()
+    }
+
+    /// Approximates the size of a range of the partition, in bytes.
+    ///
+    /// Flushed data is estimated from segment metadata and block indexes,
+    /// without reading any data blocks; unflushed data is measured in memory.
+    /// The size is uncompressed and includes old versions and tombstones,
+    /// so, like [`PartitionHandle::approximate_len`], it diverges
+    /// from the real value for update -or delete-heavy workloads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("b", "a".repeat(1_000))?;
+    ///
+    /// assert!(partition.approximate_size("b"..)? > partition.approximate_size(.."b")?);
+    /// assert_eq!(0, partition.approximate_size("c"..)?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn approximate_size<K: AsRef<[u8]>, R: RangeBounds<K>>(
+        &self,
+        range: R,
+    ) -> crate::Result<u64> {
+        let slot = self.slot();
+        let estimate = approximate::estimate(self, &slot, &to_owned_bounds(&range))?;
+        Ok(estimate.size)
+    }
+
+    /// Approximates the amount of items in a range of the partition.
+    ///
+    /// Flushed data is estimated from segment metadata and block indexes,
+    /// without reading any data blocks; unflushed data is counted in memory.
+    /// Old versions and tombstones are counted as well, so, like [`PartitionHandle::approximate_len`],
+    /// this value diverges from the real value for update -or delete-heavy workloads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("b", "abc")?;
+    /// partition.insert("c", "abc")?;
+    ///
+    /// assert_eq!(2, partition.approximate_count("a"..="b")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn approximate_count<K: AsRef<[u8]>, R: RangeBounds<K>>(
+        &self,
+        range: R,
+    ) -> crate::Result<u64> {
+        let slot = self.slot();
+        let estimate = approximate::estimate(self, &slot, &to_owned_bounds(&range))?;
+        Ok(estimate.count)
     }
 
     /// Scans the entire partition, returning the amount of items.
@@ -424,11 +767,13 @@ impl PartitionHandle {
     ///
     /// Will return `Err` if an IO error occurs.
     pub fn get<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<Option<lsm_tree::UserValue>> {
-        Ok(self.tree.get(key)?)
+        Ok(self.tree().get(key)?)
     }
 
-    /// Returns the first key-value pair in the partition.
-    /// The key in this pair is the minimum key in the partition.
+    /// Retrieves an item from the partition, together with the instant it was written at.
+    ///
+    /// The instant changes with every write to the key, so it can be used
+    /// as a version tag for optimistic updates.
     ///
     /// # Examples
     ///
@@ -438,12 +783,14 @@ impl PartitionHandle {
     /// # let folder = tempfile::tempdir()?;
     /// # let keyspace = Config::new(folder).open()?;
     /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
-    /// partition.insert("1", "abc")?;
-    /// partition.insert("3", "abc")?;
-    /// partition.insert("5", "abc")?;
+    /// partition.insert("a", "my_value")?;
     ///
-    /// let (key, _) = partition.first_key_value()?.expect("item should exist");
-    /// assert_eq!(&*key, "1".as_bytes());
+    /// let (value, seqno) = partition.get_with_seqno("a")?.expect("should exist");
+    /// assert_eq!(b"my_value", &*value);
+    ///
+    /// partition.insert("a", "my_value")?;
+    /// let (_, new_seqno) = partition.get_with_seqno("a")?.expect("should exist");
+    /// assert!(new_seqno > seqno);
     /// #
     /// # Ok::<(), fjall::Error>(())
     /// ```
@@ -451,12 +798,22 @@ impl PartitionHandle {
     /// # Errors
     ///
     /// Will return `Err` if an IO error occurs.
-    pub fn first_key_value(&self) -> crate::Result<Option<(UserKey, UserValue)>> {
-        Ok(self.tree.first_key_value()?)
+    pub fn get_with_seqno<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+    ) -> crate::Result<Option<(UserValue, Instant)>> {
+        Ok(self
+            .tree()
+            .get_internal_entry(key, true, None)?
+            .map(|item| (item.value, item.seqno)))
     }
 
-    /// Returns the last key-value pair in the partition.
-    /// The key in this pair is the maximum key in the partition.
+    /// Retrieves every retained version of an item that was written at or after the given instant,
+    /// newest first.
+    ///
+    /// Deletions are returned as tombstone versions.
+    /// Old versions are only retained until compaction drops them,
+    /// which it does not do while a snapshot still needs them.
     ///
     /// # Examples
     ///
@@ -466,12 +823,16 @@ impl PartitionHandle {
     /// # let folder = tempfile::tempdir()?;
     /// # let keyspace = Config::new(folder).open()?;
     /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
-    /// partition.insert("1", "abc")?;
-    /// partition.insert("3", "abc")?;
-    /// partition.insert("5", "abc")?;
+    /// partition.insert("a", "abc")?;
     ///
-    /// let (key, _) = partition.last_key_value()?.expect("item should exist");
-    /// assert_eq!(&*key, "5".as_bytes());
+    /// let instant = keyspace.instant();
+    /// partition.insert("a", "def")?;
+    /// partition.remove("a")?;
+    ///
+    /// let versions = partition.get_versions("a", instant)?;
+    /// assert_eq!(2, versions.len());
+    /// assert!(versions[0].is_tombstone());
+    /// assert_eq!(b"def", &**versions[1].value());
     /// #
     /// # Ok::<(), fjall::Error>(())
     /// ```
@@ -479,167 +840,740 @@ impl PartitionHandle {
     /// # Errors
     ///
     /// Will return `Err` if an IO error occurs.
-    pub fn last_key_value(&self) -> crate::Result<Option<(UserKey, UserValue)>> {
-        Ok(self.tree.last_key_value()?)
-    }
-
-    /// Returns `true` if the memtable was indeed rotated.
-    #[doc(hidden)]
-    pub fn rotate_memtable(&self) -> crate::Result<bool> {
-        log::debug!("Rotating memtable {:?}", self.name);
+    pub fn get_versions<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        since: Instant,
+    ) -> crate::Result<Vec<ValueVersion>> {
+        let key: UserKey = key.as_ref().into();
+        let bounds: Bounds = (Included(key.clone()), Included(key));
 
-        log::trace!("partition: acquiring full write lock");
-        let mut journal = self.journal.shards.full_lock().expect("lock is poisoned");
+        let slot = self.slot();
+        let view = ReadView::new(self, &slot, &bounds);
 
-        // Rotate memtable
-        let Some((yanked_id, yanked_memtable)) = self.tree.rotate_memtable() else {
-            log::debug!("Got no sealed memtable, someone beat us to it");
-            return Ok(false);
-        };
+        let mut versions = vec![];
 
-        log::trace!("partition: acquiring journal manager lock");
-        let mut journal_manager = self.journal_manager.write().expect("lock is poisoned");
+        for item in read::create_versions(view, &bounds) {
+            let item = item?;
 
-        let seqno_map = {
-            let partitions = self.partitions.write().expect("lock is poisoned");
-
-            let mut map = HashMap::new();
-
-            for (name, partition) in &*partitions {
-                if let Some(lsn) = partition.tree.get_memtable_lsn() {
-                    map.insert(
-                        name.clone(),
-                        PartitionSeqNo {
-                            lsn,
-                            partition: partition.clone(),
-                        },
-                    );
-                }
+            // NOTE: Versions are sorted by descending seqno
+            if item.seqno < since {
+                break;
             }
 
-            map.insert(
-                self.name.clone(),
-                PartitionSeqNo {
-                    partition: self.clone(),
-                    lsn: yanked_memtable
-                        .get_lsn()
-                        .expect("sealed memtable is never empty"),
-                },
-            );
-
-            map
-        };
-
-        journal_manager.rotate_journal(&mut journal, seqno_map)?;
-
-        log::trace!("partition: acquiring flush manager lock");
-        let mut flush_manager = self.flush_manager.write().expect("lock is poisoned");
-
-        flush_manager.enqueue_task(
-            self.name.clone(),
-            FlushTask {
-                id: yanked_id,
-                partition: self.clone(),
-                sealed_memtable: yanked_memtable,
-            },
-        );
-
-        journal_manager.disk_space_used();
-        drop(journal_manager);
-        drop(flush_manager);
-        drop(journal);
-
-        // Notify flush worker that new work has arrived
-        self.flush_semaphore.release();
+            versions.push(item.into());
+        }
 
-        Ok(true)
+        Ok(versions)
     }
 
-    fn check_journal_size(&self) {
-        loop {
-            let bytes = self
-                .journal_manager
-                .read()
-                .expect("lock is poisoned")
-                .disk_space_used();
-
-            if bytes <= self.keyspace_config.max_journaling_size_in_bytes {
-                if bytes as f64 > self.keyspace_config.max_journaling_size_in_bytes as f64 * 0.9 {
-                    log::info!(
-                        "partition: write stall because 90% journal threshold has been reached"
-                    );
-                    std::thread::sleep(std::time::Duration::from_millis(500));
-                }
+    /// Retrieves an item from the partition, using the given read options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, ReadOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "my_value")?;
+    /// let instant = keyspace.instant();
+    ///
+    /// partition.insert("a", "my_updated_value")?;
+    ///
+    /// let options = ReadOptions::default().fill_cache(false).snapshot(Some(instant));
+    /// let item = partition.get_with_options("a", options)?;
+    /// assert_eq!(Some("my_value".as_bytes().into()), item);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn get_with_options<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        options: ReadOptions,
+    ) -> crate::Result<Option<lsm_tree::UserValue>> {
+        if !options.needs_block_reader() {
+            return Ok(match options.snapshot {
+                Some(seqno) => self.snapshot_at(seqno).get(key)?,
+                None => self.tree().get(key)?,
+            });
+        }
 
-                break;
-            }
+        self.get_from_range(key, None, options)
+    }
 
-            log::debug!("partition: write halt because of too many journals");
-            std::thread::sleep(std::time::Duration::from_millis(100)); // TODO: maybe exponential backoff
-        }
+    /// Retrieves an item by scanning a single-key range, reading at the
+    /// given instant (or the options' snapshot), merging in the given uncommitted writes.
+    pub(crate) fn get_from_range<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        pending: Option<PendingWrites>,
+        options: ReadOptions,
+    ) -> crate::Result<Option<lsm_tree::UserValue>> {
+        let key = UserKey::from(key.as_ref());
+
+        self.create_range_with_options((Included(key.clone()), Included(key)), pending, options)
+            .next()
+            .transpose()
+            .map(|item| item.map(|(_, value)| value))
     }
 
-    fn check_write_halt(&self) {
-        while self.tree.first_level_segment_count() > 24 {
-            log::info!("Halting writes until L0 is cleared up...");
-            self.compaction_manager.notify(self.clone());
-            std::thread::sleep(Duration::from_millis(1_000));
+    /// Retrieves multiple items from the partition.
+    ///
+    /// All keys are read from the same point in time. Instead of a separate point read per key,
+    /// each memtable and segment is visited once, looking up all keys in ascending order,
+    /// and the values are returned in the order of the given keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("c", "def")?;
+    ///
+    /// let items = partition.multi_get(["c", "b", "a"])?;
+    /// assert_eq!(
+    ///     vec![Some("def".as_bytes().into()), None, Some("abc".as_bytes().into())],
+    ///     items,
+    /// );
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn multi_get<K: AsRef<[u8]>, I: IntoIterator<Item = K>>(
+        &self,
+        keys: I,
+    ) -> crate::Result<Vec<Option<UserValue>>> {
+        self.multi_get_at(self.seqno.get(), keys)
+    }
+
+    /// Retrieves multiple items from the partition, reading from the given point in time.
+    ///
+    /// Like [`PartitionHandle::multi_get`], but reads the same state as [`PartitionHandle::snapshot_at`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    ///
+    /// let instant = keyspace.instant();
+    /// partition.insert("b", "def")?;
+    ///
+    /// let items = partition.multi_get_at(instant, ["a", "b"])?;
+    /// assert_eq!(vec![Some("abc".as_bytes().into()), None], items);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a lock is poisoned.
+    pub fn multi_get_at<K: AsRef<[u8]>, I: IntoIterator<Item = K>>(
+        &self,
+        instant: Instant,
+        keys: I,
+    ) -> crate::Result<Vec<Option<UserValue>>> {
+        let keys = keys.into_iter().collect::<Vec<_>>();
+
+        // NOTE: Keep the instant alive, so compaction does not drop versions that are read
+        let _snapshot = self.snapshot_at(instant);
+
+        let mut results = Vec::with_capacity(keys.len());
+
+        multi_get::get_at(
+            self,
+            keys.iter().map(AsRef::as_ref).zip(0..).collect(),
+            instant,
+            &mut results,
+        )?;
+
+        Ok(multi_get::into_input_order(results))
+    }
+
+    /// Returns the first key-value pair in the partition.
+    /// The key in this pair is the minimum key in the partition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("1", "abc")?;
+    /// partition.insert("3", "abc")?;
+    /// partition.insert("5", "abc")?;
+    ///
+    /// let (key, _) = partition.first_key_value()?.expect("item should exist");
+    /// assert_eq!(&*key, "1".as_bytes());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn first_key_value(&self) -> crate::Result<Option<(UserKey, UserValue)>> {
+        Ok(self.tree().first_key_value()?)
+    }
+
+    /// Returns the last key-value pair in the partition.
+    /// The key in this pair is the maximum key in the partition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("1", "abc")?;
+    /// partition.insert("3", "abc")?;
+    /// partition.insert("5", "abc")?;
+    ///
+    /// let (key, _) = partition.last_key_value()?.expect("item should exist");
+    /// assert_eq!(&*key, "5".as_bytes());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn last_key_value(&self) -> crate::Result<Option<(UserKey, UserValue)>> {
+        Ok(self.tree().last_key_value()?)
+    }
+
+    /// Removes all items of the partition.
+    ///
+    /// Unlike deleting and recreating the partition, the partition keeps its name
+    /// and settings, and existing handles stay valid.
+    ///
+    /// No items are written or scanned, so clearing is cheap regardless of the partition size:
+    /// The partition is atomically switched to a new, empty LSM-tree. The old data is removed
+    /// once it is not used anymore, e.g. by iterators or snapshots that were created before.
+    /// After a crash, the old data is never resurrected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("b", "abc")?;
+    /// assert!(!partition.is_empty()?);
+    ///
+    /// partition.clear()?;
+    /// assert!(partition.is_empty()?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionDeleted`](crate::Error::PartitionDeleted)
+    /// if the partition has been deleted.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a lock is poisoned.
+    pub fn clear(&self) -> crate::Result<()> {
+        // IMPORTANT: Lock the journal, so no writes or memtable rotations
+        // can happen while the tree is exchanged
+        let _journal_lock = self.journal.shards.full_lock().expect("lock is poisoned");
+        let mut journal_manager = self.journal_manager.write().expect("lock is poisoned");
+
+        // NOTE: Lock partitions, so the partition cannot be renamed, swapped or deleted concurrently
+        let _partitions = self.partitions.read().expect("lock is poisoned");
+
+        if self.is_deleted.load(std::sync::atomic::Ordering::Acquire) {
+            return Err(crate::Error::PartitionDeleted);
+        }
+
+        let old_slot = self.slot();
+        let config = &old_slot.tree.config;
+
+        let id = self.manifest.allocate_id();
+
+        log::debug!(
+            "Clearing partition {:?}, switching from ID {} to {id}",
+            self.name(),
+            old_slot.id
+        );
+
+        let path = self
+            .keyspace_config
+            .path
+            .join(PARTITIONS_FOLDER)
+            .join(id.to_string());
+
+        let tree = lsm_tree::Config::new(path)
+            .descriptor_table(config.descriptor_table.clone())
+            .block_cache(config.block_cache.clone())
+            .block_size(config.inner.block_size)
+            .level_count(config.inner.level_count)
+            .level_ratio(config.level_ratio)
+            .open()?;
+
+        // IMPORTANT: Moving the partition to the new ID is what atomically clears it,
+        // journal items of the old ID are skipped on recovery
+        if let Err(e) = self.manifest.reassign(old_slot.id, id) {
+            if let Err(e) = std::fs::remove_dir_all(&tree.config.path) {
+                log::error!("Failed to cleanup unused partition folder: {e}");
+            }
+            return Err(e);
+        }
+
+        *self.slot.write().expect("lock is poisoned") = PartitionSlot {
+            id,
+            is_journaled: old_slot.is_journaled,
+            tree,
+            write_lock: Arc::default(),
+            memtable_readers: MemtableReaders::default(),
+        };
+
+        // NOTE: The old data does not need to be flushed anymore
+        journal_manager.remove_partition(old_slot.id);
+
+        let sealed_memtables_size = {
+            let mut flush_manager = self.flush_manager.write().expect("lock is poisoned");
+
+            let size = flush_manager
+                .queues
+                .get(&old_slot.id)
+                .map(|queue| {
+                    queue
+                        .iter()
+                        .map(|task| u64::from(task.sealed_memtable.size()))
+                        .sum::<u64>()
+                })
+                .unwrap_or_default();
+
+            flush_manager.remove_partition(old_slot.id);
+
+            size
+        };
+
+        self.write_buffer_manager
+            .free(sealed_memtables_size + u64::from(old_slot.tree.active_memtable_size()));
+
+        // IMPORTANT: Iterators, snapshots, flushes or compactions may still use the old tree,
+        // so its folder can only be removed once the last reference is dropped
+        self.obsolete_trees.register(&old_slot.tree);
+        drop(old_slot);
+        self.obsolete_trees.cleanup();
+
+        Ok(())
+    }
+
+    /// Returns `true` if the memtable was indeed rotated.
+    #[doc(hidden)]
+    pub fn rotate_memtable(&self) -> crate::Result<bool> {
+        log::debug!("Rotating memtable {:?}", self.name());
+
+        log::trace!("partition: acquiring full write lock");
+        let mut journal = self.journal.shards.full_lock().expect("lock is poisoned");
+
+        self.rotate_memtable_locked(&mut journal)
+    }
+
+    /// Blocks until the flush worker has flushed all sealed memtables of this partition
+    pub(crate) fn wait_for_flush(&self) -> crate::Result<()> {
+        // IMPORTANT: Let the flush worker flush the sealed memtables,
+        // flushing them here could race with it
+        loop {
+            let flush_manager = self.flush_manager.read().expect("lock is poisoned");
+            let signal = flush_manager.signal.clone();
+
+            // NOTE: Read the round before checking for tasks, so a round
+            // completing in between is not missed
+            let round = signal.rounds();
+
+            if !flush_manager.has_tasks(self.id()) {
+                return Ok(());
+            }
+            drop(flush_manager);
+
+            if self.is_poisoned.load(std::sync::atomic::Ordering::Relaxed) {
+                return Err(crate::Error::Poisoned);
+            }
+
+            self.flush_semaphore.release();
+            signal.wait(round);
+        }
+    }
+
+    /// Rotates the memtable, while the journal is fully locked
+    ///
+    /// Returns `true` if the memtable was indeed rotated.
+    pub(crate) fn rotate_memtable_locked(
+        &self,
+        journal: &mut [ShardGuard<'_, JournalShard>],
+    ) -> crate::Result<bool> {
+        // IMPORTANT: Get the slot while holding the journal lock,
+        // so the partition cannot be swapped concurrently
+        let slot = self.slot();
+
+        if !slot.is_journaled {
+            return self.rotate_unjournaled_memtable(&slot);
+        }
+
+        log::trace!("partition: acquiring journal manager lock");
+        let mut journal_manager = self.journal_manager.write().expect("lock is poisoned");
+
+        // IMPORTANT: Hold the flush manager lock while sealing the memtable,
+        // so readers never observe a sealed memtable that is not enqueued yet
+        log::trace!("partition: acquiring flush manager lock");
+        let mut flush_manager = self.flush_manager.write().expect("lock is poisoned");
+
+        // Rotate memtable
+        let Some((yanked_id, yanked_memtable)) = slot.tree.rotate_memtable() else {
+            log::debug!("Got no sealed memtable, someone beat us to it");
+            return Ok(false);
+        };
+
+        // IMPORTANT: Hand the sealed memtable to iterators that are still reading it,
+        // while holding the flush manager lock
+        slot.memtable_readers.seal(&yanked_memtable);
+
+        // IMPORTANT: The sealed memtable will be flushed, and its journal evicted,
+        // so persist the seqno, otherwise it may be lost if the partition is not loaded on recovery
+        self.seqno_marker.persist(self.seqno.get())?;
+
+        // NOTE: Only partitions that were written to since the last rotation have data in
+        // the journal, and each shard keeps track of those, so we do not need to scan all partitions
+        let seqno_map = {
+            let partitions = self.partitions.read().expect("lock is poisoned");
+            let unloaded_partitions = self.unloaded_partitions.read().expect("lock is poisoned");
+
+            let mut map = HashMap::<PartitionId, PartitionSeqNo>::new();
+
+            for shard in &*journal {
+                for (&partition_id, &lsn) in &shard.dirty_partitions {
+                    // NOTE: Deleted partitions do not need to be flushed
+                    let Some(entry) = self.manifest.get(partition_id) else {
+                        continue;
+                    };
+
+                    // NOTE: A closed partition may have been written to through a handle that is still alive
+                    let Some(partition) = partitions
+                        .get(&entry.name)
+                        .cloned()
+                        .or_else(|| {
+                            unloaded_partitions
+                                .get(&entry.name)
+                                .and_then(Weak::upgrade)
+                                .map(PartitionHandle)
+                        })
+                        .filter(|partition| partition.id() == partition_id)
+                    else {
+                        continue;
+                    };
+
+                    map.entry(partition_id)
+                        .and_modify(|item| item.lsn = item.lsn.max(lsn))
+                        .or_insert_with(|| PartitionSeqNo { partition, lsn });
+                }
+            }
+
+            map.insert(
+                slot.id,
+                PartitionSeqNo {
+                    partition: self.clone(),
+                    lsn: yanked_memtable
+                        .get_lsn()
+                        .expect("sealed memtable is never empty"),
+                },
+            );
+
+            map
+        };
+
+        journal_manager.rotate_journal(journal, seqno_map)?;
+
+        flush_manager.enqueue_task(
+            slot.id,
+            FlushTask {
+                id: yanked_id,
+                partition: self.clone(),
+                sealed_memtable: yanked_memtable,
+                tree: slot.tree,
+            },
+        );
+
+        journal_manager.disk_space_used();
+        drop(journal_manager);
+        drop(flush_manager);
+
+        // Notify flush worker that new work has arrived
+        self.flush_semaphore.release();
+
+        Ok(true)
+    }
+
+    /// Rotates the memtable of a non-journaled partition, without rotating the journal
+    fn rotate_unjournaled_memtable(&self, slot: &PartitionSlot) -> crate::Result<bool> {
+        // IMPORTANT: Hold the flush manager lock while sealing the memtable,
+        // so readers never observe a sealed memtable that is not enqueued yet
+        log::trace!("partition: acquiring flush manager lock");
+        let mut flush_manager = self.flush_manager.write().expect("lock is poisoned");
+
+        let Some((yanked_id, yanked_memtable)) = slot.tree.rotate_memtable() else {
+            log::debug!("Got no sealed memtable, someone beat us to it");
+            return Ok(false);
+        };
+
+        // IMPORTANT: Hand the sealed memtable to iterators that are still reading it,
+        // while holding the flush manager lock
+        slot.memtable_readers.seal(&yanked_memtable);
+
+        // IMPORTANT: Get the seqno *after* sealing the memtable,
+        // so it is higher than every seqno in the sealed memtable
+        self.seqno_marker.persist(self.seqno.get())?;
+
+        flush_manager.enqueue_task(
+            slot.id,
+            FlushTask {
+                id: yanked_id,
+                partition: self.clone(),
+                sealed_memtable: yanked_memtable,
+                tree: slot.tree.clone(),
+            },
+        );
+
+        drop(flush_manager);
+
+        // Notify flush worker that new work has arrived
+        self.flush_semaphore.release();
+
+        Ok(true)
+    }
+
+    /// Returns how long a write needs to wait because of too many journals
+    pub(crate) fn journal_size_delay(&self) -> Option<WriteDelay> {
+        let journal_manager = self.journal_manager.read().expect("lock is poisoned");
+
+        // NOTE: If there are no sealed journals, waiting for flushes cannot free up any space
+        if journal_manager.sealed_journal_count() == 0 {
+            return None;
         }
+
+        let bytes = journal_manager.sealed_disk_space_used();
+        drop(journal_manager);
+
+        let max_bytes = self.keyspace_config.max_journaling_size_in_bytes;
+
+        if bytes > max_bytes {
+            log::debug!("partition: write halt because of too many journals");
+
+            // TODO: maybe exponential backoff
+            return Some(WriteDelay::Halt(Duration::from_millis(100)));
+        }
+
+        // NOTE: 90% of the limit
+        if bytes > max_bytes - max_bytes / 10 {
+            log::info!("partition: write stall because 90% journal threshold has been reached");
+            return Some(WriteDelay::Stall(Duration::from_millis(500)));
+        }
+
+        None
+    }
+
+    /// Returns how long a write needs to wait because of too many segments in L0
+    pub(crate) fn write_halt_delay(&self) -> Option<WriteDelay> {
+        if self.tree().first_level_segment_count() > 24 {
+            log::info!("Halting writes until L0 is cleared up...");
+            self.compaction_manager.notify(self.clone());
+            return Some(WriteDelay::Halt(Duration::from_secs(1)));
+        }
+
+        None
     }
 
-    fn check_write_stall(&self) {
-        let seg_count = self.tree.first_level_segment_count();
+    /// Returns how long a write needs to wait because of many segments in L0
+    pub(crate) fn write_stall_delay(&self) -> Option<WriteDelay> {
+        let seg_count = self.tree().first_level_segment_count();
 
         if seg_count > 20 {
             log::info!("Stalling writes, many segments in L0...");
             self.compaction_manager.notify(self.clone());
 
             let ms = if seg_count > 22 { 500 } else { 100 };
-            std::thread::sleep(Duration::from_millis(ms));
+            return Some(WriteDelay::Stall(Duration::from_millis(ms)));
         }
+
+        None
     }
 
-    pub(crate) fn check_memtable_overflow(&self, size: u32) -> crate::Result<()> {
+    /// Returns how long a write needs to wait because of write buffer saturation
+    pub(crate) fn write_buffer_delay(&self) -> Option<WriteDelay> {
+        let bytes = self.write_buffer_manager.get();
+
+        let max_bytes = self.keyspace_config.max_write_buffer_size_in_bytes;
+
+        if bytes >= max_bytes {
+            log::info!("partition: write halt because of write buffer saturation");
+            return Some(WriteDelay::Halt(Duration::from_millis(100)));
+        }
+
+        // NOTE: 90% of the limit
+        if bytes > max_bytes - max_bytes / 10 {
+            log::info!(
+                "partition: write stall because 90% write buffer threshold has been reached"
+            );
+            return Some(WriteDelay::Stall(Duration::from_millis(500)));
+        }
+
+        None
+    }
+
+    /// Returns `true` if the active memtable has grown too large and needs to be rotated
+    pub(crate) fn is_memtable_overflowing(&self, size: u32) -> bool {
         use std::sync::atomic::Ordering::Acquire;
 
-        if size > self.max_memtable_size.load(Acquire) {
+        size > self.max_memtable_size.load(Acquire)
+    }
+
+    pub(crate) fn check_memtable_overflow(&self, size: u32) -> crate::Result<()> {
+        if self.is_memtable_overflowing(size) {
             self.rotate_memtable()?;
-            self.check_journal_size();
-            self.check_write_halt();
+
+            // NOTE: Non-journaled writes do not grow the journal
+            if self.is_journaled() {
+                wait_blocking(|| self.journal_size_delay());
+            }
+
+            wait_blocking(|| self.write_halt_delay());
+        }
+
+        wait_blocking(|| self.write_stall_delay());
+
+        Ok(())
+    }
+
+    pub(crate) fn check_write_buffer_size(&self, initial_size: u64) {
+        if initial_size > self.keyspace_config.max_write_buffer_size_in_bytes {
+            wait_blocking(|| self.write_buffer_delay());
         }
+    }
+
+    /// Returns the amount of disk segments of this partition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// assert_eq!(0, partition.segment_count());
+    ///
+    /// partition.insert("a", "abc")?;
+    /// partition.major_compact()?;
+    /// assert_eq!(1, partition.segment_count());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn segment_count(&self) -> usize {
+        self.tree().segment_count()
+    }
+
+    /// Flushes the memtable, and rewrites all disk segments of this partition,
+    /// dropping overwritten versions and deleted items
+    ///
+    /// Blocks until the background flush worker has flushed the memtable,
+    /// and the compaction has completed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("b", "abc")?;
+    /// partition.remove("a")?;
+    ///
+    /// partition.major_compact()?;
+    /// assert_eq!(1, partition.segment_count());
+    /// assert_eq!(1, partition.len()?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a lock is poisoned.
+    pub fn major_compact(&self) -> crate::Result<()> {
+        self.rotate_memtable()?;
+        self.wait_for_flush()?;
 
-        self.check_write_stall();
+        self.tree().major_compact(u64::MAX)?;
 
         Ok(())
     }
 
-    pub(crate) fn check_write_buffer_size(&self, initial_size: u64) {
-        if initial_size > self.keyspace_config.max_write_buffer_size_in_bytes {
-            loop {
-                let bytes = self.write_buffer_manager.get();
-
-                if bytes < self.keyspace_config.max_write_buffer_size_in_bytes {
-                    if bytes as f64
-                        > self.keyspace_config.max_write_buffer_size_in_bytes as f64 * 0.9
-                    {
-                        log::info!(
-                            "partition: write stall because 90% write buffer threshold has been reached"
-                        );
-                        std::thread::sleep(std::time::Duration::from_millis(500));
-                    }
-                    break;
-                }
-
-                log::info!("partition: write halt because of write buffer saturation");
-                std::thread::sleep(std::time::Duration::from_millis(100));
-            }
-        }
-    }
-
-    #[doc(hidden)]
-    #[must_use]
-    pub fn segment_count(&self) -> usize {
-        self.tree.segment_count()
+    /// Verifies the checksums of all disk segments of this partition
+    ///
+    /// Returns the amount of corrupted blocks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.major_compact()?;
+    ///
+    /// assert_eq!(0, partition.verify()?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn verify(&self) -> crate::Result<usize> {
+        Ok(self.tree().verify()?)
     }
 
     /// Opens a snapshot of this partition
@@ -651,7 +1585,80 @@ impl PartitionHandle {
     /// Opens a snapshot of this partition with a given sequence number
     #[must_use]
     pub fn snapshot_at(&self, seqno: crate::Instant) -> Snapshot {
-        self.tree.snapshot(seqno)
+        self.tree().snapshot(seqno)
+    }
+
+    /// Opens a seekable cursor over the current state of this partition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("b", "abc")?;
+    ///
+    /// let mut cursor = partition.cursor();
+    /// cursor.seek_to_last()?;
+    /// assert_eq!(Some(&"b".as_bytes().into()), cursor.key());
+    ///
+    /// cursor.prev()?;
+    /// assert_eq!(Some(&"a".as_bytes().into()), cursor.key());
+    ///
+    /// cursor.prev()?;
+    /// assert!(!cursor.is_valid());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn cursor(&self) -> Cursor {
+        self.cursor_at(self.seqno.get())
+    }
+
+    /// Opens a seekable cursor over this partition with a given sequence number
+    #[must_use]
+    pub fn cursor_at(&self, seqno: crate::Instant) -> Cursor {
+        Cursor::new(self.clone(), seqno, None)
+    }
+
+    /// Writes a dump of this partition into the given writer, returning the amount of items written.
+    ///
+    /// The dump contains the partition's name, creation options and all items
+    /// at the current point in time, and can be restored using [`Keyspace::import`].
+    ///
+    /// To dump multiple partitions consistently, use [`Keyspace::export`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    ///
+    /// let mut dump = vec![];
+    /// assert_eq!(1, partition.export(&mut dump)?);
+    ///
+    /// # let folder = tempfile::tempdir()?;
+    /// let keyspace = Config::new(folder).open()?;
+    /// assert_eq!(1, keyspace.import(&*dump)?);
+    ///
+    /// let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// assert!(partition.contains_key("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn export<W: std::io::Write>(&self, writer: W) -> crate::Result<u64> {
+        crate::dump::export(std::slice::from_ref(self), self.seqno.get(), writer)
     }
 
     /// Inserts a key-value pair into the partition.
@@ -680,37 +1687,51 @@ impl PartitionHandle {
     ///
     /// Will return `Err` if an IO error occurs.
     pub fn insert<K: AsRef<[u8]>, V: AsRef<[u8]>>(&self, key: K, value: V) -> crate::Result<()> {
-        if self.is_deleted.load(std::sync::atomic::Ordering::Relaxed) {
-            return Err(crate::Error::PartitionDeleted);
-        }
-
-        if self.is_poisoned.load(std::sync::atomic::Ordering::Relaxed) {
-            return Err(crate::Error::Poisoned);
-        }
-
-        let mut shard = self.journal.get_writer();
-
-        let seqno = self.seqno.next();
+        self.insert_with_options(key, value, WriteOptions::default())
+            .map(|_| ())
+    }
 
-        shard.writer.write(
-            &BatchItem {
-                key: key.as_ref().into(),
-                value: value.as_ref().into(),
-                partition: self.name.clone(),
-                value_type: lsm_tree::ValueType::Value,
-            },
-            seqno,
+    /// Inserts a key-value pair into the partition, using the given write options.
+    ///
+    /// Returns the seqno of the write. If a durability is set, the write
+    /// is synced to disk accordingly when this function returns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, PersistMode, WriteOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let options = WriteOptions::default().durability(Some(PersistMode::SyncAll));
+    /// let seqno = partition.insert_with_options("a", "abc", options)?;
+    ///
+    /// assert!(partition.snapshot_at(seqno + 1).contains_key("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn insert_with_options<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: V,
+        options: WriteOptions,
+    ) -> crate::Result<Instant> {
+        let (seqno, memtable_size, write_buffer_size) = self.write_item(
+            key.as_ref(),
+            value.as_ref(),
+            lsm_tree::ValueType::Value,
+            options.durability,
         )?;
-        drop(shard);
-
-        let (item_size, memtable_size) = self.tree.insert(key, value, seqno);
-
-        let write_buffer_size = self.write_buffer_manager.allocate(u64::from(item_size));
 
         self.check_memtable_overflow(memtable_size)?;
         self.check_write_buffer_size(write_buffer_size);
 
-        Ok(())
+        Ok(seqno)
     }
 
     /// Removes an item from the partition.
@@ -743,6 +1764,176 @@ impl PartitionHandle {
     ///
     /// Will return `Err` if an IO error occurs.
     pub fn remove<K: AsRef<[u8]>>(&self, key: K) -> crate::Result<()> {
+        self.remove_with_options(key, WriteOptions::default())
+            .map(|_| ())
+    }
+
+    /// Removes an item from the partition, using the given write options.
+    ///
+    /// Returns the seqno of the write. If a durability is set, the write
+    /// is synced to disk accordingly when this function returns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, PersistMode, WriteOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    ///
+    /// let options = WriteOptions::default().durability(Some(PersistMode::SyncData));
+    /// partition.remove_with_options("a", options)?;
+    ///
+    /// assert_eq!(None, partition.get("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn remove_with_options<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        options: WriteOptions,
+    ) -> crate::Result<Instant> {
+        let (seqno, memtable_size, write_buffer_size) = self.write_item(
+            key.as_ref(),
+            &[],
+            lsm_tree::ValueType::Tombstone,
+            options.durability,
+        )?;
+
+        self.check_memtable_overflow(memtable_size)?;
+        self.check_write_buffer_size(write_buffer_size);
+
+        Ok(seqno)
+    }
+
+    /// Atomically replaces the value of an item, if its current value is the expected one.
+    ///
+    /// `None` as the expected value expects the item not to exist;
+    /// `None` as the new value removes the item.
+    ///
+    /// Returns the seqno of the write. If the current value differs,
+    /// [`crate::Error::PreconditionFailed`] is returned, containing the current value.
+    ///
+    /// The check is atomic with respect to all other writes to the partition:
+    /// plain writes hold the partition's write lock shared until they are in the memtable,
+    /// while conditional writes hold it exclusively while checking and writing.
+    /// So a concurrent plain write is either seen by the check, or gets a newer seqno
+    /// and overwrites the swapped value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.compare_and_swap("a", None::<&str>, Some("abc"))?;
+    /// partition.compare_and_swap("a", Some("abc"), Some("def"))?;
+    ///
+    /// let result = partition.compare_and_swap("a", Some("abc"), Some("ghi"));
+    ///
+    /// match result {
+    ///     Err(fjall::Error::PreconditionFailed(current)) => {
+    ///         assert_eq!(Some("def".as_bytes().into()), current);
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the current value is not the expected one.
+    pub fn compare_and_swap<K: AsRef<[u8]>, E: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &self,
+        key: K,
+        expected: Option<E>,
+        new: Option<V>,
+    ) -> crate::Result<Instant> {
+        self.compare_and_swap_with_options(key, expected, new, WriteOptions::default())
+    }
+
+    /// Atomically replaces the value of an item, if its current value is the expected one,
+    /// using the given write options.
+    ///
+    /// See [`PartitionHandle::compare_and_swap`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, PersistMode, WriteOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let options = WriteOptions::default().durability(Some(PersistMode::SyncData));
+    /// partition.compare_and_swap_with_options("a", None::<&str>, Some("abc"), options)?;
+    ///
+    /// assert_eq!(Some("abc".as_bytes().into()), partition.get("a")?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs, or the current value is not the expected one.
+    pub fn compare_and_swap_with_options<K: AsRef<[u8]>, E: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &self,
+        key: K,
+        expected: Option<E>,
+        new: Option<V>,
+        options: WriteOptions,
+    ) -> crate::Result<Instant> {
+        let key = key.as_ref();
+
+        let condition = Precondition {
+            partition: self.clone(),
+            key: key.into(),
+            expected: Expected::Value(expected.map(|value| value.as_ref().into())),
+        };
+
+        let (seqno, memtable_size, write_buffer_size) = match new {
+            Some(value) => self.write_item_if(
+                key,
+                value.as_ref(),
+                lsm_tree::ValueType::Value,
+                options.durability,
+                &condition,
+            )?,
+            None => self.write_item_if(
+                key,
+                &[],
+                lsm_tree::ValueType::Tombstone,
+                options.durability,
+                &condition,
+            )?,
+        };
+
+        self.check_memtable_overflow(memtable_size)?;
+        self.check_write_buffer_size(write_buffer_size);
+
+        Ok(seqno)
+    }
+
+    /// Writes a single item to the journal and memtable, without stalling
+    ///
+    /// Returns the seqno of the write, and the memtable size and write buffer size
+    /// after the write, which need to be checked for overflow by the caller.
+    pub(crate) fn write_item(
+        &self,
+        key: &[u8],
+        value: &[u8],
+        value_type: lsm_tree::ValueType,
+        durability: Option<PersistMode>,
+    ) -> crate::Result<(Instant, u32, u64)> {
         if self.is_deleted.load(std::sync::atomic::Ordering::Relaxed) {
             return Err(crate::Error::PartitionDeleted);
         }
@@ -751,29 +1942,145 @@ impl PartitionHandle {
             return Err(crate::Error::Poisoned);
         }
 
-        let mut shard = self.journal.get_writer();
+        // NOTE: The journal item and the memtable write need to refer to the same tree,
+        // even if the partition is swapped concurrently
+        let slot = self.slot();
 
-        let seqno = self.seqno.next();
+        // NOTE: Lock order: journal shard -> write lock
+        let mut shard = slot.is_journaled.then(|| self.journal.get_writer());
 
-        /* let bytes_written = */
-        shard.writer.write(
-            &BatchItem {
-                key: key.as_ref().into(),
-                value: [].into(),
-                partition: self.name.clone(),
-                value_type: lsm_tree::ValueType::Tombstone,
-            },
-            seqno,
-        )?;
+        // IMPORTANT: Hold the write lock until the item is in the memtable,
+        // so a conditional write cannot take a newer seqno without seeing this write
+        let write_lock = slot.write_lock.read().expect("lock is poisoned");
+
+        // NOTE: Non-journaled writes only need a seqno
+        let (seqno, pending_sync) = if let Some(shard) = &mut shard {
+            self.write_to_journal(shard, slot.id, key, value, value_type, durability)?
+        } else {
+            (self.seqno.next(), None)
+        };
+
+        let (item_size, memtable_size) = match value_type {
+            lsm_tree::ValueType::Value => slot.tree.insert(key, value, seqno),
+            lsm_tree::ValueType::Tombstone => slot.tree.remove(key, seqno),
+        };
+
+        drop(write_lock);
         drop(shard);
 
-        let (item_size, memtable_size) = self.tree.remove(key, seqno);
+        let write_buffer_size = self.write_buffer_manager.allocate(u64::from(item_size));
+
+        // NOTE: Wait for the sync after releasing the journal shard,
+        // so concurrent writers can join the group commit
+        if let Some(pending_sync) = pending_sync {
+            pending_sync.wait(&self.journal, &self.seqno, &self.is_poisoned)?;
+        }
+
+        Ok((seqno, memtable_size, write_buffer_size))
+    }
+
+    /// Writes a single item to the journal, only if the precondition holds
+    ///
+    /// The precondition is checked while holding the write lock and the active memtable lock,
+    /// and the seqno is only assigned after, so no other write to this partition can slip in between.
+    pub(crate) fn write_item_if(
+        &self,
+        key: &[u8],
+        value: &[u8],
+        value_type: lsm_tree::ValueType,
+        durability: Option<PersistMode>,
+        condition: &Precondition,
+    ) -> crate::Result<(Instant, u32, u64)> {
+        if self.is_deleted.load(std::sync::atomic::Ordering::Relaxed) {
+            return Err(crate::Error::PartitionDeleted);
+        }
+
+        if self.is_poisoned.load(std::sync::atomic::Ordering::Relaxed) {
+            return Err(crate::Error::Poisoned);
+        }
+
+        let slot = self.slot();
+
+        // NOTE: Lock order: journal shard -> write lock -> flush manager -> levels -> active memtable
+        let mut shard = slot.is_journaled.then(|| self.journal.get_writer());
+        let write_lock = slot.write_lock.write().expect("lock is poisoned");
+        let flush_manager = self.flush_manager.read().expect("lock is poisoned");
+        let levels = slot.tree.levels.read().expect("lock is poisoned");
+        let active_memtable = slot.tree.lock_active_memtable();
+
+        let sealed_memtables = sealed_memtables(&flush_manager, &slot, &levels);
+        let latest = get_latest(&active_memtable, &sealed_memtables, &levels, key)?;
+
+        drop(levels);
+        drop(flush_manager);
+
+        if !condition.holds(latest.as_ref()) {
+            let current = latest
+                .filter(|item| !item.is_tombstone())
+                .map(|item| item.value);
+
+            return Err(crate::Error::PreconditionFailed(current));
+        }
+
+        let (seqno, pending_sync) = if let Some(shard) = &mut shard {
+            self.write_to_journal(shard, slot.id, key, value, value_type, durability)?
+        } else {
+            (self.seqno.next(), None)
+        };
+
+        let (item_size, memtable_size) = active_memtable.insert(lsm_tree::Value {
+            key: key.into(),
+            value: value.into(),
+            seqno,
+            value_type,
+        });
+
+        drop(active_memtable);
+        drop(write_lock);
+        drop(shard);
 
         let write_buffer_size = self.write_buffer_manager.allocate(u64::from(item_size));
 
-        self.check_memtable_overflow(memtable_size)?;
-        self.check_write_buffer_size(write_buffer_size);
+        // NOTE: Wait for the sync after releasing the journal shard,
+        // so concurrent writers can join the group commit
+        if let Some(pending_sync) = pending_sync {
+            pending_sync.wait(&self.journal, &self.seqno, &self.is_poisoned)?;
+        }
 
-        Ok(())
+        Ok((seqno, memtable_size, write_buffer_size))
+    }
+
+    /// Writes a single item to the journal, returning its seqno
+    fn write_to_journal(
+        &self,
+        shard: &mut JournalShard,
+        partition_id: PartitionId,
+        key: &[u8],
+        value: &[u8],
+        value_type: lsm_tree::ValueType,
+        durability: Option<PersistMode>,
+    ) -> crate::Result<(Instant, Option<PendingSync>)> {
+        // IMPORTANT: Lock replication sink before getting the seqno,
+        // so batches are shipped in seqno order
+        let mut replication = self.replication.lock();
+
+        let seqno = self.seqno.next();
+
+        let item = BatchItem {
+            key: key.into(),
+            value: value.into(),
+            partition: self.name(),
+            value_type,
+        };
+
+        shard.writer.write(partition_id, &item, seqno)?;
+        shard.mark_dirty(partition_id, seqno);
+        let pending_sync = shard.prepare_durability(seqno, durability)?;
+
+        if let Some(sink) = &mut replication {
+            sink.ship(&[&item], seqno);
+        }
+
+        Ok((seqno, pending_sync))
     }
 }
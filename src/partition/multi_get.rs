@@ -0,0 +1,196 @@
+use super::{read::sealed_memtables, PartitionHandle};
+use lsm_tree::{
+    merge::seqno_filter,
+    segment::value_block::{CachePolicy, ValueBlock},
+    GlobalSegmentId, Segment, SeqNo, UserValue, Value, ValueType,
+};
+use std::{ops::Bound::Included, sync::Arc};
+
+/// Key to look up, and its position in the input
+pub type Lookup<'a> = (&'a [u8], usize);
+
+/// Looks up keys of a partition at the given seqno,
+/// appending each value together with its position in the input
+///
+/// Instead of a separate point read per key, every memtable and segment is visited once,
+/// looking up all keys in ascending order, so keys that are stored in the same data block
+/// only need to look up and load that block once.
+///
+/// Duplicate keys are only looked up once.
+///
+/// The caller needs to keep the seqno alive using a snapshot,
+/// so compaction does not drop versions that are visible at that point in time.
+pub fn get_at(
+    partition: &PartitionHandle,
+    mut lookups: Vec<Lookup<'_>>,
+    seqno: SeqNo,
+    results: &mut Vec<(usize, Option<UserValue>)>,
+) -> crate::Result<()> {
+    lookups.sort_unstable();
+
+    let mut keys = lookups.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+    keys.dedup();
+
+    let latest = get_latest_at(partition, &keys, seqno)?;
+
+    // NOTE: Both lookups and keys are sorted, so they can be walked in lockstep
+    let mut latest = keys.into_iter().zip(latest);
+    let mut current = latest.next();
+
+    for (key, idx) in lookups {
+        while current.as_ref().is_some_and(|(k, _)| *k != key) {
+            current = latest.next();
+        }
+
+        let value = current
+            .as_ref()
+            .and_then(|(_, item)| item.as_ref())
+            .filter(|item| item.value_type != ValueType::Tombstone)
+            .map(|item| item.value.clone());
+
+        results.push((idx, value));
+    }
+
+    Ok(())
+}
+
+/// Restores the input order of looked up values
+pub fn into_input_order(mut results: Vec<(usize, Option<UserValue>)>) -> Vec<Option<UserValue>> {
+    results.sort_unstable_by_key(|(idx, _)| *idx);
+    results.into_iter().map(|(_, value)| value).collect()
+}
+
+/// Keeps the newer of both versions
+fn keep_latest(latest: &mut Option<Value>, item: Option<Value>) {
+    let Some(item) = item else {
+        return;
+    };
+
+    match latest {
+        Some(latest) if latest.seqno >= item.seqno => {}
+        _ => *latest = Some(item),
+    }
+}
+
+/// Returns the latest version of each of the sorted, deduplicated keys, including tombstones
+fn get_latest_at(
+    partition: &PartitionHandle,
+    keys: &[&[u8]],
+    seqno: SeqNo,
+) -> crate::Result<Vec<Option<Value>>> {
+    let slot = partition.slot();
+
+    // NOTE: Lock order: flush manager -> levels -> active memtable
+    let flush_manager = partition.flush_manager.read().expect("lock is poisoned");
+    let levels = slot.tree.levels.read().expect("lock is poisoned");
+
+    let memtables = sealed_memtables(&flush_manager, &slot, &levels);
+
+    let active_memtable = slot.tree.lock_active_memtable();
+
+    let mut latest = keys
+        .iter()
+        .map(|key| active_memtable.get(key, Some(seqno)))
+        .collect::<Vec<_>>();
+
+    drop(active_memtable);
+    drop(flush_manager);
+
+    for memtable in &memtables {
+        for (key, latest) in keys.iter().zip(&mut latest) {
+            keep_latest(latest, memtable.get(key, Some(seqno)));
+        }
+    }
+
+    // NOTE: The levels stay locked, so compaction cannot remove segments while reading them
+    for segment in levels.iter() {
+        // NOTE: The segment only contains versions that are too new
+        if segment.metadata.seqnos.0 >= seqno {
+            continue;
+        }
+
+        let segment_id: GlobalSegmentId = (slot.tree.id, segment.metadata.id).into();
+        let mut block: Option<Arc<ValueBlock>> = None;
+
+        for (key, latest) in keys.iter().zip(&mut latest) {
+            if !segment.metadata.key_range.contains_key(key) {
+                continue;
+            }
+
+            #[cfg(feature = "bloom")]
+            if !segment
+                .bloom_filter
+                .contains_hash(lsm_tree::bloom::BloomFilter::get_hash(key))
+            {
+                continue;
+            }
+
+            // NOTE: Keys are ascending, so the previously loaded block can be reused,
+            // as long as the key is not past its last item
+            let current = if let Some(block) = block
+                .as_ref()
+                .filter(|block| block.items.last().is_some_and(|last| **key <= *last.key))
+            {
+                block.clone()
+            } else {
+                let Some(handle) = segment
+                    .block_index
+                    .get_lowest_data_block_handle_containing_item(key, CachePolicy::Write)?
+                else {
+                    continue;
+                };
+
+                let loaded = ValueBlock::load_by_block_handle(
+                    &segment.descriptor_table,
+                    &segment.block_cache,
+                    segment_id,
+                    handle.offset,
+                    CachePolicy::Write,
+                )?
+                .expect("data block should exist");
+
+                block = Some(loaded.clone());
+                loaded
+            };
+
+            keep_latest(latest, get_from_block(&segment, &current, key, seqno)?);
+        }
+    }
+
+    Ok(latest)
+}
+
+/// Returns the latest version of a key inside a data block of a segment
+fn get_from_block(
+    segment: &Segment,
+    block: &ValueBlock,
+    key: &[u8],
+    seqno: SeqNo,
+) -> lsm_tree::Result<Option<Value>> {
+    // NOTE: Versions of a key are sorted by descending seqno
+    let start = block.items.partition_point(|item| &*item.key < key);
+
+    let item = block
+        .items
+        .iter()
+        .skip(start)
+        .take_while(|item| &*item.key == key)
+        .find(|item| seqno_filter(item.seqno, seqno));
+
+    if item.is_some() {
+        return Ok(item.cloned());
+    }
+
+    // NOTE: Older versions of the key may continue in the next data block
+    if block.items.last().is_some_and(|last| &*last.key == key) {
+        return segment
+            .range((Included(key.into()), Included(key.into())))
+            .find(|item| {
+                item.as_ref()
+                    .map_or(true, |item| seqno_filter(item.seqno, seqno))
+            })
+            .transpose();
+    }
+
+    Ok(None)
+}
@@ -1,16 +1,12 @@
-const VALID_CHARACTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_-";
-
-/// Partition names can be up to 255 characters long, can not be empty and
-/// can only contain alphanumerics, underscore (`_`) and dash (`-`).
+/// Partition names can be any UTF-8 string up to 255 bytes long, and can not be empty.
+///
+/// Partitions are identified by their ID on disk, so the name is not
+/// restricted by the file system, but it is stored with a length prefix of 1 byte.
 #[allow(clippy::module_name_repetitions)]
 pub fn is_valid_partition_name(s: &str) -> bool {
     if s.is_empty() {
         return false;
     }
 
-    if u8::try_from(s.len()).is_err() {
-        return false;
-    }
-
-    s.chars().all(|c| VALID_CHARACTERS.contains(c))
+    u8::try_from(s.len()).is_ok()
 }
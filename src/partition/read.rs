@@ -0,0 +1,861 @@
+use super::{PartitionHandle, PartitionSlot};
+use crate::{flush::manager::FlushManager, ReadOptions};
+use lsm_tree::{
+    levels::LevelManifest,
+    merge::{seqno_filter, BoxedIterator, MergeIterator},
+    segment::{
+        block::header::Header as BlockHeader,
+        value_block::{CachePolicy, ValueBlock},
+    },
+    serde::Deserializable,
+    GlobalSegmentId, KvPair, MemTable, Segment, SeqNo, Tree as LsmTree, UserKey, UserValue, Value,
+    ValueType,
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    io::{Read, Seek, SeekFrom},
+    ops::{
+        Bound::{self, Excluded, Included, Unbounded},
+        Range, RangeBounds,
+    },
+    sync::{Arc, Mutex, OnceLock, RwLock, Weak},
+};
+
+pub type Bounds = (Bound<UserKey>, Bound<UserKey>);
+
+/// Converts range bounds into owned key bounds
+pub fn to_owned_bounds<K: AsRef<[u8]>, R: RangeBounds<K>>(range: &R) -> Bounds {
+    let convert = |bound: Bound<&K>| match bound {
+        Included(key) => Included(key.as_ref().into()),
+        Excluded(key) => Excluded(key.as_ref().into()),
+        Unbounded => Unbounded,
+    };
+
+    (convert(range.start_bound()), convert(range.end_bound()))
+}
+
+/// Marks a data block that failed checksum verification
+///
+/// The merge iterator only passes through LSM-tree errors,
+/// so the mismatch is smuggled through as an I/O error and restored afterwards.
+#[derive(Debug)]
+struct ChecksumMismatch;
+
+impl std::fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "data block checksum mismatch")
+    }
+}
+
+impl std::error::Error for ChecksumMismatch {}
+
+fn into_crate_error(error: lsm_tree::Error) -> crate::Error {
+    match error {
+        lsm_tree::Error::Io(e)
+            if e.get_ref()
+                .and_then(|inner| inner.downcast_ref::<ChecksumMismatch>())
+                .is_some() =>
+        {
+            crate::Error::ChecksumMismatch
+        }
+        e => e.into(),
+    }
+}
+
+/// Converts a bound on `Value`s into a bound on the memtable's internal key type
+///
+/// The internal key type is not exported by `lsm-tree`, so it is inferred
+/// from `Value`'s `From` implementation.
+fn internal_key_bound<T: From<Value>>(
+    _: fn((T, UserValue)) -> Value,
+    bound: Bound<Value>,
+) -> Bound<T> {
+    match bound {
+        Included(value) => Included(value.into()),
+        Excluded(value) => Excluded(value.into()),
+        Unbounded => Unbounded,
+    }
+}
+
+fn bound_key(key: &UserKey, seqno: SeqNo, value_type: ValueType) -> Value {
+    Value {
+        key: key.clone(),
+        value: UserValue::from(&[][..]),
+        seqno,
+        value_type,
+    }
+}
+
+/// Converts user key bounds into bounds on the memtable's internal keys
+pub fn internal_bounds(bounds: &Bounds) -> (Bound<Value>, Bound<Value>) {
+    // NOTE: See lsm-tree's range.rs; items are sorted by key, then by descending seqno,
+    // so the lower bound needs the highest, and the upper bound the lowest seqno
+    let lo = match &bounds.0 {
+        Included(key) => Included(bound_key(key, SeqNo::MAX, ValueType::Tombstone)),
+        Excluded(key) => Excluded(bound_key(key, 0, ValueType::Tombstone)),
+        Unbounded => Unbounded,
+    };
+
+    let hi = match &bounds.1 {
+        Included(key) => Included(bound_key(key, 0, ValueType::Value)),
+        Excluded(key) => Excluded(bound_key(key, SeqNo::MAX, ValueType::Value)),
+        Unbounded => Unbounded,
+    };
+
+    (lo, hi)
+}
+
+/// Returns all versions of items inside the internal key bounds
+pub fn memtable_range<'a>(
+    memtable: &'a MemTable,
+    (lo, hi): &(Bound<Value>, Bound<Value>),
+) -> impl DoubleEndedIterator<Item = Value> + 'a {
+    memtable.range((
+        internal_key_bound(Value::from, lo.clone()),
+        internal_key_bound(Value::from, hi.clone()),
+    ))
+}
+
+/// Iterates over all versions of items of a shared memtable inside some bounds
+///
+/// Every step seeks past the previously returned version,
+/// so the iterator does not need to borrow the memtable.
+struct MemtableIter {
+    memtable: Arc<MemTable>,
+    bounds: (Bound<Value>, Bound<Value>),
+}
+
+impl Iterator for MemtableIter {
+    type Item = lsm_tree::Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = memtable_range(&self.memtable, &self.bounds).next()?;
+        self.bounds.0 = Excluded(item.clone());
+        Some(Ok(item))
+    }
+}
+
+impl DoubleEndedIterator for MemtableIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let item = memtable_range(&self.memtable, &self.bounds).next_back()?;
+        self.bounds.1 = Excluded(item.clone());
+        Some(Ok(item))
+    }
+}
+
+/// Uncommitted writes of a transaction, which are merged into its reads
+///
+/// Every write is appended as a new version of its key, so rolling back
+/// to a savepoint only needs to mark the seqnos written since as rolled back.
+#[derive(Clone)]
+pub struct PendingWrites {
+    pub memtable: Arc<MemTable>,
+
+    /// Sorted, disjoint seqno ranges of rolled back writes
+    pub rolled_back: Arc<Vec<Range<SeqNo>>>,
+}
+
+impl PendingWrites {
+    /// Returns `true` if the write with the given seqno was rolled back
+    pub fn is_rolled_back(&self, seqno: SeqNo) -> bool {
+        let idx = self.rolled_back.partition_point(|range| range.end <= seqno);
+
+        self.rolled_back
+            .get(idx)
+            .is_some_and(|range| range.contains(&seqno))
+    }
+
+    /// Returns the newest version of the key that was not rolled back
+    pub fn get(&self, key: &[u8]) -> Option<Value> {
+        let key = UserKey::from(key);
+        let bounds = internal_bounds(&(Included(key.clone()), Included(key)));
+
+        memtable_range(&self.memtable, &bounds).find(|item| !self.is_rolled_back(item.seqno))
+    }
+
+    /// Returns the newest version of every key that was not rolled back, including tombstones
+    pub fn iter(&self) -> impl Iterator<Item = Value> + '_ {
+        let mut prev_key: Option<UserKey> = None;
+
+        self.memtable.iter().filter(move |item| {
+            if self.is_rolled_back(item.seqno) || prev_key.as_ref() == Some(&item.key) {
+                return false;
+            }
+
+            prev_key = Some(item.key.clone());
+            true
+        })
+    }
+
+    /// Iterates over all versions inside the bounds that were not rolled back
+    fn into_range(
+        self,
+        bounds: &Bounds,
+    ) -> impl DoubleEndedIterator<Item = lsm_tree::Result<Value>> {
+        MemtableIter {
+            memtable: self.memtable.clone(),
+            bounds: internal_bounds(bounds),
+        }
+        .filter(move |item| {
+            item.as_ref()
+                .map_or(true, |item| !self.is_rolled_back(item.seqno))
+        })
+    }
+}
+
+/// Amount of items loaded from the active memtable while locking it
+const ACTIVE_MEMTABLE_CHUNK_SIZE: usize = 128;
+
+/// Receives the active memtable of a tree once it is sealed
+type SealedSlot = OnceLock<Arc<MemTable>>;
+
+/// Iterators over the active memtable of a tree, which receive it once it is sealed
+///
+/// Memtables are only sealed while holding the flush manager write lock,
+/// and iterators hold the flush manager read lock while loading items,
+/// so an iterator either finds the memtable still active, or has already received it.
+#[derive(Clone, Default)]
+pub struct MemtableReaders(Arc<Mutex<Vec<Weak<SealedSlot>>>>);
+
+impl MemtableReaders {
+    /// Registers an iterator over the active memtable
+    ///
+    /// Needs to be called while holding the flush manager lock.
+    fn register(&self) -> Arc<SealedSlot> {
+        let mut readers = self.0.lock().expect("lock is poisoned");
+
+        // NOTE: Forget finished iterators, so the list does not grow while the memtable is not sealed
+        readers.retain(|reader| reader.strong_count() > 0);
+
+        let reader = Arc::<SealedSlot>::default();
+        readers.push(Arc::downgrade(&reader));
+        reader
+    }
+
+    /// Hands the sealed memtable to all registered iterators
+    ///
+    /// Needs to be called right after sealing the memtable, while holding the flush manager write lock.
+    pub fn seal(&self, memtable: &Arc<MemTable>) {
+        let readers = std::mem::take(&mut *self.0.lock().expect("lock is poisoned"));
+
+        for reader in readers.iter().filter_map(Weak::upgrade) {
+            // NOTE: Readers are forgotten once they receive a memtable, so the slot is still empty
+            let _ = reader.set(memtable.clone());
+        }
+    }
+}
+
+/// Iterates over all versions of items of the active memtable inside some bounds
+///
+/// The memtable is only locked while loading a few items at a time,
+/// so the scan does not copy the memtable and writers are not blocked until it ends.
+/// Once the memtable is sealed, the rest is read from the sealed memtable.
+struct ActiveMemtableIter {
+    tree: LsmTree,
+    flush_manager: Arc<RwLock<FlushManager>>,
+    sealed: Arc<SealedSlot>,
+
+    /// Bounds of the items that have not been loaded yet
+    bounds: (Bound<Value>, Bound<Value>),
+
+    /// `true` once no items are left to load
+    is_exhausted: bool,
+
+    /// Items loaded from the front
+    front: VecDeque<Value>,
+
+    /// Items loaded from the back
+    back: VecDeque<Value>,
+}
+
+impl ActiveMemtableIter {
+    /// Calls `f` with the memtable, locking it if it is still active
+    fn with_memtable<T>(&self, f: impl FnOnce(&MemTable) -> T) -> T {
+        if let Some(memtable) = self.sealed.get() {
+            return f(memtable);
+        }
+
+        // NOTE: Lock order: flush manager -> active memtable
+        let _flush_manager = self.flush_manager.read().expect("lock is poisoned");
+
+        if let Some(memtable) = self.sealed.get() {
+            return f(memtable);
+        }
+
+        f(&self.tree.lock_active_memtable())
+    }
+
+    fn load_front(&mut self) {
+        let items = self.with_memtable(|memtable| {
+            memtable_range(memtable, &self.bounds)
+                .take(ACTIVE_MEMTABLE_CHUNK_SIZE)
+                .collect::<VecDeque<_>>()
+        });
+
+        match items.back() {
+            Some(last) => self.bounds.0 = Excluded(last.clone()),
+            None => self.is_exhausted = true,
+        }
+
+        self.front = items;
+    }
+
+    fn load_back(&mut self) {
+        let items = self.with_memtable(|memtable| {
+            memtable_range(memtable, &self.bounds)
+                .rev()
+                .take(ACTIVE_MEMTABLE_CHUNK_SIZE)
+                .collect::<VecDeque<_>>()
+        });
+
+        match items.back() {
+            Some(first) => self.bounds.1 = Excluded(first.clone()),
+            None => self.is_exhausted = true,
+        }
+
+        self.back = items.into_iter().rev().collect();
+    }
+}
+
+impl Iterator for ActiveMemtableIter {
+    type Item = lsm_tree::Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.front.pop_front() {
+                return Some(Ok(item));
+            }
+
+            // NOTE: All items are loaded, the rest was loaded from the back
+            if self.is_exhausted {
+                return self.back.pop_front().map(Ok);
+            }
+
+            self.load_front();
+        }
+    }
+}
+
+impl DoubleEndedIterator for ActiveMemtableIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.back.pop_back() {
+                return Some(Ok(item));
+            }
+
+            // NOTE: All items are loaded, the rest was loaded from the front
+            if self.is_exhausted {
+                return self.front.pop_back().map(Ok);
+            }
+
+            self.load_back();
+        }
+    }
+}
+
+/// Returns the sealed memtables of a partition that are not yet registered as segments
+pub fn sealed_memtables(
+    flush_manager: &FlushManager,
+    slot: &PartitionSlot,
+    levels: &LevelManifest,
+) -> Vec<Arc<MemTable>> {
+    let segment_ids = levels
+        .iter()
+        .map(|segment| segment.metadata.id)
+        .collect::<HashSet<_>>();
+
+    // NOTE: A memtable may have been flushed and registered as a segment already,
+    // while its flush task is not dequeued yet
+    flush_manager
+        .queues
+        .get(&slot.id)
+        .map(|queue| {
+            queue
+                .iter()
+                .filter(|task| !segment_ids.contains(&task.id))
+                .map(|task| task.sealed_memtable.clone())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the latest version of an item, including tombstones
+///
+/// Used while the caller holds the active memtable lock,
+/// so the tree's own read path, which locks the active memtable, cannot be used.
+pub fn get_latest(
+    active_memtable: &MemTable,
+    sealed_memtables: &[Arc<MemTable>],
+    levels: &LevelManifest,
+    key: &[u8],
+) -> lsm_tree::Result<Option<Value>> {
+    let mut latest = active_memtable.get(key, None);
+
+    let mut keep_latest = |item: Option<Value>| {
+        let Some(item) = item else {
+            return;
+        };
+
+        match &latest {
+            Some(latest) if latest.seqno >= item.seqno => {}
+            _ => latest = Some(item),
+        }
+    };
+
+    for memtable in sealed_memtables {
+        keep_latest(memtable.get(key, None));
+    }
+
+    #[cfg(feature = "bloom")]
+    let key_hash = lsm_tree::bloom::BloomFilter::get_hash(key);
+
+    for segment in levels.iter() {
+        if segment.metadata.key_range.contains_key(key) {
+            #[cfg(not(feature = "bloom"))]
+            let item = segment.get(key, None)?;
+            #[cfg(feature = "bloom")]
+            let item = segment.get_with_hash(key, None, key_hash)?;
+
+            keep_latest(item);
+        }
+    }
+
+    Ok(latest)
+}
+
+/// The segments and memtables of a partition that may contain items inside some bounds
+pub struct ReadView {
+    tree: LsmTree,
+
+    segments: Vec<Arc<Segment>>,
+    memtables: Vec<Arc<MemTable>>,
+
+    /// Used to lock the active memtable
+    flush_manager: Arc<RwLock<FlushManager>>,
+
+    /// Receives the active memtable once it is sealed
+    active_memtable: Arc<SealedSlot>,
+}
+
+impl ReadView {
+    /// Gathers the segments and memtables of the partition.
+    ///
+    /// Sealed memtables are immutable and shared; the active memtable
+    /// is read lazily, see [`ActiveMemtableIter`].
+    pub fn new(partition: &PartitionHandle, slot: &PartitionSlot, bounds: &Bounds) -> Self {
+        // IMPORTANT: Memtables are sealed and enqueued for flushing while holding the flush manager lock,
+        // and flushed memtables are only dequeued after being registered as segments,
+        // so while holding the flush manager lock, every memtable is either active, sealed or flushed
+        //
+        // NOTE: Lock order: flush manager -> levels
+        let flush_manager = partition.flush_manager.read().expect("lock is poisoned");
+        let levels = slot.tree.levels.read().expect("lock is poisoned");
+
+        let segments = levels
+            .iter()
+            .filter(|segment| segment.metadata.key_range.overlaps_with_bounds(bounds))
+            .collect::<Vec<_>>();
+
+        let memtables = sealed_memtables(&flush_manager, slot, &levels);
+
+        // IMPORTANT: Register while holding the flush manager lock,
+        // so the active memtable cannot be sealed in between
+        let active_memtable = slot.memtable_readers.register();
+
+        drop(levels);
+        drop(flush_manager);
+
+        Self {
+            tree: slot.tree.clone(),
+            segments,
+            memtables,
+            flush_manager: partition.flush_manager.clone(),
+            active_memtable,
+        }
+    }
+}
+
+/// Reads the data blocks of a segment inside some bounds, honoring [`ReadOptions`]
+///
+/// `lsm-tree`'s segment reader always inserts loaded blocks into the block cache.
+struct SegmentReader {
+    /// Keeps the tree alive while reading, so its folder is not removed,
+    /// e.g. when the partition is cleared concurrently
+    tree: LsmTree,
+
+    segment: Arc<Segment>,
+    bounds: Bounds,
+    options: ReadOptions,
+
+    is_initialized: bool,
+
+    /// Offsets of the first and last data block that have not been loaded yet
+    unread: Option<(u64, u64)>,
+
+    /// Items of blocks loaded from the front
+    front: VecDeque<Value>,
+
+    /// Items of blocks loaded from the back
+    back: VecDeque<Value>,
+}
+
+impl SegmentReader {
+    fn new(tree: LsmTree, segment: Arc<Segment>, bounds: Bounds, options: ReadOptions) -> Self {
+        Self {
+            tree,
+            segment,
+            bounds,
+            options,
+            is_initialized: false,
+            unread: None,
+            front: VecDeque::new(),
+            back: VecDeque::new(),
+        }
+    }
+
+    fn segment_id(&self) -> GlobalSegmentId {
+        (self.tree.id, self.segment.metadata.id).into()
+    }
+
+    fn cache_policy(&self) -> CachePolicy {
+        if self.options.fill_cache {
+            CachePolicy::Write
+        } else {
+            CachePolicy::Read
+        }
+    }
+
+    /// Finds the first and last data block that may contain items inside the bounds
+    fn initialize(&mut self) -> lsm_tree::Result<()> {
+        let block_index = &self.segment.block_index;
+        let cache_policy = self.cache_policy();
+
+        let lo = match &self.bounds.0 {
+            Included(key) | Excluded(key) => block_index
+                .get_lowest_data_block_handle_containing_item(key, cache_policy)?
+                .map_or(0, |handle| handle.offset),
+            Unbounded => 0,
+        };
+
+        let hi = match &self.bounds.1 {
+            Included(key) | Excluded(key) => block_index
+                .get_lowest_data_block_handle_not_containing_item(key, cache_policy)?
+                .map(|handle| handle.offset),
+            Unbounded => None,
+        };
+
+        let hi = match hi {
+            Some(offset) => offset,
+            None => block_index.get_last_data_block_handle(cache_policy)?.offset,
+        };
+
+        self.unread = (lo <= hi).then_some((lo, hi));
+        self.is_initialized = true;
+
+        Ok(())
+    }
+
+    fn load_block(&self, offset: u64) -> lsm_tree::Result<Arc<ValueBlock>> {
+        let block = ValueBlock::load_by_block_handle(
+            &self.segment.descriptor_table,
+            &self.segment.block_cache,
+            self.segment_id(),
+            offset,
+            self.cache_policy(),
+        )?
+        .expect("data block should exist");
+
+        self.check_block(offset, &block)?;
+
+        Ok(block)
+    }
+
+    fn check_block(&self, offset: u64, block: &ValueBlock) -> lsm_tree::Result<()> {
+        if self.options.verify_checksums
+            && ValueBlock::create_crc(&block.items)? != block.header.crc
+        {
+            log::error!(
+                "Checksum mismatch in data block {:?}/{offset}",
+                self.segment_id()
+            );
+
+            return Err(lsm_tree::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                ChecksumMismatch,
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Reads `readahead` bytes starting at the given data block at once,
+    /// returning the blocks that were read completely, up to the last data block
+    fn read_ahead(&self, lo: u64, hi: u64) -> lsm_tree::Result<Vec<(u64, Arc<ValueBlock>)>> {
+        let segment_id = self.segment_id();
+
+        let mut bytes = Vec::new();
+
+        {
+            let file_guard = self
+                .segment
+                .descriptor_table
+                .access(&segment_id)?
+                .expect("should acquire file handle");
+
+            let mut file = file_guard.file.lock().expect("lock is poisoned");
+            file.seek(SeekFrom::Start(lo))?;
+            file.by_ref()
+                .take(self.options.readahead)
+                .read_to_end(&mut bytes)?;
+        }
+
+        let mut blocks = Vec::new();
+        let mut offset = lo;
+        let mut rest = bytes.as_slice();
+
+        while offset <= hi {
+            // NOTE: The last block may be cut off, it is loaded by the next read
+            let Ok(header) = BlockHeader::deserialize(&mut &*rest) else {
+                break;
+            };
+
+            let block_len = BlockHeader::serialized_len() + header.data_length as usize;
+
+            if rest.len() < block_len {
+                break;
+            }
+
+            let (mut block_bytes, tail) = rest.split_at(block_len);
+            rest = tail;
+
+            let block = Arc::new(ValueBlock::from_reader_compressed(&mut block_bytes)?);
+            self.check_block(offset, &block)?;
+
+            if self.options.fill_cache {
+                self.segment
+                    .block_cache
+                    .insert_disk_block(segment_id, offset, block.clone());
+            }
+
+            blocks.push((offset, block));
+            offset += block_len as u64;
+        }
+
+        Ok(blocks)
+    }
+
+    fn items_in_bounds<'a>(
+        bounds: &'a Bounds,
+        block: &'a ValueBlock,
+    ) -> impl DoubleEndedIterator<Item = Value> + 'a {
+        block
+            .items
+            .iter()
+            .filter(|item| bounds.contains(&item.key))
+            .cloned()
+    }
+
+    /// Loads the next data block from the front
+    ///
+    /// If the block is not cached, and readahead is enabled,
+    /// subsequent blocks are read from disk at once.
+    fn load_front(&mut self) -> lsm_tree::Result<()> {
+        let Some((lo, hi)) = self.unread else {
+            return Ok(());
+        };
+
+        let is_cached = self
+            .segment
+            .block_cache
+            .get_disk_block(self.segment_id(), lo)
+            .is_some();
+
+        let mut blocks = if self.options.readahead > 0 && !is_cached {
+            self.read_ahead(lo, hi)?
+        } else {
+            vec![]
+        };
+
+        // NOTE: The block is cached, readahead is disabled, or the block is larger than the readahead
+        if blocks.is_empty() {
+            blocks.push((lo, self.load_block(lo)?));
+        }
+
+        for (offset, block) in blocks {
+            let next_offset =
+                offset + BlockHeader::serialized_len() as u64 + u64::from(block.header.data_length);
+
+            self.unread = (offset < hi).then_some((next_offset, hi));
+
+            self.front
+                .extend(Self::items_in_bounds(&self.bounds, &block));
+        }
+
+        Ok(())
+    }
+
+    /// Loads the previous data block from the back
+    fn load_back(&mut self) -> lsm_tree::Result<()> {
+        if let Some((lo, hi)) = self.unread {
+            let block = self.load_block(hi)?;
+
+            self.unread = (lo < hi).then_some((lo, block.header.previous_block_offset));
+
+            for item in Self::items_in_bounds(&self.bounds, &block).rev() {
+                self.back.push_front(item);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Iterator for SegmentReader {
+    type Item = lsm_tree::Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.is_initialized {
+            if let Err(e) = self.initialize() {
+                return Some(Err(e));
+            }
+        }
+
+        loop {
+            if let Some(item) = self.front.pop_front() {
+                return Some(Ok(item));
+            }
+
+            // NOTE: All blocks are loaded, the rest was loaded from the back
+            if self.unread.is_none() {
+                return self.back.pop_front().map(Ok);
+            }
+
+            if let Err(e) = self.load_front() {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl DoubleEndedIterator for SegmentReader {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if !self.is_initialized {
+            if let Err(e) = self.initialize() {
+                return Some(Err(e));
+            }
+        }
+
+        loop {
+            if let Some(item) = self.back.pop_back() {
+                return Some(Ok(item));
+            }
+
+            // NOTE: All blocks are loaded, the rest was loaded from the front
+            if self.unread.is_none() {
+                return self.front.pop_back().map(Ok);
+            }
+
+            if let Err(e) = self.load_back() {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Creates iterators over all versions of items of a [`ReadView`] inside the bounds
+fn view_iters(
+    view: ReadView,
+    bounds: &Bounds,
+    seqno: Option<SeqNo>,
+    options: ReadOptions,
+) -> Vec<BoxedIterator<'static>> {
+    let mut iters: Vec<BoxedIterator<'static>> = Vec::new();
+
+    for segment in view.segments {
+        let reader = SegmentReader::new(view.tree.clone(), segment, bounds.clone(), options);
+
+        if let Some(seqno) = seqno {
+            iters.push(Box::new(reader.filter(move |item| {
+                item.as_ref()
+                    .map_or(true, |item| seqno_filter(item.seqno, seqno))
+            })));
+        } else {
+            iters.push(Box::new(reader));
+        }
+    }
+
+    let mut memtable_iters: Vec<BoxedIterator<'static>> = view
+        .memtables
+        .into_iter()
+        .map(|memtable| -> BoxedIterator<'static> {
+            Box::new(MemtableIter {
+                memtable,
+                bounds: internal_bounds(bounds),
+            })
+        })
+        .collect();
+
+    memtable_iters.push(Box::new(ActiveMemtableIter {
+        tree: view.tree,
+        flush_manager: view.flush_manager,
+        sealed: view.active_memtable,
+        bounds: internal_bounds(bounds),
+        is_exhausted: false,
+        front: VecDeque::new(),
+        back: VecDeque::new(),
+    }));
+
+    for iter in memtable_iters {
+        if let Some(seqno) = seqno {
+            iters.push(Box::new(iter.filter(move |item| {
+                item.as_ref()
+                    .map_or(true, |item| seqno_filter(item.seqno, seqno))
+            })));
+        } else {
+            iters.push(iter);
+        }
+    }
+
+    iters
+}
+
+/// Creates an iterator over the items inside the bounds, reading segments block by block
+///
+/// Behaves like `lsm-tree`'s own range iterator, but honors [`ReadOptions`].
+pub fn create_range(
+    view: ReadView,
+    bounds: &Bounds,
+    seqno: Option<SeqNo>,
+    pending: Option<PendingWrites>,
+    options: ReadOptions,
+) -> impl DoubleEndedIterator<Item = crate::Result<KvPair>> {
+    let mut iters = view_iters(view, bounds, seqno, options);
+
+    // NOTE: Uncommitted writes of a transaction are never filtered by seqno
+    if let Some(pending) = pending {
+        iters.push(Box::new(pending.into_range(bounds)));
+    }
+
+    MergeIterator::new(iters)
+        .evict_old_versions(true)
+        .filter(|item| {
+            item.as_ref()
+                .map_or(true, |item| item.value_type != ValueType::Tombstone)
+        })
+        .map(|item| match item {
+            Ok(item) => Ok((item.key, item.value)),
+            Err(e) => Err(into_crate_error(e)),
+        })
+}
+
+/// Creates an iterator over every retained version of the items inside the bounds,
+/// including tombstones
+///
+/// Versions of the same key are returned from newest to oldest.
+pub fn create_versions(
+    view: ReadView,
+    bounds: &Bounds,
+) -> impl DoubleEndedIterator<Item = crate::Result<Value>> {
+    MergeIterator::new(view_iters(view, bounds, None, ReadOptions::default()))
+        .map(|item| item.map_err(into_crate_error))
+}
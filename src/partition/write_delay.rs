@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+/// Delay of a write, because background work is not keeping up with the write load
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum WriteDelay {
+    /// Writes are halted: wait, then check again
+    Halt(Duration),
+
+    /// Writes are slowed down: wait once, then proceed
+    Stall(Duration),
+}
+
+/// Blocks the current thread until the check allows the write to proceed
+pub fn wait_blocking<F: FnMut() -> Option<WriteDelay>>(mut check: F) {
+    loop {
+        match check() {
+            Some(WriteDelay::Halt(duration)) => std::thread::sleep(duration),
+            Some(WriteDelay::Stall(duration)) => {
+                std::thread::sleep(duration);
+                return;
+            }
+            None => return,
+        }
+    }
+}
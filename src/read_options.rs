@@ -0,0 +1,105 @@
+use crate::Instant;
+
+/// Options for a single read operation
+///
+/// # Examples
+///
+/// ```
+/// # use fjall::{Config, PartitionCreateOptions, ReadOptions};
+/// #
+/// # let folder = tempfile::tempdir()?;
+/// # let keyspace = Config::new(folder).open()?;
+/// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+/// partition.insert("a", "abc")?;
+///
+/// // Scan without evicting hot blocks from the block cache
+/// let options = ReadOptions::default().fill_cache(false).readahead(256 * 1_024);
+///
+/// for kv in partition.range_with_options::<&str, _>(.., options) {
+///     let (key, value) = kv?;
+/// }
+/// #
+/// # Ok::<(), fjall::Error>(())
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[allow(clippy::module_name_repetitions)]
+pub struct ReadOptions {
+    /// Whether blocks loaded from disk are inserted into the block cache
+    pub(crate) fill_cache: bool,
+
+    /// Whether data blocks are checked against their checksum
+    pub(crate) verify_checksums: bool,
+
+    /// Amount of bytes to read ahead when scanning forwards
+    pub(crate) readahead: u64,
+
+    /// Instant to read at
+    pub(crate) snapshot: Option<Instant>,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            fill_cache: true,
+            verify_checksums: false,
+            readahead: 0,
+            snapshot: None,
+        }
+    }
+}
+
+impl ReadOptions {
+    /// Sets whether blocks loaded from disk are inserted into the block cache.
+    ///
+    /// Blocks that are already cached are still used.
+    /// Disabling this for large scans prevents them from evicting
+    /// hot blocks of other readers.
+    ///
+    /// Default = true
+    #[must_use]
+    pub fn fill_cache(mut self, enabled: bool) -> Self {
+        self.fill_cache = enabled;
+        self
+    }
+
+    /// Sets whether data blocks are checked against their checksum when read.
+    ///
+    /// A corrupted block results in [`crate::Error::ChecksumMismatch`].
+    ///
+    /// Default = false
+    #[must_use]
+    pub fn verify_checksums(mut self, enabled: bool) -> Self {
+        self.verify_checksums = enabled;
+        self
+    }
+
+    /// Sets the amount of bytes that are read ahead when scanning forwards.
+    ///
+    /// When a data block needs to be loaded from disk, this many bytes are read at once,
+    /// so the following data blocks do not need separate I/O operations.
+    /// Blocks that are larger than the readahead are read on their own.
+    ///
+    /// Default = 0
+    #[must_use]
+    pub fn readahead(mut self, bytes: u64) -> Self {
+        self.readahead = bytes;
+        self
+    }
+
+    /// Sets the instant to read at.
+    ///
+    /// If `None`, the latest state is read.
+    /// Transactions ignore this option and read at their own instant.
+    ///
+    /// Default = None
+    #[must_use]
+    pub fn snapshot(mut self, instant: Option<Instant>) -> Self {
+        self.snapshot = instant;
+        self
+    }
+
+    /// Returns `true` if the read can not be served by the LSM-tree's own read path.
+    pub(crate) fn needs_block_reader(&self) -> bool {
+        !self.fill_cache || self.verify_checksums || self.readahead > 0
+    }
+}
@@ -1,127 +1,249 @@
 use crate::{
     batch::PartitionKey,
+    config::Config,
     file::{
-        FLUSH_MARKER, FLUSH_PARTITIONS_LIST, JOURNALS_FOLDER, PARTITIONS_FOLDER,
-        PARTITION_DELETED_MARKER,
+        fsync_directory, FLUSH_MARKER, FLUSH_PARTITIONS_LIST, JOURNALS_FOLDER, JOURNAL_MANIFEST,
+        LEGACY_FLUSH_PARTITIONS_LIST, PARTITIONS_FOLDER, SEQNO_MARKER,
     },
     journal::Journal,
-    partition::PartitionHandleInner,
+    manifest::{Manifest, PartitionEntry, PartitionId},
+    partition::{read::MemtableReaders, PartitionHandleInner, PartitionSlot},
     Keyspace, PartitionHandle,
 };
-use lsm_tree::MemTable;
+use lsm_tree::{MemTable, SeqNo, Tree as LsmTree};
 use std::{
-    collections::HashMap,
-    sync::{atomic::AtomicBool, Arc, RwLock},
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicBool, Arc, RwLock, Weak},
 };
 
-const LSM_VERSION_MARKER_FILE: &str = "version";
-
-/// Recovers partitions
-pub fn recover_partitions(
+/// Recovers a single partition from disk
+///
+/// If given, the memtable recovered from the active journal becomes the partition's active memtable.
+pub fn recover_partition(
     keyspace: &Keyspace,
-    memtables: &mut HashMap<PartitionKey, MemTable>,
-) -> crate::Result<()> {
-    let partitions_folder = keyspace.config.path.join(PARTITIONS_FOLDER);
-
-    for dirent in std::fs::read_dir(&partitions_folder)? {
-        let dirent = dirent?;
-        let partition_name = dirent.file_name();
-        let partition_path = dirent.path();
+    partition_name: &str,
+    recovered_memtable: Option<MemTable>,
+) -> crate::Result<PartitionHandle> {
+    log::trace!("Recovering partition {partition_name:?}");
+
+    // NOTE: The partition may have been deleted concurrently
+    let Some((partition_id, entry)) = keyspace.manifest.find(partition_name) else {
+        return Err(crate::Error::PartitionDeleted);
+    };
 
-        log::trace!("Recovering partition {:?}", partition_name);
+    let path = keyspace
+        .config
+        .path
+        .join(PARTITIONS_FOLDER)
+        .join(partition_id.to_string());
+
+    let tree = lsm_tree::Config::new(path)
+        .descriptor_table(keyspace.config.descriptor_table.clone())
+        .block_cache(keyspace.config.block_cache.clone())
+        .open()?;
+
+    let partition_inner = PartitionHandleInner {
+        max_memtable_size: (8 * 1_024 * 1_024).into(),
+        compaction_strategy: RwLock::new(Arc::new(lsm_tree::compaction::Levelled::default())),
+        name: RwLock::new(partition_name.into()),
+        manifest: keyspace.manifest.clone(),
+        slot: RwLock::new(PartitionSlot {
+            id: partition_id,
+            is_journaled: entry.journaled,
+            tree: tree.clone(),
+            write_lock: Arc::default(),
+            memtable_readers: MemtableReaders::default(),
+        }),
+        partitions: keyspace.partitions.clone(),
+        unloaded_partitions: keyspace.unloaded_partitions.clone(),
+        keyspace_config: keyspace.config.clone(),
+        flush_manager: keyspace.flush_manager.clone(),
+        flush_semaphore: keyspace.flush_semaphore.clone(),
+        journal_manager: keyspace.journal_manager.clone(),
+        journal: keyspace.journal.clone(),
+        compaction_manager: keyspace.compaction_manager.clone(),
+        seqno: keyspace.seqno.clone(),
+        seqno_marker: keyspace.seqno_marker.clone(),
+        obsolete_trees: keyspace.obsolete_trees.clone(),
+        write_buffer_manager: keyspace.write_buffer_manager.clone(),
+        is_deleted: AtomicBool::default(),
+        is_poisoned: keyspace.is_poisoned.clone(),
+        replication: keyspace.replication.clone(),
+    };
+    let partition_inner = Arc::new(partition_inner);
+    let partition = PartitionHandle(partition_inner);
+
+    // NOTE: We already recovered all active memtables from the active journal,
+    // so just yank it out and give to the partition
+    if let Some(recovered_memtable) = recovered_memtable {
+        log::trace!(
+            "Recovered previously active memtable for {:?}, with size: {} B",
+            partition_name,
+            recovered_memtable.size()
+        );
+
+        // IMPORTANT: Add active memtable size to current write buffer size
+        keyspace
+            .write_buffer_manager
+            .allocate(recovered_memtable.size().into());
 
-        // IMPORTANT: Check deletion marker
-        if partition_path.join(PARTITION_DELETED_MARKER).try_exists()? {
-            log::debug!("Deleting deleted partition {:?}", partition_name);
-            std::fs::remove_dir_all(partition_path)?;
-            continue;
+        // IMPORTANT: The recovered data lives in the active journal,
+        // so the partition needs to be listed when the journal is sealed
+        if let Some(lsn) = recovered_memtable.get_lsn() {
+            keyspace.journal.mark_dirty(partition_id, lsn);
         }
 
-        // Check for marker, maybe the partition is not fully initialized
-        if !partition_path.join(LSM_VERSION_MARKER_FILE).try_exists()? {
-            log::debug!("Deleting uninitialized partition {:?}", partition_name);
-            std::fs::remove_dir_all(partition_path)?;
+        tree.set_active_memtable(recovered_memtable);
+    }
+
+    // Recover seqno
+    let maybe_next_seqno = tree.get_lsn().map(|x| x + 1).unwrap_or_default();
+    keyspace
+        .seqno
+        .fetch_max(maybe_next_seqno, std::sync::atomic::Ordering::AcqRel);
+
+    log::debug!("Keyspace seqno is now {}", keyspace.seqno.get());
+
+    log::trace!("Recovered partition {partition_name:?}");
+
+    Ok(partition)
+}
+
+/// Reads the partitions (and their highest seqno) that have data in a sealed journal
+///
+/// Journals sealed before partitions had IDs list partitions by name,
+/// which are mapped to an ID using `resolve_name`.
+fn read_sealed_journal_partitions(
+    journal_path: &Path,
+    resolve_name: &dyn Fn(&str) -> Option<PartitionId>,
+) -> crate::Result<Vec<(PartitionId, SeqNo)>> {
+    use crate::journal::partition_manifest::{
+        Error as PartitionManifestParseError, PartitionManifest,
+    };
+
+    let path = journal_path.join(FLUSH_PARTITIONS_LIST);
+    let is_legacy = !path.try_exists()?;
+
+    let file_content = if is_legacy {
+        std::fs::read_to_string(journal_path.join(LEGACY_FLUSH_PARTITIONS_LIST))?
+    } else {
+        std::fs::read_to_string(path)?
+    };
+
+    let entries = match PartitionManifest::from_str(&file_content) {
+        Ok(v) => Ok(v),
+        Err(e) => match e {
+            PartitionManifestParseError::Io(e) => Err(crate::Error::from(e)),
+            e => {
+                panic!("invalid partition reference file: {e:?}")
+            }
+        },
+    }?;
+
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| {
+            let partition_id = if is_legacy {
+                resolve_name(entry.partition_name)
+            } else {
+                Some(
+                    entry
+                        .partition_name
+                        .parse::<PartitionId>()
+                        .expect("should be valid partition ID"),
+                )
+            };
+
+            partition_id.map(|partition_id| (partition_id, entry.seqno))
+        })
+        .collect())
+}
+
+/// Lists the partitions that are referenced by sealed journals
+fn list_sealed_journal_partitions(keyspace: &Keyspace) -> crate::Result<HashSet<PartitionId>> {
+    let mut ids = HashSet::new();
+
+    for dirent in std::fs::read_dir(keyspace.config.path.join(JOURNALS_FOLDER))? {
+        let journal_path = dirent?.path();
+
+        if !journal_path.join(FLUSH_MARKER).try_exists()? {
             continue;
         }
 
-        let partition_name = partition_name
-            .to_str()
-            .expect("should be valid partition name");
-
-        let path = partitions_folder.join(partition_name);
-
-        let tree = lsm_tree::Config::new(path)
-            .descriptor_table(keyspace.config.descriptor_table.clone())
-            .block_cache(keyspace.config.block_cache.clone())
-            .open()?;
-
-        let partition_inner = PartitionHandleInner {
-            max_memtable_size: (8 * 1_024 * 1_024).into(),
-            compaction_strategy: RwLock::new(Arc::new(lsm_tree::compaction::Levelled::default())),
-            name: partition_name.into(),
-            tree,
-            partitions: keyspace.partitions.clone(),
-            keyspace_config: keyspace.config.clone(),
-            flush_manager: keyspace.flush_manager.clone(),
-            flush_semaphore: keyspace.flush_semaphore.clone(),
-            journal_manager: keyspace.journal_manager.clone(),
-            journal: keyspace.journal.clone(),
-            compaction_manager: keyspace.compaction_manager.clone(),
-            seqno: keyspace.seqno.clone(),
-            write_buffer_manager: keyspace.write_buffer_manager.clone(),
-            is_deleted: AtomicBool::default(),
-            is_poisoned: keyspace.is_poisoned.clone(),
-        };
-        let partition_inner = Arc::new(partition_inner);
-        let partition = PartitionHandle(partition_inner);
+        let legacy_snapshot = Manifest::read_legacy_snapshot(&journal_path)?;
 
-        // NOTE: We already recovered all active memtables from the active journal,
-        // so just yank it out and give to the partition
-        if let Some(recovered_memtable) = memtables.remove(partition_name) {
-            log::trace!(
-                "Recovered previously active memtable for {:?}, with size: {} B",
-                partition_name,
-                recovered_memtable.size()
-            );
+        let entries = read_sealed_journal_partitions(&journal_path, &|name| {
+            keyspace
+                .manifest
+                .resolve_legacy_name(legacy_snapshot.as_ref(), name)
+        })?;
+
+        ids.extend(entries.into_iter().map(|(partition_id, _)| partition_id));
+    }
+
+    Ok(ids)
+}
+
+/// Recovers partitions
+///
+/// Only partitions that have unflushed data in the journals are opened,
+/// all others are registered as unloaded, and are opened on first access.
+///
+/// If `load_all` is set, all partitions are opened.
+pub fn recover_partitions(
+    keyspace: &Keyspace,
+    memtables: &mut HashMap<PartitionId, MemTable>,
+    load_all: bool,
+) -> crate::Result<()> {
+    let sealed_journal_partitions = list_sealed_journal_partitions(keyspace)?;
+
+    for (partition_id, entry) in keyspace.manifest.list() {
+        let partition_name = entry.name;
+        let recovered_memtable = memtables.remove(&partition_id);
+
+        if !load_all
+            && recovered_memtable.is_none()
+            && !sealed_journal_partitions.contains(&partition_id)
+        {
+            log::trace!("Registering unloaded partition {partition_name:?}");
 
-            // IMPORTANT: Add active memtable size to current write buffer size
             keyspace
-                .write_buffer_manager
-                .allocate(recovered_memtable.size().into());
+                .unloaded_partitions
+                .write()
+                .expect("lock is poisoned")
+                .insert(partition_name, Weak::new());
 
-            partition.tree.set_active_memtable(recovered_memtable);
+            continue;
         }
 
-        // Recover seqno
-        let maybe_next_seqno = partition.tree.get_lsn().map(|x| x + 1).unwrap_or_default();
-        keyspace
-            .seqno
-            .fetch_max(maybe_next_seqno, std::sync::atomic::Ordering::AcqRel);
-
-        log::debug!("Keyspace seqno is now {}", keyspace.seqno.get());
+        let partition = recover_partition(keyspace, &partition_name, recovered_memtable)?;
 
         // Add partition to dictionary
         keyspace
             .partitions
             .write()
             .expect("lock is poisoned")
-            .insert(partition_name.into(), partition.clone());
-
-        log::trace!("Recovered partition {:?}", partition_name);
+            .insert(partition_name, partition);
     }
 
     Ok(())
 }
 
+#[allow(clippy::too_many_lines)]
 pub fn recover_sealed_memtables(keyspace: &Keyspace) -> crate::Result<()> {
-    use crate::journal::partition_manifest::{
-        Error as PartitionManifestParseError, PartitionManifest,
-    };
-
     let mut journal_manager_lock = keyspace.journal_manager.write().expect("lock is poisoned");
     let mut flush_manager_lock = keyspace.flush_manager.write().expect("lock is poisoned");
     let partitions_lock = keyspace.partitions.read().expect("lock is poisoned");
 
+    let get_partition = |partition_id: PartitionId| {
+        keyspace
+            .manifest
+            .get(partition_id)
+            .and_then(|entry| partitions_lock.get(&entry.name))
+            .filter(|partition| partition.id() == partition_id)
+    };
+
     let journals_folder = keyspace.config.path.join(JOURNALS_FOLDER);
     let mut dirents = std::fs::read_dir(journals_folder)?.collect::<std::io::Result<Vec<_>>>()?;
     dirents.sort_by_key(std::fs::DirEntry::file_name);
@@ -139,17 +261,16 @@ pub fn recover_sealed_memtables(keyspace: &Keyspace) -> crate::Result<()> {
 
             log::trace!("Reading sealed journal at {:?}", journal_path);
 
+            let legacy_snapshot = Manifest::read_legacy_snapshot(&journal_path)?;
+            let resolve_name = |name: &str| {
+                keyspace
+                    .manifest
+                    .resolve_legacy_name(legacy_snapshot.as_ref(), name)
+            };
+
             // Only consider partitions that are registered in the journal
-            let file_content = std::fs::read_to_string(journal_path.join(FLUSH_PARTITIONS_LIST))?;
-            let partitions_to_consider = match PartitionManifest::from_str(&file_content) {
-                Ok(v) => Ok(v),
-                Err(e) => match e {
-                    PartitionManifestParseError::Io(e) => Err(crate::Error::from(e)),
-                    e => {
-                        panic!("invalid partition reference file: {e:?}")
-                    }
-                },
-            }?;
+            let partitions_to_consider =
+                read_sealed_journal_partitions(&journal_path, &resolve_name)?;
 
             log::trace!(
                 "Journal contains data of {} partitions",
@@ -160,41 +281,41 @@ pub fn recover_sealed_memtables(keyspace: &Keyspace) -> crate::Result<()> {
 
             // Only get the partitions that have a lower seqno than the journal
             // which means there's still some unflushed data in this sealed journal
-            for entry in partitions_to_consider {
-                let Some(partition) = partitions_lock.get(entry.partition_name) else {
+            for (partition_id, seqno) in partitions_to_consider {
+                let Some(partition) = get_partition(partition_id) else {
                     // Partition was probably deleted
-                    log::trace!("Partition {} does not exist", entry.partition_name);
+                    log::trace!("Partition {partition_id} does not exist");
                     continue;
                 };
 
-                let partition_lsn = partition.tree.get_segment_lsn();
+                let partition_lsn = partition.tree().get_segment_lsn();
                 let has_lower_lsn =
-                    partition_lsn.map_or(true, |partition_lsn| entry.seqno > partition_lsn);
+                    partition_lsn.map_or(true, |partition_lsn| seqno > partition_lsn);
 
                 if has_lower_lsn {
                     partition_seqno_map.insert(
-                        entry.partition_name.into(),
+                        partition_id,
                         crate::journal::manager::PartitionSeqNo {
-                            lsn: entry.seqno,
                             partition: partition.clone(),
+                            lsn: seqno,
                         },
                     );
                 } else {
                     log::trace!(
-                        "Partition {} has higher seqno ({partition_lsn:?}), skipping",
-                        entry.partition_name
+                        "Partition {:?} has higher seqno ({partition_lsn:?}), skipping",
+                        partition.name()
                     );
                 }
             }
 
             // Recover sealed memtables for affected partitions
-            let partition_names_to_recover =
-                partition_seqno_map.keys().cloned().collect::<Vec<_>>();
+            let partition_ids_to_recover = partition_seqno_map.keys().copied().collect::<Vec<_>>();
 
-            log::trace!("Recovering memtables for partitions: {partition_names_to_recover:#?}");
+            log::trace!("Recovering memtables for partitions: {partition_ids_to_recover:#?}");
             let memtables = Journal::recover_memtables(
                 &journal_path,
-                Some(&partition_names_to_recover),
+                Some(&partition_ids_to_recover),
+                &resolve_name,
                 keyspace.config.journal_recovery_mode,
             )?;
             log::trace!("Recovered {} sealed memtables", memtables.len());
@@ -207,21 +328,21 @@ pub fn recover_sealed_memtables(keyspace: &Keyspace) -> crate::Result<()> {
             });
 
             // Consume memtables by giving back to the partition
-            for (partition_name, sealed_memtable) in memtables {
-                let Some(partition) = partitions_lock.get(&partition_name) else {
+            for (partition_id, sealed_memtable) in memtables {
+                let Some(partition) = get_partition(partition_id) else {
                     // Should not happen
                     continue;
                 };
 
-                let memtable_id = partition.tree.get_next_segment_id();
+                let tree = partition.tree();
+
+                let memtable_id = tree.get_next_segment_id();
                 let sealed_memtable = Arc::new(sealed_memtable);
 
-                partition
-                    .tree
-                    .add_sealed_memtable(memtable_id, sealed_memtable.clone());
+                tree.add_sealed_memtable(memtable_id, sealed_memtable.clone());
 
                 // Maybe the memtable has a higher seqno, so try to set to maximum
-                let maybe_next_seqno = partition.tree.get_lsn().map(|x| x + 1).unwrap_or_default();
+                let maybe_next_seqno = tree.get_lsn().map(|x| x + 1).unwrap_or_default();
                 keyspace
                     .seqno
                     .fetch_max(maybe_next_seqno, std::sync::atomic::Ordering::AcqRel);
@@ -237,11 +358,12 @@ pub fn recover_sealed_memtables(keyspace: &Keyspace) -> crate::Result<()> {
 
                 // IMPORTANT: Add sealed memtable to flush manager, so it can be flushed
                 flush_manager_lock.enqueue_task(
-                    partition_name,
+                    partition_id,
                     crate::flush::manager::Task {
                         id: memtable_id,
                         sealed_memtable,
                         partition: partition.clone(),
+                        tree,
                     },
                 );
             }
@@ -252,3 +374,217 @@ pub fn recover_sealed_memtables(keyspace: &Keyspace) -> crate::Result<()> {
 
     Ok(())
 }
+
+/// Lists the journals in a folder, by journal ID
+fn list_journals<P: AsRef<Path>>(folder: P) -> crate::Result<BTreeMap<u64, PathBuf>> {
+    let mut journals = BTreeMap::new();
+
+    for dirent in std::fs::read_dir(folder)? {
+        let dirent = dirent?;
+
+        let file_name = dirent.file_name();
+
+        let Some(journal_id) = file_name
+            .to_str()
+            .and_then(|name| name.parse::<lsm_tree::SegmentId>().ok())
+        else {
+            log::warn!(
+                "Skipping unexpected file in journal folder: {}",
+                dirent.path().display()
+            );
+            continue;
+        };
+
+        journals.insert(journal_id, dirent.path());
+    }
+
+    Ok(journals)
+}
+
+/// Rolls back the keyspace on disk to the given instant, before the keyspace is recovered.
+///
+/// Items of the local & archived journals with a seqno lower than the instant are replayed
+/// into disk segments. Then, the local journals are replaced by a new, empty journal.
+#[allow(clippy::too_many_lines)]
+pub fn recover_point_in_time(config: &Config, instant: crate::Instant) -> crate::Result<()> {
+    log::info!(
+        "Rolling back keyspace at {} to instant {instant}",
+        config.path.display()
+    );
+
+    let partitions_folder = config.path.join(PARTITIONS_FOLDER);
+    let journals_folder = config.path.join(JOURNALS_FOLDER);
+
+    // NOTE: Recovering the manifest migrates older keyspaces, so partition folders are named by ID
+    let mut manifest = Manifest::recover(&config.path)?;
+
+    let open_tree = |partition_id: PartitionId| {
+        lsm_tree::Config::new(partitions_folder.join(partition_id.to_string()))
+            .descriptor_table(config.descriptor_table.clone())
+            .block_cache(config.block_cache.clone())
+            .open()
+    };
+
+    let mut trees: HashMap<PartitionId, LsmTree> = HashMap::new();
+
+    for (&partition_id, entry) in &manifest.partitions {
+        // NOTE: Non-journaled partitions have no data in the journals to roll back
+        if !entry.journaled {
+            log::debug!("Skipping non-journaled partition {:?}", entry.name);
+            continue;
+        }
+
+        let tree = open_tree(partition_id)?;
+
+        if let Some(lsn) = tree.get_segment_lsn() {
+            if lsn >= instant {
+                log::error!(
+                    "Cannot roll back partition {:?} to {instant}, its segments contain seqno {lsn}",
+                    entry.name
+                );
+                return Err(crate::Error::RecoveryTargetUnreachable(lsn));
+            }
+        }
+
+        trees.insert(partition_id, tree);
+    }
+
+    // IMPORTANT: The lower bound needs to be fixed before replaying,
+    // because flushing replayed items moves the segment LSN
+    let segment_lsns = trees
+        .iter()
+        .map(|(partition_id, tree)| (*partition_id, tree.get_segment_lsn()))
+        .collect::<HashMap<PartitionId, Option<SeqNo>>>();
+
+    let local_journals = list_journals(&journals_folder)?;
+    let mut journals = local_journals.clone();
+
+    if let Some(archive_path) = &config.journal_archive_path {
+        if archive_path.try_exists()? {
+            // NOTE: An archived journal is at least as complete as its local version
+            journals.extend(list_journals(archive_path)?);
+        }
+    }
+
+    // NOTE: Partitions that were created after the checkpoint are not in the keyspace manifest,
+    // but sealed journals contain a copy of the manifest at the time they were sealed
+    //
+    // IDs are never reused, so partitions with a lower ID than the next ID of
+    // the keyspace manifest were deleted before the checkpoint, and stay deleted.
+    let mut created_partitions: HashMap<PartitionId, PartitionEntry> = HashMap::new();
+
+    for journal_path in journals.values() {
+        let manifest_path = journal_path.join(JOURNAL_MANIFEST);
+
+        if !manifest_path.try_exists()? {
+            continue;
+        }
+
+        for (partition_id, entry) in Manifest::read_file(manifest_path)?.partitions {
+            if partition_id >= manifest.next_partition_id && entry.journaled {
+                created_partitions.insert(partition_id, entry);
+            }
+        }
+    }
+
+    let filter = |partition_id: PartitionId, seqno: SeqNo| {
+        seqno < instant
+            && segment_lsns.get(&partition_id).map_or_else(
+                || created_partitions.contains_key(&partition_id),
+                |lsn| lsn.map_or(true, |lsn| seqno > lsn),
+            )
+    };
+
+    // NOTE: Journals written before partitions had IDs reference partitions by name
+    let partition_names = manifest
+        .partitions
+        .iter()
+        .map(|(partition_id, entry)| (entry.name.clone(), *partition_id))
+        .collect::<HashMap<PartitionKey, PartitionId>>();
+
+    for journal_path in journals.values() {
+        log::debug!("Replaying journal at {}", journal_path.display());
+
+        let legacy_snapshot = Manifest::read_legacy_snapshot(journal_path)?;
+        let resolve_name = |name: &str| {
+            legacy_snapshot.as_ref().map_or_else(
+                || partition_names.get(name).copied(),
+                |snapshot| snapshot.find(name),
+            )
+        };
+
+        let memtables = Journal::recover_memtables_filtered(
+            journal_path,
+            &filter,
+            &resolve_name,
+            config.journal_recovery_mode,
+        )?;
+
+        for (partition_id, memtable) in memtables {
+            if memtable.is_empty() {
+                continue;
+            }
+
+            let tree = if let Some(tree) = trees.get(&partition_id) {
+                tree.clone()
+            } else {
+                let entry = created_partitions
+                    .get(&partition_id)
+                    .cloned()
+                    .expect("partition should exist");
+
+                log::debug!(
+                    "Creating partition {:?} with ID {partition_id} from journal",
+                    entry.name
+                );
+
+                let tree = open_tree(partition_id)?;
+
+                manifest.partitions.insert(partition_id, entry);
+                manifest.next_partition_id = manifest.next_partition_id.max(partition_id + 1);
+                manifest.write(&config.path)?;
+
+                trees.insert(partition_id, tree.clone());
+                tree
+            };
+
+            log::trace!(
+                "Flushing {} replayed items of partition {partition_id}",
+                memtable.len()
+            );
+
+            tree.set_active_memtable(memtable);
+            tree.flush_active_memtable()?;
+        }
+    }
+
+    // IMPORTANT: Create the new journal first, so it has the highest ID,
+    // and is picked up as active journal
+    let next_journal_id = journals.keys().max().map_or(0, |id| id + 1);
+    Journal::create_new(
+        journals_folder.join(next_journal_id.to_string()),
+        config.journal_shard_count,
+        config.journal_preallocated_bytes,
+    )?;
+
+    for journal_path in local_journals.values() {
+        log::trace!("Removing rolled back journal at {}", journal_path.display());
+        std::fs::remove_dir_all(journal_path)?;
+    }
+
+    // IMPORTANT: fsync folder on Unix
+    fsync_directory(&journals_folder)?;
+
+    // NOTE: The replayed items are not covered by the seqno marker,
+    // so remove it, which makes the recovery open all partitions
+    // and persist a new marker
+    let seqno_marker_path = config.path.join(SEQNO_MARKER);
+    if seqno_marker_path.try_exists()? {
+        std::fs::remove_file(seqno_marker_path)?;
+        fsync_directory(&config.path)?;
+    }
+
+    log::info!("Rolled back keyspace to instant {instant}");
+
+    Ok(())
+}
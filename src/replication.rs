@@ -0,0 +1,454 @@
+use crate::{
+    batch::{item::Item as BatchItem, Batch, PartitionKey},
+    journal::{marker::Marker, writer::write_batch},
+    Keyspace, PartitionCreateOptions,
+};
+use lsm_tree::{
+    serde::{Deserializable, Serializable},
+    SeqNo,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{BufRead, BufReader, Read, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+/// Errors that may occur while reading a replication stream
+#[derive(Debug)]
+pub enum ReplicationError {
+    /// A marker was found in an unexpected position (e.g. an item outside a batch)
+    UnexpectedMarker,
+
+    /// The stream ended in the middle of a batch
+    Truncated,
+
+    /// The amount of items does not match the batch's start marker
+    ItemCount,
+
+    /// The CRC of the batch's items does not match the end marker
+    CrcCheck,
+}
+
+impl std::fmt::Display for ReplicationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ReplicationError: {self:?}")
+    }
+}
+
+impl std::error::Error for ReplicationError {}
+
+impl From<ReplicationError> for crate::Error {
+    fn from(value: ReplicationError) -> Self {
+        Self::Replication(value)
+    }
+}
+
+type SinkWriter = Box<dyn Write + Send>;
+
+#[derive(Default)]
+pub(crate) struct ReplicationSinkInner {
+    /// Allows writes to skip locking the sink if replication is not used
+    is_active: AtomicBool,
+
+    writer: Mutex<Option<SinkWriter>>,
+}
+
+/// Ships committed batches of a primary keyspace to a follower
+#[derive(Clone, Default)]
+pub(crate) struct ReplicationSink(Arc<ReplicationSinkInner>);
+
+impl std::ops::Deref for ReplicationSink {
+    type Target = ReplicationSinkInner;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl ReplicationSink {
+    /// Installs a new writer, replacing (and closing) the previous one
+    ///
+    /// IMPORTANT: The caller needs to hold all journal shard locks, so
+    /// no batch is committed while the sink is changed
+    pub(crate) fn attach(&self, writer: SinkWriter) {
+        let mut lock = self.writer.lock().expect("lock is poisoned");
+        *lock = Some(writer);
+        self.is_active.store(true, Ordering::Release);
+    }
+
+    /// Removes (and closes) the writer
+    pub(crate) fn detach(&self) {
+        let mut lock = self.writer.lock().expect("lock is poisoned");
+        *lock = None;
+        self.is_active.store(false, Ordering::Release);
+    }
+
+    /// Locks the sink, if replication is active
+    ///
+    /// IMPORTANT: Needs to be called while holding a journal shard lock,
+    /// and before the batch's seqno is assigned, so batches are shipped in seqno order
+    pub(crate) fn lock(&self) -> Option<SinkGuard<'_>> {
+        if !self.is_active.load(Ordering::Acquire) {
+            return None;
+        }
+
+        Some(SinkGuard {
+            writer: self.writer.lock().expect("lock is poisoned"),
+            is_active: &self.is_active,
+        })
+    }
+}
+
+pub(crate) struct SinkGuard<'a> {
+    writer: MutexGuard<'a, Option<SinkWriter>>,
+    is_active: &'a AtomicBool,
+}
+
+impl SinkGuard<'_> {
+    /// Ships a committed batch
+    ///
+    /// The batch is already persisted in the journal at this point, so a failing
+    /// sink does not fail the write. Instead the sink is detached, because the
+    /// follower would otherwise silently miss batches.
+    pub(crate) fn ship(&mut self, items: &[&BatchItem], seqno: SeqNo) {
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+
+        let mut bytes = Vec::new();
+
+        let result = write_batch(&mut bytes, items, seqno).and_then(|_| {
+            writer.write_all(&bytes)?;
+            writer.flush()?;
+            Ok(())
+        });
+
+        if let Err(e) = result {
+            log::error!("Failed to ship batch {seqno} to replication sink, detaching: {e:?}");
+            *self.writer = None;
+            self.is_active.store(false, Ordering::Release);
+        }
+    }
+}
+
+/// Reads the batches of a replication stream, validating each batch
+struct BatchReader<R: Read> {
+    reader: BufReader<R>,
+}
+
+impl<R: Read> BatchReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+        }
+    }
+
+    fn read_marker(&mut self) -> crate::Result<Marker> {
+        Marker::deserialize(&mut self.reader).map_err(|e| match e {
+            lsm_tree::DeserializeError::Io(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                ReplicationError::Truncated.into()
+            }
+            e => e.into(),
+        })
+    }
+
+    fn read_batch(&mut self) -> crate::Result<Option<(SeqNo, Vec<BatchItem>)>> {
+        // NOTE: The stream may only end in between batches
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+
+        let Marker::Start { item_count, seqno } = self.read_marker()? else {
+            return Err(ReplicationError::UnexpectedMarker.into());
+        };
+
+        let mut hasher = crc32fast::Hasher::new();
+        let mut items = Vec::with_capacity(item_count as usize);
+
+        loop {
+            match self.read_marker()? {
+                // NOTE: Partition IDs are local to a keyspace, so items are shipped by name
+                Marker::Start { .. } | Marker::ItemById { .. } => {
+                    return Err(ReplicationError::UnexpectedMarker.into())
+                }
+                Marker::Item {
+                    partition,
+                    key,
+                    value,
+                    value_type,
+                } => {
+                    let marker = Marker::Item {
+                        partition: partition.clone(),
+                        key: key.clone(),
+                        value: value.clone(),
+                        value_type,
+                    };
+                    let mut bytes = Vec::with_capacity(100);
+                    marker.serialize(&mut bytes)?;
+
+                    hasher.update(&bytes);
+
+                    items.push(BatchItem {
+                        partition,
+                        key,
+                        value,
+                        value_type,
+                    });
+                }
+                Marker::End(crc) => {
+                    if items.len() != item_count as usize {
+                        return Err(ReplicationError::ItemCount.into());
+                    }
+
+                    if hasher.finalize() != crc {
+                        return Err(ReplicationError::CrcCheck.into());
+                    }
+
+                    return Ok(Some((seqno, items)));
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for BatchReader<R> {
+    type Item = crate::Result<(SeqNo, Vec<BatchItem>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_batch().transpose()
+    }
+}
+
+/// Applies the batches of a replication stream to the keyspace, until the stream ends
+pub(crate) fn apply<R: Read>(keyspace: &Keyspace, reader: R) -> crate::Result<u64> {
+    let mut batch_count = 0;
+
+    for batch in BatchReader::new(reader) {
+        let (seqno, items) = batch?;
+
+        // NOTE: Skip batches that were already applied, e.g. when resuming a stream
+        if seqno < keyspace.applied_instant() {
+            log::trace!("Skipping already applied batch {seqno}");
+            continue;
+        }
+
+        let partition_names = items
+            .iter()
+            .map(|item| item.partition.clone())
+            .collect::<HashSet<PartitionKey>>();
+
+        let mut handles = HashMap::new();
+
+        for name in partition_names {
+            if !keyspace.partition_exists(&name) {
+                log::debug!("Creating replicated partition {name}");
+            }
+
+            let partition = keyspace.open_partition(&name, PartitionCreateOptions::default())?;
+            handles.insert(name, partition);
+        }
+
+        let mut batch = Batch::with_capacity(keyspace.clone(), 0);
+        batch.data = items
+            .into_iter()
+            .filter_map(|item| {
+                handles
+                    .get(&item.partition)
+                    .map(|partition| (partition.clone(), item))
+            })
+            .collect();
+        batch.commit_at(seqno)?;
+
+        keyspace
+            .applied_instant
+            .fetch_max(seqno + 1, Ordering::AcqRel);
+
+        batch_count += 1;
+    }
+
+    Ok(batch_count)
+}
+
+/// Creates an in-process pipe, which can be used to replicate
+/// from a primary keyspace to a follower keyspace
+///
+/// The reader returns EOF once the writer is dropped, e.g. by calling
+/// [`Keyspace::stop_replication`] on the primary keyspace.
+///
+/// # Examples
+///
+/// ```
+/// # use fjall::{Config, PartitionCreateOptions};
+/// #
+/// # let folder = tempfile::tempdir()?;
+/// # let primary = Config::new(folder).open()?;
+/// # let folder = tempfile::tempdir()?;
+/// # let follower = Config::new(folder).open()?;
+/// let (writer, reader) = fjall::replication::pipe();
+/// primary.replicate_to(writer);
+///
+/// let partition = primary.open_partition("default", PartitionCreateOptions::default())?;
+/// partition.insert("a", "abc")?;
+///
+/// primary.stop_replication();
+///
+/// assert_eq!(1, follower.replicate_from(reader)?);
+/// assert_eq!(primary.instant(), follower.applied_instant());
+/// #
+/// # Ok::<(), fjall::Error>(())
+/// ```
+#[must_use]
+pub fn pipe() -> (PipeWriter, PipeReader) {
+    let (tx, rx) = channel();
+
+    (
+        PipeWriter(tx),
+        PipeReader {
+            rx,
+            buf: Vec::new(),
+            pos: 0,
+        },
+    )
+}
+
+/// Writing half of a [`pipe`]
+pub struct PipeWriter(Sender<Vec<u8>>);
+
+impl Write for PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // NOTE: Don't send empty chunks, the reader would interpret them as EOF
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        self.0
+            .send(buf.to_vec())
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe))?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reading half of a [`pipe`]
+pub struct PipeReader {
+    rx: Receiver<Vec<u8>>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl Read for PipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos == self.buf.len() {
+            // NOTE: Blocks until data arrives, or all writers are dropped (EOF)
+            let Ok(chunk) = self.rx.recv() else {
+                return Ok(0);
+            };
+
+            self.buf = chunk;
+            self.pos = 0;
+        }
+
+        let remaining = self.buf.get(self.pos..).unwrap_or_default();
+        let len = remaining.len().min(buf.len());
+
+        #[allow(clippy::indexing_slicing)]
+        buf[..len].copy_from_slice(&remaining[..len]);
+        self.pos += len;
+
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsm_tree::ValueType;
+    use test_log::test;
+
+    fn write_stream(batches: &[(SeqNo, &[&BatchItem])]) -> crate::Result<Vec<u8>> {
+        let mut bytes = vec![];
+
+        for (seqno, items) in batches {
+            write_batch(&mut bytes, items, *seqno)?;
+        }
+
+        Ok(bytes)
+    }
+
+    #[test]
+    fn replication_stream_round_trip() -> crate::Result<()> {
+        let a = BatchItem::new("default", *b"a", *b"abc", ValueType::Value);
+        let b = BatchItem::new("other", *b"b", *b"", ValueType::Tombstone);
+
+        let bytes = write_stream(&[(0, &[&a]), (1, &[&a, &b])])?;
+
+        let batches = BatchReader::new(&*bytes).collect::<crate::Result<Vec<_>>>()?;
+        assert_eq!(2, batches.len());
+        assert_eq!(0, batches[0].0);
+        assert_eq!(1, batches[0].1.len());
+        assert_eq!(1, batches[1].0);
+        assert_eq!(2, batches[1].1.len());
+        assert_eq!(ValueType::Tombstone, batches[1].1[1].value_type);
+
+        Ok(())
+    }
+
+    #[test]
+    fn replication_stream_truncated() -> crate::Result<()> {
+        let a = BatchItem::new("default", *b"a", *b"abc", ValueType::Value);
+
+        let mut bytes = write_stream(&[(0, &[&a]), (1, &[&a])])?;
+        bytes.truncate(bytes.len() - 5);
+
+        let mut reader = BatchReader::new(&*bytes);
+        assert!(matches!(reader.next(), Some(Ok(_))));
+        assert!(matches!(
+            reader.next(),
+            Some(Err(crate::Error::Replication(ReplicationError::Truncated)))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn replication_stream_crc_mismatch() -> crate::Result<()> {
+        let a = BatchItem::new("default", *b"a", *b"abc", ValueType::Value);
+
+        let mut bytes = write_stream(&[(0, &[&a])])?;
+
+        // Flip a byte inside the value
+        let idx = bytes.iter().position(|&x| x == b'c').expect("should exist");
+        *bytes.get_mut(idx).expect("should exist") ^= 0xFF;
+
+        assert!(matches!(
+            BatchReader::new(&*bytes).next(),
+            Some(Err(crate::Error::Replication(ReplicationError::CrcCheck)))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn replication_pipe() -> crate::Result<()> {
+        let (mut writer, mut reader) = pipe();
+
+        writer.write_all(b"abc")?;
+        writer.write_all(b"")?;
+        writer.write_all(b"def")?;
+        drop(writer);
+
+        let mut buf = vec![];
+        reader.read_to_end(&mut buf)?;
+        assert_eq!(b"abcdef", &*buf);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use crate::file::{fsync_directory, SEQNO_MARKER};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use lsm_tree::SeqNo;
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Persists an upper bound of the seqnos stored in the keyspace's partitions
+///
+/// Partitions are loaded lazily, so on recovery, the keyspace seqno cannot be
+/// derived from the partitions alone. Every seqno that is not covered by a journal
+/// anymore (because it was flushed) is lower than the persisted seqno.
+pub struct SeqnoMarker {
+    folder: PathBuf,
+
+    /// The highest persisted seqno
+    persisted: Mutex<SeqNo>,
+}
+
+impl SeqnoMarker {
+    pub fn new<P: AsRef<Path>>(folder: P, seqno: SeqNo) -> Self {
+        Self {
+            folder: folder.as_ref().to_path_buf(),
+            persisted: Mutex::new(seqno),
+        }
+    }
+
+    /// Reads the persisted seqno
+    ///
+    /// Returns `None` if the keyspace was created before the marker existed.
+    pub fn read<P: AsRef<Path>>(folder: P) -> crate::Result<Option<SeqNo>> {
+        let path = folder.as_ref().join(SEQNO_MARKER);
+
+        if !path.try_exists()? {
+            return Ok(None);
+        }
+
+        Ok(Some(File::open(path)?.read_u64::<BigEndian>()?))
+    }
+
+    /// Persists the seqno, if it is higher than the currently persisted one
+    pub fn persist(&self, seqno: SeqNo) -> crate::Result<()> {
+        let mut persisted = self.persisted.lock().expect("lock is poisoned");
+
+        if seqno > *persisted {
+            self.write(seqno)?;
+            *persisted = seqno;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the marker file, even if the seqno has not changed
+    ///
+    /// Only used before the keyspace is shared, e.g. when creating it.
+    pub fn write(&self, seqno: SeqNo) -> crate::Result<()> {
+        let path = self.folder.join(SEQNO_MARKER);
+        let tmp_path = self.folder.join(format!("{SEQNO_MARKER}.tmp"));
+
+        let mut file = File::create(&tmp_path)?;
+        file.write_u64::<BigEndian>(seqno)?;
+        file.flush()?;
+        file.sync_all()?;
+
+        // IMPORTANT: Rename, so the marker is replaced atomically
+        std::fs::rename(&tmp_path, path)?;
+
+        // IMPORTANT: fsync folder on Unix
+        fsync_directory(&self.folder)?;
+
+        Ok(())
+    }
+}
@@ -1,14 +1,84 @@
-use std::sync::{PoisonError, RwLock, RwLockWriteGuard};
+use std::sync::{
+    atomic::{fence, AtomicUsize, Ordering},
+    Condvar, Mutex, MutexGuard, PoisonError, RwLock, RwLockWriteGuard, TryLockError,
+};
 
 type Shard<T> = RwLock<T>;
 
+/// FIFO ticket queue of threads waiting for a shard
+#[derive(Default)]
+struct Queue {
+    next_ticket: u64,
+    now_serving: u64,
+}
+
+/// Write access to a single shard
+///
+/// Wakes up queued writers when dropped
+pub struct ShardGuard<'a, T> {
+    guard: Option<RwLockWriteGuard<'a, T>>,
+    sharded: &'a Sharded<T>,
+}
+
+impl<T> std::ops::Deref for ShardGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        #[allow(clippy::expect_used)]
+        self.guard.as_ref().expect("guard should exist")
+    }
+}
+
+impl<T> std::ops::DerefMut for ShardGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        #[allow(clippy::expect_used)]
+        self.guard.as_mut().expect("guard should exist")
+    }
+}
+
+impl<T> Drop for ShardGuard<'_, T> {
+    fn drop(&mut self) {
+        // IMPORTANT: Release the shard *before* waking up the queue,
+        // otherwise the head of the queue may go back to sleep
+        drop(self.guard.take());
+        self.sharded.wake_waiters();
+    }
+}
+
+/// The turn of a queued thread
+///
+/// Serves the next ticket when dropped, even if acquiring the shard(s) failed
+struct Turn<'a, T> {
+    sharded: &'a Sharded<T>,
+}
+
+impl<T> Drop for Turn<'_, T> {
+    fn drop(&mut self) {
+        let mut queue = self.sharded.lock_queue();
+        queue.now_serving += 1;
+        self.sharded.waiting.fetch_sub(1, Ordering::SeqCst);
+        drop(queue);
+
+        self.sharded.signal.notify_all();
+    }
+}
+
 /// Defines a sharded structure
 ///
 /// The sharded structure consists of N shards that can be independently locked
 ///
 /// This reduces contention when working with multiple threads
+///
+/// If no shard is free, writers park in a FIFO queue, so a writer
+/// (or a full lock) cannot be starved by other threads
 pub struct Sharded<T> {
     shards: Vec<Shard<T>>,
+
+    queue: Mutex<Queue>,
+    signal: Condvar,
+
+    /// Amount of queued threads, used to skip the queue when there is no contention
+    waiting: AtomicUsize,
 }
 
 impl<T> std::ops::Deref for Sharded<T> {
@@ -22,24 +92,166 @@ impl<T> std::ops::Deref for Sharded<T> {
 impl<T> Sharded<T> {
     /// Creates a new sharded structure
     pub fn new(shards: Vec<Shard<T>>) -> Self {
-        Self { shards }
+        Self {
+            shards,
+            queue: Mutex::default(),
+            signal: Condvar::new(),
+            waiting: AtomicUsize::default(),
+        }
+    }
+
+    fn lock_queue(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().expect("lock is poisoned")
+    }
+
+    fn wake_waiters(&self) {
+        // NOTE: Pairs with the fence in `wait_for_turn`, so either the waiter
+        // sees the released shard, or we see the waiter
+        fence(Ordering::SeqCst);
+
+        if self.waiting.load(Ordering::SeqCst) > 0 {
+            // NOTE: Take the queue lock, so we cannot notify between
+            // a waiter checking the shards and going to sleep
+            drop(self.lock_queue());
+            self.signal.notify_all();
+        }
+    }
+
+    fn try_write_any(&self) -> Option<ShardGuard<'_, T>> {
+        for shard in &self.shards {
+            match shard.try_write() {
+                Ok(guard) => {
+                    return Some(ShardGuard {
+                        guard: Some(guard),
+                        sharded: self,
+                    })
+                }
+                Err(TryLockError::WouldBlock) => {}
+                Err(TryLockError::Poisoned(_)) => panic!("lock is poisoned"),
+            }
+        }
+
+        None
+    }
+
+    /// Takes a ticket and parks until it is served
+    fn wait_for_turn(&self) -> (Turn<'_, T>, MutexGuard<'_, Queue>) {
+        let mut queue = self.lock_queue();
+
+        let ticket = queue.next_ticket;
+        queue.next_ticket += 1;
+        self.waiting.fetch_add(1, Ordering::SeqCst);
+        fence(Ordering::SeqCst);
+
+        let queue = self
+            .signal
+            .wait_while(queue, |queue| queue.now_serving != ticket)
+            .expect("lock is poisoned");
+
+        (Turn { sharded: self }, queue)
     }
 
     /// Gives write access to a shard
-    pub fn write_one(&self) -> RwLockWriteGuard<'_, T> {
+    ///
+    /// Blocks (without spinning) if all shards are locked.
+    /// Blocked writers are served in FIFO order.
+    pub fn write_one(&self) -> ShardGuard<'_, T> {
+        // NOTE: Fast path, if nobody is queued, we can just grab any free shard
+        if self.waiting.load(Ordering::SeqCst) == 0 {
+            if let Some(guard) = self.try_write_any() {
+                return guard;
+            }
+        }
+
+        let (turn, mut queue) = self.wait_for_turn();
+
         loop {
-            for shard in &self.shards {
-                if let Ok(shard) = shard.try_write() {
-                    return shard;
-                }
+            if let Some(guard) = self.try_write_any() {
+                drop(queue);
+                drop(turn);
+                return guard;
             }
+
+            queue = self.signal.wait(queue).expect("lock is poisoned");
         }
     }
 
+    /// Gives write access to every shard, locking one shard after another
+    ///
+    /// Each shard is locked when the iterator reaches it, so only a single shard
+    /// is locked at a time, if the previous guard is dropped before advancing.
+    pub fn lock_each(&self) -> impl Iterator<Item = ShardGuard<'_, T>> {
+        self.shards.iter().map(|shard| ShardGuard {
+            guard: Some(shard.write().expect("lock is poisoned")),
+            sharded: self,
+        })
+    }
+
     /// Gives exclusive control over the entire structure
+    ///
+    /// Queues up like any other writer, so it cannot be starved by [`Sharded::write_one`].
     pub fn full_lock(
         &self,
-    ) -> Result<Vec<RwLockWriteGuard<'_, T>>, PoisonError<RwLockWriteGuard<'_, T>>> {
-        self.shards.iter().map(|shard| shard.write()).collect()
+    ) -> Result<Vec<ShardGuard<'_, T>>, PoisonError<RwLockWriteGuard<'_, T>>> {
+        let (turn, queue) = self.wait_for_turn();
+
+        // NOTE: Keep the turn, so no writer behind us can take a shard,
+        // but do not block the queue while waiting for the current shard holders
+        drop(queue);
+
+        let guards = self
+            .shards
+            .iter()
+            .map(|shard| {
+                shard.write().map(|guard| ShardGuard {
+                    guard: Some(guard),
+                    sharded: self,
+                })
+            })
+            .collect();
+
+        drop(turn);
+
+        guards
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn sharded_write_one_contended() {
+        const THREADS: usize = 16;
+        const ITERATIONS: usize = 1_000;
+
+        let sharded = Sharded::new(vec![RwLock::new(0), RwLock::new(0)]);
+
+        std::thread::scope(|scope| {
+            for _ in 0..THREADS {
+                scope.spawn(|| {
+                    for _ in 0..ITERATIONS {
+                        *sharded.write_one() += 1;
+                    }
+                });
+            }
+
+            // NOTE: Full lock needs to get through, even with all writers hammering the shards
+            scope.spawn(|| {
+                for _ in 0..100 {
+                    let guards = sharded.full_lock().expect("lock is poisoned");
+                    assert_eq!(2, guards.len());
+                }
+            });
+        });
+
+        let sum: usize = sharded
+            .iter()
+            .map(|shard| *shard.read().expect("lock is poisoned"))
+            .sum();
+
+        assert_eq!(THREADS * ITERATIONS, sum);
+        assert_eq!(0, sharded.waiting.load(Ordering::SeqCst));
     }
 }
@@ -63,6 +63,17 @@ impl TxKeyspace {
         self.inner.persist(mode)
     }
 
+    /// Blocks until the write with the given seqno is durable.
+    ///
+    /// See [`Keyspace::wait_for_durable`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if the keyspace was poisoned because a sync failed.
+    pub fn wait_for_durable(&self, seqno: crate::Instant) -> crate::Result<()> {
+        self.inner.wait_for_durable(seqno)
+    }
+
     /// Creates or opens a keyspace partition.
     ///
     /// # Errors
@@ -85,13 +96,24 @@ impl TxKeyspace {
         })
     }
 
-    /// Returns the amount of partitions
+    /// Closes the partition, freeing its memory and file descriptors.
+    ///
+    /// See [`Keyspace::close_partition`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn close_partition(&self, handle: TxPartitionHandle) -> crate::Result<()> {
+        self.inner.close_partition(handle.inner)
+    }
+
+    /// Returns the amount of partitions, including unloaded partitions
     #[must_use]
     pub fn partition_count(&self) -> usize {
         self.inner.partition_count()
     }
 
-    /// Gets a list of all partition names in the keyspace
+    /// Gets a list of all partition names in the keyspace, including unloaded partitions
     #[must_use]
     pub fn list_partitions(&self) -> Vec<PartitionKey> {
         self.inner.list_partitions()
@@ -112,6 +134,105 @@ impl TxKeyspace {
         self.inner.delete_partition(handle.inner)
     }
 
+    /// Renames a partition.
+    ///
+    /// See [`Keyspace::rename_partition`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionExists`](crate::Error::PartitionExists) if a partition
+    /// with the new name already exists.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new partition name is invalid.
+    pub fn rename_partition(
+        &self,
+        handle: &TxPartitionHandle,
+        new_name: &str,
+    ) -> crate::Result<()> {
+        self.inner.rename_partition(&handle.inner, new_name)
+    }
+
+    /// Creates a new partition as a copy-on-write clone of another partition.
+    ///
+    /// See [`Keyspace::fork_partition`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionExists`](crate::Error::PartitionExists) if a partition
+    /// with the new name already exists.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new partition name is invalid.
+    pub fn fork_partition(
+        &self,
+        source: &TxPartitionHandle,
+        new_name: &str,
+    ) -> crate::Result<TxPartitionHandle> {
+        // NOTE: Fork between write transactions, so the fork does not contain a partial transaction
+        let _lock = self.tx_lock.lock().expect("lock is poisoned");
+        let partition = self.inner.fork_partition(&source.inner, new_name)?;
+
+        Ok(TxPartitionHandle {
+            inner: partition,
+            tx_lock: self.tx_lock.clone(),
+        })
+    }
+
+    /// Atomically exchanges the contents of two partitions.
+    ///
+    /// See [`Keyspace::swap_partitions`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionDeleted`](crate::Error::PartitionDeleted)
+    /// if either partition has been deleted.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a lock is poisoned.
+    pub fn swap_partitions(
+        &self,
+        a: &TxPartitionHandle,
+        b: &TxPartitionHandle,
+    ) -> crate::Result<()> {
+        // NOTE: Swap between write transactions, so a transaction does not observe both contents
+        let _lock = self.tx_lock.lock().expect("lock is poisoned");
+        self.inner.swap_partitions(&a.inner, &b.inner)
+    }
+
+    /// Atomically replaces the contents of a partition with another partition.
+    ///
+    /// See [`Keyspace::replace_partition`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartitionDeleted`](crate::Error::PartitionDeleted)
+    /// if either partition has been deleted.
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a lock is poisoned.
+    pub fn replace_partition(
+        &self,
+        target: &TxPartitionHandle,
+        source: TxPartitionHandle,
+    ) -> crate::Result<()> {
+        // NOTE: Replace between write transactions, so a transaction does not observe both contents
+        let _lock = self.tx_lock.lock().expect("lock is poisoned");
+        self.inner.replace_partition(&target.inner, source.inner)
+    }
+
     /// Returns the current write buffer size (active + sealed memtables).
     #[must_use]
     pub fn write_buffer_size(&self) -> u64 {
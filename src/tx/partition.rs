@@ -1,4 +1,4 @@
-use crate::PartitionHandle;
+use crate::{batch::PartitionKey, PartitionHandle};
 use lsm_tree::UserValue;
 use std::{
     path::PathBuf,
@@ -13,6 +13,12 @@ pub struct TransactionalPartitionHandle {
 }
 
 impl TransactionalPartitionHandle {
+    /// Returns the partition name
+    #[must_use]
+    pub fn name(&self) -> PartitionKey {
+        self.inner.name()
+    }
+
     /// Returns the underlying LSM-tree's path
     #[must_use]
     pub fn path(&self) -> PathBuf {
@@ -244,6 +250,38 @@ impl TransactionalPartitionHandle {
         self.inner.remove(key)
     }
 
+    /// Removes all items of the partition.
+    ///
+    /// See [`PartitionHandle::clear`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    ///
+    /// partition.clear()?;
+    /// assert!(keyspace.read_tx().is_empty(&partition)?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a lock is poisoned.
+    pub fn clear(&self) -> crate::Result<()> {
+        let _lock = self.tx_lock.lock().expect("lock is poisoned");
+        self.inner.clear()
+    }
+
     /// Retrieves an item from the partition.
     ///
     /// The operation will run wrapped in a read snapshot.
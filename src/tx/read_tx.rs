@@ -1,5 +1,5 @@
-use crate::{Instant, TxPartitionHandle};
-use lsm_tree::{UserKey, UserValue};
+use crate::{partition::read::to_owned_bounds, Cursor, Instant, ReadOptions, TxPartitionHandle};
+use lsm_tree::{range::prefix_to_range, UserKey, UserValue};
 use std::ops::RangeBounds;
 
 /// A cross-partition, read-only transaction (snapshot)
@@ -48,6 +48,78 @@ impl ReadTransaction {
         Ok(partition.inner.snapshot_at(self.instant).get(key)?)
     }
 
+    /// Retrieves an item from the transaction's state, using the given read options.
+    ///
+    /// The snapshot of the read options is ignored, as the transaction reads at its own instant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, ReadOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "my_value")?;
+    ///
+    /// let tx = keyspace.read_tx();
+    /// partition.insert("a", "my_updated_value")?;
+    ///
+    /// let options = ReadOptions::default().fill_cache(false);
+    /// let item = tx.get_with_options(&partition, "a", options)?;
+    /// assert_eq!(Some("my_value".as_bytes().into()), item);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn get_with_options<K: AsRef<[u8]>>(
+        &self,
+        partition: &TxPartitionHandle,
+        key: K,
+        options: ReadOptions,
+    ) -> crate::Result<Option<UserValue>> {
+        partition
+            .inner
+            .get_from_range(key, None, options.snapshot(Some(self.instant)))
+    }
+
+    /// Retrieves multiple items from the transaction's state.
+    ///
+    /// The values are returned in the order of the given keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "my_value")?;
+    ///
+    /// let tx = keyspace.read_tx();
+    /// partition.insert("b", "my_value")?;
+    ///
+    /// let items = tx.multi_get(&partition, ["a", "b"])?;
+    /// assert_eq!(vec![Some("my_value".as_bytes().into()), None], items);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn multi_get<K: AsRef<[u8]>, I: IntoIterator<Item = K>>(
+        &self,
+        partition: &TxPartitionHandle,
+        keys: I,
+    ) -> crate::Result<Vec<Option<UserValue>>> {
+        partition.inner.multi_get_at(self.instant, keys)
+    }
+
     /// Returns `true` if the transaction's state contains the specified key.
     ///
     /// # Examples
@@ -242,11 +314,37 @@ impl ReadTransaction {
     ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
         partition
             .inner
-            .tree
+            .tree()
             .create_iter(Some(self.instant), None)
             .map(|item| Ok(item?))
     }
 
+    /// Opens a seekable cursor over the transaction's state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    ///
+    /// let tx = keyspace.read_tx();
+    /// partition.insert("b", "abc")?;
+    ///
+    /// let mut cursor = tx.cursor(&partition);
+    /// cursor.seek("b")?;
+    /// assert!(!cursor.is_valid());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn cursor(&self, partition: &TxPartitionHandle) -> Cursor {
+        partition.inner.cursor_at(self.instant)
+    }
+
     /// Iterates over a range of the transaction's state.
     ///
     /// Avoid using full or unbounded ranges as they may scan a lot of items (unless limited).
@@ -275,7 +373,7 @@ impl ReadTransaction {
     ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
         partition
             .inner
-            .tree
+            .tree()
             .create_range(&range, Some(self.instant), None)
             .map(|item| Ok(item?))
     }
@@ -308,8 +406,82 @@ impl ReadTransaction {
     ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
         partition
             .inner
-            .tree
+            .tree()
             .create_prefix(prefix, Some(self.instant), None)
             .map(|item| Ok(item?))
     }
+
+    /// Iterates over a range of the transaction's state, using the given read options.
+    ///
+    /// The snapshot of the read options is ignored, as the transaction reads at its own instant.
+    ///
+    /// Avoid using full or unbounded ranges as they may scan a lot of items (unless limited).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, ReadOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("f", "abc")?;
+    /// partition.insert("g", "abc")?;
+    ///
+    /// let options = ReadOptions::default().fill_cache(false);
+    /// assert_eq!(2, keyspace.read_tx().range_with_options(&partition, "a"..="f", options).count());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn range_with_options<'a, K: AsRef<[u8]> + 'a, R: RangeBounds<K> + 'a>(
+        &'a self,
+        partition: &'a TxPartitionHandle,
+        range: R,
+        options: ReadOptions,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
+        partition.inner.create_range_with_options(
+            to_owned_bounds(&range),
+            None,
+            options.snapshot(Some(self.instant)),
+        )
+    }
+
+    /// Iterates over a prefixed set of the transaction's state, using the given read options.
+    ///
+    /// The snapshot of the read options is ignored, as the transaction reads at its own instant.
+    ///
+    /// Avoid using an empty prefix as it may scan a lot of items (unless limited).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, ReadOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("ab", "abc")?;
+    /// partition.insert("abc", "abc")?;
+    ///
+    /// let options = ReadOptions::default().fill_cache(false);
+    /// assert_eq!(2, keyspace.read_tx().prefix_with_options(&partition, "ab", options).count());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn prefix_with_options<'a, K: AsRef<[u8]> + 'a>(
+        &'a self,
+        partition: &'a TxPartitionHandle,
+        prefix: K,
+        options: ReadOptions,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
+        partition.inner.create_range_with_options(
+            prefix_to_range(prefix.as_ref()),
+            None,
+            options.snapshot(Some(self.instant)),
+        )
+    }
 }
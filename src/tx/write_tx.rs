@@ -1,11 +1,12 @@
 use crate::{
-    batch::{item::Item, PartitionKey},
-    Batch, Instant, Keyspace, TxPartitionHandle,
+    batch::item::Item,
+    partition::read::{to_owned_bounds, PendingWrites},
+    Batch, Cursor, Instant, Keyspace, PartitionHandle, ReadOptions, TxPartitionHandle,
 };
-use lsm_tree::{MemTable, SeqNo, UserKey, UserValue, Value};
+use lsm_tree::{range::prefix_to_range, MemTable, SeqNo, UserKey, UserValue, Value, ValueType};
 use std::{
     collections::HashMap,
-    ops::RangeBounds,
+    ops::{Bound::Unbounded, Range, RangeBounds},
     sync::{Arc, MutexGuard},
 };
 
@@ -17,6 +18,14 @@ fn ignore_tombstone_value(item: Value) -> Option<Value> {
     }
 }
 
+/// A position in a [`WriteTransaction`]'s pending writes
+///
+/// Created using [`WriteTransaction::savepoint`], and used to undo
+/// all writes made after it using [`WriteTransaction::rollback_to`].
+pub struct Savepoint {
+    seqno: SeqNo,
+}
+
 /// A single-writer (serialized) cross-partition transaction
 ///
 /// Use [`WriteTransaction::commit`] to commit changes to the partition(s).
@@ -24,9 +33,24 @@ fn ignore_tombstone_value(item: Value) -> Option<Value> {
 /// Drop the transaction to rollback changes.
 pub struct WriteTransaction<'a> {
     keyspace: Keyspace,
-    memtables: HashMap<PartitionKey, Arc<MemTable>>,
+    memtables: HashMap<PartitionHandle, Arc<MemTable>>,
     instant: Instant,
 
+    /// Seqno of the next pending write
+    ///
+    /// Pending writes are appended as new versions of their keys, starting at the
+    /// transaction's instant, so they are newer than every item the transaction reads.
+    next_seqno: SeqNo,
+
+    /// Position of the newest savepoint
+    ///
+    /// Versions written after it can be overwritten in place,
+    /// as they can only be rolled back together.
+    savepoint_seqno: SeqNo,
+
+    /// Seqno ranges of rolled back pending writes
+    rolled_back: Arc<Vec<Range<SeqNo>>>,
+
     #[allow(unused)]
     tx_lock: MutexGuard<'a, ()>,
 }
@@ -37,6 +61,9 @@ impl<'a> WriteTransaction<'a> {
             keyspace,
             memtables: HashMap::default(),
             instant,
+            next_seqno: instant,
+            savepoint_seqno: instant,
+            rolled_back: Arc::default(),
             tx_lock,
         }
     }
@@ -79,8 +106,8 @@ impl<'a> WriteTransaction<'a> {
         partition: &TxPartitionHandle,
         key: K,
     ) -> crate::Result<Option<lsm_tree::UserValue>> {
-        if let Some(memtable) = self.memtables.get(&partition.inner.name) {
-            if let Some(item) = memtable.get(&key, None) {
+        if let Some(pending) = self.pending(&partition.inner) {
+            if let Some(item) = pending.get(key.as_ref()) {
                 return Ok(ignore_tombstone_value(item).map(|x| x.value));
             }
         }
@@ -88,6 +115,49 @@ impl<'a> WriteTransaction<'a> {
         partition.inner.get(key)
     }
 
+    /// Retrieves an item from the transaction's state, using the given read options.
+    ///
+    /// The transaction allows reading your own writes (RYOW).
+    ///
+    /// The snapshot of the read options is ignored, as the transaction reads at its own instant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, ReadOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "previous_value")?;
+    ///
+    /// let mut tx = keyspace.write_tx();
+    /// tx.insert(&partition, "a", "new_value");
+    ///
+    /// // Read-your-own-write
+    /// let options = ReadOptions::default().fill_cache(false);
+    /// let item = tx.get_with_options(&partition, "a", options)?;
+    /// assert_eq!(Some("new_value".as_bytes().into()), item);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn get_with_options<K: AsRef<[u8]>>(
+        &self,
+        partition: &TxPartitionHandle,
+        key: K,
+        options: ReadOptions,
+    ) -> crate::Result<Option<UserValue>> {
+        partition.inner.get_from_range(
+            key,
+            self.pending(&partition.inner),
+            options.snapshot(Some(self.instant)),
+        )
+    }
+
     /// Returns `true` if the transaction's state contains the specified key.
     ///
     /// # Examples
@@ -233,9 +303,7 @@ impl<'a> WriteTransaction<'a> {
     pub fn len(&self, partition: &TxPartitionHandle) -> crate::Result<usize> {
         let mut count = 0;
 
-        let iter = self.iter(partition);
-
-        for kv in iter {
+        for kv in self.iter(partition) {
             let _ = kv?;
             count += 1;
         }
@@ -271,14 +339,54 @@ impl<'a> WriteTransaction<'a> {
         &'b self,
         partition: &'b TxPartitionHandle,
     ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
-        partition
-            .inner
-            .tree
-            .create_iter(
-                Some(self.instant),
-                self.memtables.get(&partition.inner.name).cloned(),
-            )
-            .map(|item| Ok(item?))
+        partition.inner.create_range_with_options(
+            (Unbounded, Unbounded),
+            self.pending(&partition.inner),
+            ReadOptions::default().snapshot(Some(self.instant)),
+        )
+    }
+
+    /// Opens a seekable cursor over the transaction's state.
+    ///
+    /// The cursor allows reading your own writes (RYOW), including writes made
+    /// after the cursor was opened. After rolling back to a savepoint,
+    /// the cursor needs to be opened again to observe the rolled back state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    ///
+    /// let mut tx = keyspace.write_tx();
+    /// tx.insert(&partition, "b", "abc");
+    ///
+    /// let mut cursor = tx.cursor(&partition);
+    /// cursor.seek_to_first()?;
+    /// cursor.next()?;
+    /// assert_eq!(Some(&"b".as_bytes().into()), cursor.key());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn cursor(&mut self, partition: &TxPartitionHandle) -> Cursor {
+        // NOTE: The cursor shares the pending writes, so it observes later writes as well
+        let memtable = self
+            .memtables
+            .entry(partition.inner.clone())
+            .or_default()
+            .clone();
+
+        let pending = PendingWrites {
+            memtable,
+            rolled_back: self.rolled_back.clone(),
+        };
+
+        Cursor::new(partition.inner.clone(), self.instant, Some(pending))
     }
 
     /// Iterates over a range of the transaction's state.
@@ -310,15 +418,11 @@ impl<'a> WriteTransaction<'a> {
         partition: &'b TxPartitionHandle,
         range: R,
     ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
-        partition
-            .inner
-            .tree
-            .create_range(
-                &range,
-                Some(self.instant),
-                self.memtables.get(&partition.inner.name).cloned(),
-            )
-            .map(|item| Ok(item?))
+        partition.inner.create_range_with_options(
+            to_owned_bounds(&range),
+            self.pending(&partition.inner),
+            ReadOptions::default().snapshot(Some(self.instant)),
+        )
     }
 
     /// Iterates over a range of the transaction's state.
@@ -350,15 +454,93 @@ impl<'a> WriteTransaction<'a> {
         partition: &'b TxPartitionHandle,
         prefix: K,
     ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
-        partition
-            .inner
-            .tree
-            .create_prefix(
-                prefix,
-                Some(self.instant),
-                self.memtables.get(&partition.inner.name).cloned(),
-            )
-            .map(|item| Ok(item?))
+        partition.inner.create_range_with_options(
+            prefix_to_range(prefix.as_ref()),
+            self.pending(&partition.inner),
+            ReadOptions::default().snapshot(Some(self.instant)),
+        )
+    }
+
+    /// Iterates over a range of the transaction's state, using the given read options.
+    ///
+    /// The transaction allows reading your own writes (RYOW).
+    ///
+    /// The snapshot of the read options is ignored, as the transaction reads at its own instant.
+    ///
+    /// Avoid using full or unbounded ranges as they may scan a lot of items (unless limited).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, ReadOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// #
+    /// let mut tx = keyspace.write_tx();
+    /// tx.insert(&partition, "a", "abc");
+    /// tx.insert(&partition, "f", "abc");
+    /// tx.insert(&partition, "g", "abc");
+    ///
+    /// let options = ReadOptions::default().fill_cache(false);
+    /// assert_eq!(2, tx.range_with_options(&partition, "a"..="f", options).count());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn range_with_options<'b, K: AsRef<[u8]> + 'b, R: RangeBounds<K> + 'b>(
+        &'b self,
+        partition: &'b TxPartitionHandle,
+        range: R,
+        options: ReadOptions,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
+        partition.inner.create_range_with_options(
+            to_owned_bounds(&range),
+            self.pending(&partition.inner),
+            options.snapshot(Some(self.instant)),
+        )
+    }
+
+    /// Iterates over a prefixed set of the transaction's state, using the given read options.
+    ///
+    /// The transaction allows reading your own writes (RYOW).
+    ///
+    /// The snapshot of the read options is ignored, as the transaction reads at its own instant.
+    ///
+    /// Avoid using an empty prefix as it may scan a lot of items (unless limited).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions, ReadOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// #
+    /// let mut tx = keyspace.write_tx();
+    /// tx.insert(&partition, "a", "abc");
+    /// tx.insert(&partition, "ab", "abc");
+    /// tx.insert(&partition, "abc", "abc");
+    ///
+    /// let options = ReadOptions::default().fill_cache(false);
+    /// assert_eq!(2, tx.prefix_with_options(&partition, "ab", options).count());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn prefix_with_options<'b, K: AsRef<[u8]> + 'b>(
+        &'b self,
+        partition: &'b TxPartitionHandle,
+        prefix: K,
+        options: ReadOptions,
+    ) -> impl DoubleEndedIterator<Item = crate::Result<(UserKey, UserValue)>> {
+        partition.inner.create_range_with_options(
+            prefix_to_range(prefix.as_ref()),
+            self.pending(&partition.inner),
+            options.snapshot(Some(self.instant)),
+        )
     }
 
     /// Inserts a key-value pair into the partition.
@@ -399,17 +581,12 @@ impl<'a> WriteTransaction<'a> {
         key: K,
         value: V,
     ) {
-        self.memtables
-            .entry(partition.inner.name.clone())
-            .or_default()
-            .insert(lsm_tree::Value::new(
-                key.as_ref(),
-                value.as_ref(),
-                // NOTE: Just take the max seqno, which should never be reached
-                // that way, the write is definitely always the newest
-                SeqNo::MAX,
-                lsm_tree::ValueType::Value,
-            ));
+        self.write(
+            &partition.inner,
+            key.as_ref(),
+            value.as_ref(),
+            ValueType::Value,
+        );
     }
 
     /// Removes an item from the partition.
@@ -447,35 +624,366 @@ impl<'a> WriteTransaction<'a> {
     ///
     /// Will return `Err` if an IO error occurs.
     pub fn remove<K: AsRef<[u8]>>(&mut self, partition: &TxPartitionHandle, key: K) {
-        self.memtables
-            .entry(partition.inner.name.clone())
-            .or_default()
-            .insert(lsm_tree::Value::new_tombstone(
-                key.as_ref(),
-                // NOTE: Just take the max seqno, which should never be reached
-                // that way, the write is definitely always the newest
-                SeqNo::MAX,
-            ));
+        self.write(&partition.inner, key.as_ref(), &[], ValueType::Tombstone);
+    }
+
+    /// Removes all items inside a range of the transaction's state.
+    ///
+    /// The removal respects the transaction's pending writes;
+    /// a tombstone is written for every item, so this is an O(n) operation.
+    ///
+    /// Returns the amount of removed items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    /// partition.insert("b", "abc")?;
+    ///
+    /// let mut tx = keyspace.write_tx();
+    /// tx.insert(&partition, "c", "abc");
+    /// tx.insert(&partition, "d", "abc");
+    ///
+    /// assert_eq!(3, tx.remove_range(&partition, "b"..="d")?);
+    /// assert_eq!(1, tx.len(&partition)?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn remove_range<K: AsRef<[u8]>, R: RangeBounds<K>>(
+        &mut self,
+        partition: &TxPartitionHandle,
+        range: R,
+    ) -> crate::Result<usize> {
+        let keys = self
+            .range(partition, range)
+            .map(|kv| Ok(kv?.0))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        for key in &keys {
+            self.remove(partition, key);
+        }
+
+        Ok(keys.len())
+    }
+
+    /// Removes all items with the given prefix from the transaction's state.
+    ///
+    /// The removal respects the transaction's pending writes;
+    /// a tombstone is written for every item, so this is an O(n) operation.
+    ///
+    /// Returns the amount of removed items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("user#1", "abc")?;
+    ///
+    /// let mut tx = keyspace.write_tx();
+    /// tx.insert(&partition, "user#2", "abc");
+    /// tx.insert(&partition, "order#1", "abc");
+    ///
+    /// assert_eq!(2, tx.remove_prefix(&partition, "user#")?);
+    /// tx.commit()?;
+    ///
+    /// assert_eq!(1, keyspace.read_tx().len(&partition)?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn remove_prefix<K: AsRef<[u8]>>(
+        &mut self,
+        partition: &TxPartitionHandle,
+        prefix: K,
+    ) -> crate::Result<usize> {
+        let keys = self
+            .prefix(partition, prefix)
+            .map(|kv| Ok(kv?.0))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        for key in &keys {
+            self.remove(partition, key);
+        }
+
+        Ok(keys.len())
+    }
+
+    /// Removes an item and returns its value if it existed.
+    ///
+    /// The transaction's pending writes are respected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    ///
+    /// let mut tx = keyspace.write_tx();
+    /// tx.insert(&partition, "a", "def");
+    ///
+    /// let taken = tx.take(&partition, "a")?.unwrap();
+    /// assert_eq!(b"def", &*taken);
+    ///
+    /// let item = tx.get(&partition, "a")?;
+    /// assert!(item.is_none());
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn take<K: AsRef<[u8]>>(
+        &mut self,
+        partition: &TxPartitionHandle,
+        key: K,
+    ) -> crate::Result<Option<UserValue>> {
+        self.fetch_update(partition, key, |_| None)
+    }
+
+    /// Updates an item and returns the previous value.
+    ///
+    /// Returning `None` removes the item if it existed before.
+    ///
+    /// The transaction's pending writes are respected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// # use std::sync::Arc;
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// partition.insert("a", "abc")?;
+    ///
+    /// let mut tx = keyspace.write_tx();
+    /// tx.insert(&partition, "a", "def");
+    ///
+    /// let prev = tx.fetch_update(&partition, "a", |_| Some(Arc::from(*b"ghi")))?.unwrap();
+    /// assert_eq!(b"def", &*prev);
+    ///
+    /// let item = tx.get(&partition, "a")?;
+    /// assert_eq!(Some("ghi".as_bytes().into()), item);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn fetch_update<K: AsRef<[u8]>, F: Fn(Option<&UserValue>) -> Option<UserValue>>(
+        &mut self,
+        partition: &TxPartitionHandle,
+        key: K,
+        f: F,
+    ) -> crate::Result<Option<UserValue>> {
+        let prev = self.get(partition, &key)?;
+        let updated = f(prev.as_ref());
+
+        if let Some(value) = updated {
+            self.insert(partition, &key, value);
+        } else if prev.is_some() {
+            self.remove(partition, &key);
+        }
+
+        Ok(prev)
+    }
+
+    /// Updates an item and returns the new value.
+    ///
+    /// Returning `None` removes the item if it existed before.
+    ///
+    /// The transaction's pending writes are respected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// # use std::sync::Arc;
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let mut tx = keyspace.write_tx();
+    /// tx.insert(&partition, "a", "abc");
+    ///
+    /// let updated = tx.update_fetch(&partition, "a", |_| Some(Arc::from(*b"def")))?.unwrap();
+    /// assert_eq!(b"def", &*updated);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an IO error occurs.
+    pub fn update_fetch<K: AsRef<[u8]>, F: Fn(Option<&UserValue>) -> Option<UserValue>>(
+        &mut self,
+        partition: &TxPartitionHandle,
+        key: K,
+        f: F,
+    ) -> crate::Result<Option<UserValue>> {
+        let prev = self.get(partition, &key)?;
+        let updated = f(prev.as_ref());
+
+        if let Some(value) = &updated {
+            self.insert(partition, &key, value);
+        } else if prev.is_some() {
+            self.remove(partition, &key);
+        }
+
+        Ok(updated)
+    }
+
+    /// Returns a savepoint of the transaction's pending writes.
+    ///
+    /// Use [`WriteTransaction::rollback_to`] to undo all writes made after it,
+    /// while keeping the writes made before.
+    ///
+    /// The savepoint only holds a position in the pending writes, so this is an O(1) operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fjall::{Config, Keyspace, PartitionCreateOptions};
+    /// #
+    /// # let folder = tempfile::tempdir()?;
+    /// # let keyspace = Config::new(folder).open_transactional()?;
+    /// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    /// let mut tx = keyspace.write_tx();
+    /// tx.insert(&partition, "a", "abc");
+    ///
+    /// let savepoint = tx.savepoint();
+    /// tx.insert(&partition, "a", "def");
+    /// tx.insert(&partition, "b", "def");
+    ///
+    /// tx.rollback_to(&savepoint);
+    /// assert_eq!(Some("abc".as_bytes().into()), tx.get(&partition, "a")?);
+    /// assert_eq!(None, tx.get(&partition, "b")?);
+    ///
+    /// tx.commit()?;
+    /// assert_eq!(1, keyspace.read_tx().len(&partition)?);
+    /// #
+    /// # Ok::<(), fjall::Error>(())
+    /// ```
+    #[must_use]
+    pub fn savepoint(&mut self) -> Savepoint {
+        self.savepoint_seqno = self.next_seqno;
+
+        Savepoint {
+            seqno: self.next_seqno,
+        }
+    }
+
+    /// Undoes all writes made after the savepoint was created.
+    ///
+    /// The savepoint needs to be created by this transaction.
+    /// It stays valid, so the transaction can be rolled back to it again.
+    /// Savepoints created after it are released, and should not be used anymore.
+    ///
+    /// The writes are only marked as rolled back, so this is an O(1) operation.
+    /// Their memory is freed when the transaction ends.
+    pub fn rollback_to(&mut self, savepoint: &Savepoint) {
+        if savepoint.seqno < self.next_seqno {
+            let rolled_back = Arc::make_mut(&mut self.rolled_back);
+
+            // NOTE: Seqnos only grow, so ranges starting after the savepoint
+            // are contained in the new range
+            while rolled_back
+                .last()
+                .is_some_and(|range| range.start >= savepoint.seqno)
+            {
+                rolled_back.pop();
+            }
+
+            match rolled_back.last_mut() {
+                Some(range) if range.end >= savepoint.seqno => range.end = self.next_seqno,
+                _ => rolled_back.push(savepoint.seqno..self.next_seqno),
+            }
+        }
+
+        self.savepoint_seqno = savepoint.seqno;
+    }
+
+    /// Returns the pending writes of a partition, to be read from.
+    fn pending(&self, partition: &PartitionHandle) -> Option<PendingWrites> {
+        self.memtables.get(partition).map(|memtable| PendingWrites {
+            memtable: memtable.clone(),
+            rolled_back: self.rolled_back.clone(),
+        })
+    }
+
+    /// Appends a write to the pending writes of a partition.
+    fn write(
+        &mut self,
+        partition: &PartitionHandle,
+        key: &[u8],
+        value: &[u8],
+        value_type: ValueType,
+    ) {
+        let memtable = self.memtables.entry(partition.clone()).or_default();
+
+        let pending = PendingWrites {
+            memtable: memtable.clone(),
+            rolled_back: self.rolled_back.clone(),
+        };
+
+        let seqno = match pending.get(key) {
+            Some(item) if item.seqno >= self.savepoint_seqno => item.seqno,
+            _ => {
+                let seqno = self.next_seqno;
+                self.next_seqno += 1;
+                seqno
+            }
+        };
+
+        memtable.insert(Value::new(key, value, seqno, value_type));
     }
 
     /// Commits the transaction.
     ///
+    /// Returns the seqno of the commit, see [`crate::Batch::commit`].
+    /// The transaction's writes are visible to snapshots at `seqno + 1` or later.
+    ///
     /// # Errors
     ///
     /// Will return `Err` if an IO error occurs.
-    pub fn commit(self) -> crate::Result<()> {
+    pub fn commit(self) -> crate::Result<Instant> {
         let mut batch = Batch::with_capacity(self.keyspace, 10);
 
-        for (partition_key, memtable) in &self.memtables {
-            for entry in &memtable.items {
-                let key = entry.key();
-                let value = entry.value();
+        for (partition, memtable) in &self.memtables {
+            let partition_key = partition.name();
+
+            let pending = PendingWrites {
+                memtable: memtable.clone(),
+                rolled_back: self.rolled_back.clone(),
+            };
 
-                batch.data.push(Item::new(
-                    partition_key.clone(),
-                    key.user_key.clone(),
-                    value.clone(),
-                    key.value_type,
+            for item in pending.iter() {
+                batch.data.push((
+                    partition.clone(),
+                    Item::new(partition_key.clone(), item.key, item.value, item.value_type),
                 ));
             }
         }
@@ -0,0 +1,42 @@
+use crate::PersistMode;
+
+/// Options for a single write operation
+///
+/// # Examples
+///
+/// ```
+/// # use fjall::{Config, PartitionCreateOptions, PersistMode, WriteOptions};
+/// #
+/// # let folder = tempfile::tempdir()?;
+/// # let keyspace = Config::new(folder).open()?;
+/// # let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+/// let options = WriteOptions::default().durability(Some(PersistMode::SyncAll));
+///
+/// // The write is synced to disk when the function returns
+/// let seqno = partition.insert_with_options("a", "abc", options)?;
+/// #
+/// # Ok::<(), fjall::Error>(())
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[allow(clippy::module_name_repetitions)]
+pub struct WriteOptions {
+    /// Durability the write needs to have before it is acknowledged
+    pub(crate) durability: Option<PersistMode>,
+}
+
+impl WriteOptions {
+    /// Sets the durability the write needs to have before it is acknowledged.
+    ///
+    /// If `None`, the write is only buffered in memory, and is persisted
+    /// by the fsync thread, or an explicit [`crate::Keyspace::persist`].
+    ///
+    /// Concurrent writers that request a sync are coalesced into a single
+    /// sync per journal shard (group commit).
+    ///
+    /// Default = None
+    #[must_use]
+    pub fn durability(mut self, mode: Option<PersistMode>) -> Self {
+        self.durability = mode;
+        self
+    }
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "async")]
+
+use fjall::{Config, PartitionCreateOptions, PersistMode};
+use futures_util::StreamExt;
+
+const ITEM_COUNT: usize = 100;
+
+#[tokio::test]
+async fn async_partition_read_write() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let keyspace = Config::new(&folder).open_async().await?;
+    let partition = keyspace
+        .open_partition("default", PartitionCreateOptions::default())
+        .await?;
+
+    for x in 0..ITEM_COUNT as u64 {
+        partition.insert(x.to_be_bytes(), "abc").await?;
+    }
+
+    for x in 0..(ITEM_COUNT / 2) as u64 {
+        partition.remove(x.to_be_bytes()).await?;
+    }
+
+    keyspace.persist(PersistMode::SyncAll).await?;
+
+    assert_eq!(None, partition.get(0u64.to_be_bytes()).await?);
+    assert_eq!(
+        Some("abc".as_bytes().into()),
+        partition.get((ITEM_COUNT as u64 - 1).to_be_bytes()).await?
+    );
+    assert_eq!(ITEM_COUNT / 2, partition.inner().len()?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_batch() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let keyspace = Config::new(&folder).open_async().await?;
+    let partition1 = keyspace
+        .open_partition("default1", PartitionCreateOptions::default())
+        .await?;
+    let partition2 = keyspace
+        .open_partition("default2", PartitionCreateOptions::default())
+        .await?;
+
+    partition1.insert("a", "abc").await?;
+
+    let mut batch = keyspace.batch();
+    batch.remove(&partition1, "a");
+    batch.insert(&partition1, "b", "abc");
+    batch.insert(&partition2, "c", "abc");
+    batch.commit().await?;
+
+    assert!(!partition1.contains_key("a").await?);
+    assert!(partition1.contains_key("b").await?);
+    assert!(partition2.contains_key("c").await?);
+
+    keyspace.delete_partition(partition2).await?;
+    assert_eq!(1, keyspace.partition_count());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn async_streams() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let keyspace = Config::new(&folder).open_async().await?;
+    let partition = keyspace
+        .open_partition("default", PartitionCreateOptions::default())
+        .await?;
+
+    for prefix in ["a", "b", "c"] {
+        for x in 0..ITEM_COUNT {
+            partition.insert(format!("{prefix}{x:0>5}"), "abc").await?;
+        }
+    }
+
+    assert_eq!(ITEM_COUNT * 3, partition.iter().count().await);
+    assert_eq!(ITEM_COUNT, partition.prefix("b").count().await);
+    assert_eq!(ITEM_COUNT * 2, partition.range("b"..).count().await);
+    assert_eq!(0, partition.prefix("d").count().await);
+
+    let mut stream = partition.range("b".."c");
+    let (key, _) = stream.next().await.expect("should exist")?;
+    assert_eq!(b"b00000", &*key);
+
+    // NOTE: Dropping the stream early stops the background read
+    drop(stream);
+
+    let keys = partition
+        .prefix("c")
+        .map(|kv| kv.expect("should read").0)
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(ITEM_COUNT, keys.len());
+    assert!(keys.windows(2).all(|w| w[0] < w[1]));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn async_write_stall() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let keyspace = Config::new(&folder)
+        .max_write_buffer_size(1_024 * 1_024)
+        .open_async()
+        .await?;
+    let partition = keyspace
+        .open_partition("default", PartitionCreateOptions::default())
+        .await?;
+    partition.inner().set_max_memtable_size(128_000);
+
+    let value = "a".repeat(1_000);
+
+    for x in 0..4_000u64 {
+        partition.insert(x.to_be_bytes(), &value).await?;
+    }
+
+    assert_eq!(4_000, partition.iter().count().await);
+
+    // NOTE: Writes were stalled until the write buffer was flushed
+    assert!(keyspace.inner().write_buffer_size() < 4_000 * 1_000);
+
+    Ok(())
+}
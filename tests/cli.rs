@@ -0,0 +1,81 @@
+#![cfg(feature = "cli")]
+
+use std::{io::Write, path::Path, process::Command};
+use test_log::test;
+
+// NOTE: More than the default write buffer size of 64 MiB,
+// so the import needs to flush memtables while writing
+const ITEM_COUNT: u64 = 20_000;
+const VALUE_SIZE: usize = 4_096;
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn fjall(args: &[&Path]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_fjall"))
+        .args(args)
+        .output()
+        .expect("should run CLI");
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr),
+    );
+
+    String::from_utf8(output.stdout).expect("should be UTF-8")
+}
+
+fn write_input(path: &Path) -> std::io::Result<()> {
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+    let value = to_hex(&[b'a'; VALUE_SIZE]);
+
+    for x in 0..ITEM_COUNT {
+        writeln!(
+            file,
+            r#"{{"partition":"default","key":"{}","value":"{value}"}}"#,
+            to_hex(&x.to_be_bytes()),
+        )?;
+    }
+
+    for x in 0..10_u64 {
+        writeln!(
+            file,
+            r#"{{"partition":"other","key":"{}","value":"{}"}}"#,
+            to_hex(&x.to_be_bytes()),
+            to_hex(b"abc"),
+        )?;
+    }
+
+    file.flush()
+}
+
+#[test]
+fn cli_import_export_compact() -> std::io::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let keyspace = folder.path().join("keyspace");
+    let input = folder.path().join("input.jsonl");
+    let output = folder.path().join("output.jsonl");
+
+    write_input(&input)?;
+
+    fjall(&["import".as_ref(), &keyspace, "--input".as_ref(), &input]);
+
+    // NOTE: Partitions and keys are exported in order, so the export matches the input
+    fjall(&["export".as_ref(), &keyspace, "--output".as_ref(), &output]);
+    assert!(std::fs::read(&input)? == std::fs::read(&output)?);
+
+    let stdout = fjall(&["compact".as_ref(), &keyspace]);
+    assert!(stdout.contains("default: 1 segments"));
+    assert!(stdout.contains("other: 1 segments"));
+
+    let stdout = fjall(&["verify".as_ref(), &keyspace]);
+    assert_eq!("default: OK\nother: OK\n", stdout);
+
+    fjall(&["export".as_ref(), &keyspace, "--output".as_ref(), &output]);
+    assert!(std::fs::read(&input)? == std::fs::read(&output)?);
+
+    Ok(())
+}
@@ -0,0 +1,205 @@
+use fjall::{Config, PartitionCreateOptions};
+use std::ops::Bound;
+use test_log::test;
+
+const ITEM_COUNT: u64 = 100;
+
+#[test]
+fn cursor_seek_and_step() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    // NOTE: Have even keys in segments, odd keys in the active memtable
+    for x in (0..ITEM_COUNT).step_by(2) {
+        partition.insert(x.to_be_bytes(), x.to_be_bytes())?;
+    }
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+
+    for x in (1..ITEM_COUNT).step_by(2) {
+        partition.insert(x.to_be_bytes(), x.to_be_bytes())?;
+    }
+    partition.remove(50u64.to_be_bytes())?;
+
+    let mut cursor = partition.cursor();
+    assert!(!cursor.is_valid());
+
+    cursor.next()?;
+    assert!(!cursor.is_valid());
+
+    cursor.seek(10u64.to_be_bytes())?;
+    assert_eq!(Some(&10u64.to_be_bytes().into()), cursor.key());
+    assert_eq!(Some(&10u64.to_be_bytes().into()), cursor.value());
+
+    cursor.next()?;
+    assert_eq!(Some(&11u64.to_be_bytes().into()), cursor.key());
+
+    // NOTE: Change direction
+    cursor.prev()?;
+    cursor.prev()?;
+    assert_eq!(Some(&9u64.to_be_bytes().into()), cursor.key());
+
+    cursor.next()?;
+    assert_eq!(Some(&10u64.to_be_bytes().into()), cursor.key());
+
+    // NOTE: Deleted items are skipped
+    cursor.seek(50u64.to_be_bytes())?;
+    assert_eq!(Some(&51u64.to_be_bytes().into()), cursor.key());
+
+    cursor.seek_for_prev(50u64.to_be_bytes())?;
+    assert_eq!(Some(&49u64.to_be_bytes().into()), cursor.key());
+
+    cursor.seek_to_first()?;
+    let mut count = 0;
+    while cursor.is_valid() {
+        count += 1;
+        cursor.next()?;
+    }
+    assert_eq!(ITEM_COUNT - 1, count);
+
+    cursor.seek_to_last()?;
+    let mut count = 0;
+    while cursor.is_valid() {
+        count += 1;
+        cursor.prev()?;
+    }
+    assert_eq!(ITEM_COUNT - 1, count);
+
+    cursor.seek(ITEM_COUNT.to_be_bytes())?;
+    assert!(!cursor.is_valid());
+
+    Ok(())
+}
+
+#[test]
+fn cursor_bounds() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    for key in ["a", "b", "c", "d", "e"] {
+        partition.insert(key, key)?;
+    }
+
+    let mut cursor = partition.cursor();
+    cursor.set_lower_bound(Bound::Excluded("a"));
+    cursor.set_upper_bound(Bound::Included("d"));
+
+    cursor.seek_to_first()?;
+    assert_eq!(Some(&"b".as_bytes().into()), cursor.key());
+
+    cursor.seek_to_last()?;
+    assert_eq!(Some(&"d".as_bytes().into()), cursor.key());
+
+    cursor.next()?;
+    assert!(!cursor.is_valid());
+
+    cursor.seek("a")?;
+    assert_eq!(Some(&"b".as_bytes().into()), cursor.key());
+
+    cursor.prev()?;
+    assert!(!cursor.is_valid());
+
+    cursor.seek_for_prev("z")?;
+    assert_eq!(Some(&"d".as_bytes().into()), cursor.key());
+
+    cursor.seek("e")?;
+    assert!(!cursor.is_valid());
+
+    // NOTE: Changing a bound unpositions the cursor
+    cursor.seek("c")?;
+    cursor.set_upper_bound(Bound::Excluded("c"));
+    assert!(!cursor.is_valid());
+
+    cursor.seek("c")?;
+    assert!(!cursor.is_valid());
+
+    cursor.set_lower_bound(Bound::Included("d"));
+    cursor.seek_to_first()?;
+    assert!(!cursor.is_valid());
+
+    cursor.set_lower_bound(Bound::<&str>::Unbounded);
+    cursor.set_upper_bound(Bound::<&str>::Unbounded);
+    cursor.seek_to_last()?;
+    assert_eq!(Some(&"e".as_bytes().into()), cursor.key());
+
+    Ok(())
+}
+
+#[test]
+fn cursor_snapshot() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    partition.insert("a", "old")?;
+    let instant = keyspace.instant();
+
+    let mut cursor = partition.cursor();
+
+    partition.insert("a", "new")?;
+    partition.insert("b", "new")?;
+
+    cursor.seek_to_first()?;
+    assert_eq!(
+        Some(&("a".as_bytes().into(), "old".as_bytes().into())),
+        cursor.item()
+    );
+    cursor.next()?;
+    assert!(!cursor.is_valid());
+
+    let mut cursor = partition.cursor_at(instant);
+    cursor.seek_to_last()?;
+    assert_eq!(Some(&"old".as_bytes().into()), cursor.value());
+
+    let mut cursor = partition.cursor();
+    cursor.seek_to_last()?;
+    assert_eq!(Some(&"b".as_bytes().into()), cursor.key());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "single_writer_tx")]
+fn tx_cursor() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open_transactional()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    partition.insert("a", "a")?;
+    partition.insert("c", "c")?;
+
+    let read_tx = keyspace.read_tx();
+
+    let mut tx = keyspace.write_tx();
+    tx.insert(&partition, "b", "b");
+    tx.remove(&partition, "c");
+
+    let mut cursor = tx.cursor(&partition);
+    cursor.seek_to_first()?;
+    assert_eq!(Some(&"a".as_bytes().into()), cursor.key());
+    cursor.next()?;
+    assert_eq!(Some(&"b".as_bytes().into()), cursor.key());
+    cursor.next()?;
+    assert!(!cursor.is_valid());
+    drop(cursor);
+
+    tx.commit()?;
+
+    let mut cursor = read_tx.cursor(&partition);
+    cursor.seek("b")?;
+    assert_eq!(Some(&"c".as_bytes().into()), cursor.key());
+
+    let mut cursor = keyspace.read_tx().cursor(&partition);
+    cursor.seek("b")?;
+    assert_eq!(Some(&"b".as_bytes().into()), cursor.key());
+    cursor.next()?;
+    assert!(!cursor.is_valid());
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use fjall::{Config, PartitionCreateOptions, PersistMode, WriteOptions};
+use std::collections::HashSet;
+use test_log::test;
+
+const WRITER_COUNT: u64 = 8;
+const ITEM_COUNT: u64 = 100;
+
+#[test]
+fn group_commit_concurrent_writers() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).fsync_ms(None).open()?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        let options = WriteOptions::default().durability(Some(PersistMode::SyncAll));
+
+        let seqnos = std::thread::scope(|scope| {
+            let handles = (0..WRITER_COUNT)
+                .map(|writer| {
+                    let partition = partition.clone();
+
+                    scope.spawn(move || {
+                        (0..ITEM_COUNT)
+                            .map(|x| {
+                                let key = (writer * ITEM_COUNT + x).to_be_bytes();
+                                partition.insert_with_options(key, "abc", options)
+                            })
+                            .collect::<fjall::Result<Vec<_>>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("should join"))
+                .collect::<fjall::Result<Vec<_>>>()
+        })?;
+
+        let seqnos = seqnos.into_iter().flatten().collect::<HashSet<_>>();
+        assert_eq!(seqnos.len() as u64, WRITER_COUNT * ITEM_COUNT);
+        assert_eq!(partition.len()?, (WRITER_COUNT * ITEM_COUNT) as usize);
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        assert_eq!(partition.len()?, (WRITER_COUNT * ITEM_COUNT) as usize);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn group_commit_batch_and_remove() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).fsync_ms(None).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let options = WriteOptions::default().durability(Some(PersistMode::SyncData));
+
+    let mut batch = keyspace.batch();
+    batch.insert(&partition, "a", "abc");
+    batch.insert(&partition, "b", "abc");
+    let batch_seqno = batch.commit_with_options(options)?;
+
+    let remove_seqno = partition.remove_with_options("a", options)?;
+    assert!(remove_seqno > batch_seqno);
+
+    assert!(partition.snapshot_at(batch_seqno + 1).contains_key("a")?);
+    assert!(!partition.contains_key("a")?);
+    assert!(partition.contains_key("b")?);
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const PARTITION_COUNT: usize = 10;
+
+/// Returns the sorted partition IDs listed in a sealed journal's manifest
+fn read_manifest(path: &std::path::Path, journal_id: u64) -> fjall::Result<Vec<u64>> {
+    let content = std::fs::read_to_string(
+        path.join("journals")
+            .join(journal_id.to_string())
+            .join(".partition_ids"),
+    )?;
+
+    let mut ids = content
+        .lines()
+        .map(|line| {
+            line.split(':')
+                .next()
+                .expect("should exist")
+                .parse::<u64>()
+                .expect("should be partition ID")
+        })
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+
+    Ok(ids)
+}
+
+#[test]
+fn journal_manifest_only_dirty_partitions() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let partitions = (0..PARTITION_COUNT)
+            .map(|idx| {
+                keyspace.open_partition(&format!("p{idx}"), PartitionCreateOptions::default())
+            })
+            .collect::<fjall::Result<Vec<_>>>()?;
+
+        for partition in &partitions {
+            partition.insert("a", "a")?;
+        }
+
+        partitions[0].rotate_memtable()?;
+        assert_eq!(PARTITION_COUNT, read_manifest(folder.path(), 0)?.len());
+
+        // NOTE: Partitions get IDs in creation order, so pN has ID N
+        //
+        // p1 still has data in its memtable, but not in the new journal
+        partitions[2].insert("b", "b")?;
+        partitions[2].rotate_memtable()?;
+        assert_eq!(vec![2], read_manifest(folder.path(), 1)?);
+
+        partitions[3].insert("c", "c")?;
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let p3 = keyspace.open_partition("p3", PartitionCreateOptions::default())?;
+        let p4 = keyspace.open_partition("p4", PartitionCreateOptions::default())?;
+
+        assert_eq!(2, p3.len()?);
+
+        // NOTE: Recovered data of p3 is still in the active journal, so it needs to be listed
+        p4.insert("d", "d")?;
+        p4.rotate_memtable()?;
+        assert_eq!(vec![3, 4], read_manifest(folder.path(), 2)?);
+    }
+
+    Ok(())
+}
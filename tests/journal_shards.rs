@@ -0,0 +1,127 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const ITEM_COUNT: usize = 1_000;
+const WRITER_COUNT: usize = 8;
+
+fn count_shard_files(path: &std::path::Path) -> fjall::Result<usize> {
+    let journals_folder = path.join("journals");
+    let mut count = 0;
+
+    for journal in std::fs::read_dir(journals_folder)? {
+        for dirent in std::fs::read_dir(journal?.path())? {
+            if dirent?
+                .file_name()
+                .to_str()
+                .expect("should be utf-8")
+                .parse::<u8>()
+                .is_ok()
+            {
+                count += 1;
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+#[test]
+fn journal_shard_count_change() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder)
+            .journal_shards(WRITER_COUNT as u8)
+            .journal_preallocation(4_096)
+            .open()?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        std::thread::scope(|scope| {
+            for writer in 0..WRITER_COUNT {
+                let partition = partition.clone();
+
+                scope.spawn(move || {
+                    for x in 0..ITEM_COUNT {
+                        let key = ((writer * ITEM_COUNT + x) as u64).to_be_bytes();
+                        partition.insert(key, "abc").expect("should insert");
+                    }
+                });
+            }
+        });
+
+        assert_eq!(partition.len()?, WRITER_COUNT * ITEM_COUNT);
+        assert_eq!(count_shard_files(folder.path())?, WRITER_COUNT);
+    }
+
+    // NOTE: Fewer shards, the superfluous shard files still need to be recovered
+    {
+        let keyspace = Config::new(&folder)
+            .journal_shards(1)
+            .journal_preallocation(0)
+            .open()?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        assert_eq!(partition.len()?, WRITER_COUNT * ITEM_COUNT);
+
+        partition.insert("a", "abc")?;
+    }
+
+    // NOTE: More shards
+    {
+        let keyspace = Config::new(&folder).journal_shards(16).open()?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        assert_eq!(partition.len()?, WRITER_COUNT * ITEM_COUNT + 1);
+        assert_eq!(count_shard_files(folder.path())?, 16);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn journal_preallocation_disk_space() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder)
+        .journal_shards(2)
+        .journal_preallocation(1_024 * 1_024)
+        .open()?;
+
+    // NOTE: The preallocated active journal counts towards the disk space
+    assert!(keyspace.disk_space() >= 2 * 1_024 * 1_024);
+
+    Ok(())
+}
+
+#[test]
+fn journal_preallocation_write_halt() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    // NOTE: The preallocated journal (32 MiB) is larger than the journaling limit,
+    // so only the data of sealed journals may count towards it
+    let keyspace = Config::new(&folder)
+        .max_journaling_size(24 * 1_024 * 1_024)
+        .flush_workers(0)
+        .open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    partition.set_max_memtable_size(1_024);
+
+    let writer = {
+        let partition = partition.clone();
+
+        std::thread::spawn(move || {
+            for x in 0..ITEM_COUNT as u64 {
+                partition.insert(x.to_be_bytes(), "abc")?;
+            }
+            Ok::<_, fjall::Error>(())
+        })
+    };
+
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    assert!(writer.is_finished(), "writes should not be halted");
+    writer.join().expect("should join")?;
+
+    assert!(keyspace.journal_count() > 1);
+
+    Ok(())
+}
@@ -1,11 +1,31 @@
 use fjall::Config;
+use std::path::Path;
 use test_log::test;
 
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+
+    for dirent in std::fs::read_dir(from)? {
+        let dirent = dirent?;
+        let target = to.join(dirent.file_name());
+
+        if dirent.file_type()?.is_dir() {
+            copy_dir(&dirent.path(), &target)?;
+        } else {
+            std::fs::copy(dirent.path(), target)?;
+        }
+    }
+
+    Ok(())
+}
+
 #[test]
 fn keyspace_load_v1() -> fjall::Result<()> {
-    let folder = "test_fixture/v1_keyspace";
+    // NOTE: Recovering writes to the keyspace folder, so work on a copy of the fixture
+    let folder = tempfile::tempdir()?;
+    copy_dir(Path::new("test_fixture/v1_keyspace"), folder.path())?;
 
-    let keyspace = Config::new(folder).open()?;
+    let keyspace = Config::new(&folder).open()?;
 
     let a = keyspace.open_partition("a", Default::default())?;
     let b = keyspace.open_partition("b", Default::default())?;
@@ -13,13 +33,13 @@ fn keyspace_load_v1() -> fjall::Result<()> {
 
     assert_eq!(3, keyspace.partition_count());
 
-    assert_eq!(1, a.tree.first_level_segment_count());
+    assert_eq!(1, a.tree().first_level_segment_count());
     assert_eq!(8, a.len()?);
 
-    assert_eq!(1, b.tree.first_level_segment_count());
+    assert_eq!(1, b.tree().first_level_segment_count());
     assert_eq!(4, b.len()?);
 
-    assert_eq!(0, c.tree.first_level_segment_count());
+    assert_eq!(0, c.tree().first_level_segment_count());
     assert_eq!(4, c.len()?);
 
     // TODO: call Keyspace::verify
@@ -28,11 +48,48 @@ fn keyspace_load_v1() -> fjall::Result<()> {
     Ok(())
 }
 
+#[test]
+fn keyspace_load_v1_rename_partition() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    copy_dir(Path::new("test_fixture/v1_keyspace"), folder.path())?;
+
+    // NOTE: The data of "c" is only in the journal, which references partitions by name
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let c = keyspace.open_partition("c", Default::default())?;
+        keyspace.rename_partition(&c, "d")?;
+
+        let a = keyspace.open_partition("a", Default::default())?;
+        keyspace.rename_partition(&a, "c")?;
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        assert_eq!(3, keyspace.partition_count());
+        assert!(!keyspace.partition_exists("a"));
+
+        let c = keyspace.open_partition("c", Default::default())?;
+        let d = keyspace.open_partition("d", Default::default())?;
+
+        assert_eq!(8, c.len()?);
+        assert_eq!(4, d.len()?);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn keyspace_load_v1_corrupt_journal() -> fjall::Result<()> {
-    let folder = "test_fixture/v1_keyspace_corrupt_journal";
+    // NOTE: Recovering writes to the keyspace folder, so work on a copy of the fixture
+    let folder = tempfile::tempdir()?;
+    copy_dir(
+        Path::new("test_fixture/v1_keyspace_corrupt_journal"),
+        folder.path(),
+    )?;
 
-    let result = Config::new(folder).open();
+    let result = Config::new(&folder).open();
 
     matches!(
         result,
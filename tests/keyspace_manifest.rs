@@ -0,0 +1,103 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const ITEM_COUNT: usize = 10;
+
+#[test]
+fn keyspace_manifest_unicode_partition_name() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let names = ["users/eu", "Größe", "日本語 partition", "a:b"];
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        for name in names {
+            let partition = keyspace.open_partition(name, PartitionCreateOptions::default())?;
+
+            for x in 0..ITEM_COUNT as u64 {
+                partition.insert(x.to_be_bytes(), name)?;
+            }
+        }
+
+        assert_eq!(names.len(), keyspace.partition_count());
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        assert_eq!(names.len(), keyspace.partition_count());
+
+        for name in names {
+            assert!(keyspace.partition_exists(name));
+
+            let partition = keyspace.open_partition(name, PartitionCreateOptions::default())?;
+            assert_eq!(ITEM_COUNT, partition.len()?);
+            assert_eq!(
+                Some(name.as_bytes().into()),
+                partition.get(0u64.to_be_bytes())?
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_manifest_long_partition_name() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let name = "ä".repeat(127);
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let partition = keyspace.open_partition(&name, PartitionCreateOptions::default())?;
+        partition.insert("a", "a")?;
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        assert!(keyspace.partition_exists(&name));
+
+        let partition = keyspace.open_partition(&name, PartitionCreateOptions::default())?;
+        assert_eq!(1, partition.len()?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_manifest_deleted_partition_stays_deleted() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+        let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+
+        for x in 0..ITEM_COUNT as u64 {
+            a.insert(x.to_be_bytes(), "a")?;
+            b.insert(x.to_be_bytes(), "b")?;
+        }
+
+        keyspace.delete_partition(a)?;
+        assert_eq!(1, keyspace.partition_count());
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        assert!(!keyspace.partition_exists("a"));
+        assert_eq!(1, keyspace.partition_count());
+
+        // NOTE: A new partition with the same name does not see the old data
+        let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+        assert!(a.is_empty()?);
+
+        let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+        assert_eq!(ITEM_COUNT, b.len()?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const ITEM_COUNT: usize = 100;
+
+#[test]
+fn partition_multi_get() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    partition.insert("a", "abc")?;
+    partition.insert("c", "def")?;
+
+    assert_eq!(
+        vec![
+            Some("def".as_bytes().into()),
+            None,
+            Some("abc".as_bytes().into()),
+            Some("def".as_bytes().into()),
+        ],
+        partition.multi_get(["c", "b", "a", "c"])?
+    );
+
+    assert!(partition.multi_get(Vec::<&[u8]>::new())?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn partition_multi_get_flushed() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    for x in 0..ITEM_COUNT as u64 {
+        partition.insert(x.to_be_bytes(), x.to_string())?;
+    }
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+
+    // NOTE: Shadow some of the flushed items
+    partition.insert(0u64.to_be_bytes(), "updated")?;
+    partition.remove(1u64.to_be_bytes())?;
+
+    let keys = (0..ITEM_COUNT as u64).rev().map(u64::to_be_bytes);
+    let items = partition.multi_get(keys.clone())?;
+    assert_eq!(ITEM_COUNT, items.len());
+
+    for (key, item) in keys.zip(items) {
+        assert_eq!(partition.get(key)?, item);
+    }
+
+    assert_eq!(
+        vec![Some("updated".as_bytes().into()), None, None],
+        partition.multi_get([
+            0u64.to_be_bytes(),
+            1u64.to_be_bytes(),
+            u64::MAX.to_be_bytes(),
+        ])?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn partition_multi_get_at() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition(
+        "default",
+        PartitionCreateOptions::default().block_size(1_024),
+    )?;
+
+    partition.insert("a", "old")?;
+    partition.insert("b", "old")?;
+    let instant = keyspace.instant();
+
+    // NOTE: Spread the versions of "a" across multiple data blocks
+    for _ in 0..ITEM_COUNT {
+        partition.insert("a", "new".repeat(50))?;
+    }
+    partition.remove("b")?;
+
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+    assert_eq!(1, partition.segment_count());
+
+    assert_eq!(
+        vec![Some("old".as_bytes().into()), Some("old".as_bytes().into())],
+        partition.multi_get_at(instant, ["b", "a"])?
+    );
+
+    assert_eq!(
+        vec![None, Some("new".repeat(50).as_bytes().into())],
+        partition.multi_get(["b", "a"])?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_multi_get() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+    let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+
+    a.insert("1", "a1")?;
+    a.insert("2", "a2")?;
+    b.insert("1", "b1")?;
+
+    let items = keyspace.multi_get(&[(&b, "1"), (&a, "2"), (&b, "2"), (&a, "1")])?;
+
+    assert_eq!(
+        vec![
+            Some("b1".as_bytes().into()),
+            Some("a2".as_bytes().into()),
+            None,
+            Some("a1".as_bytes().into()),
+        ],
+        items
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "single_writer_tx")]
+fn tx_multi_get() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open_transactional()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    partition.insert("a", "old")?;
+
+    let tx = keyspace.read_tx();
+
+    partition.insert("a", "new")?;
+    partition.insert("b", "new")?;
+
+    // NOTE: Repeatable read
+    assert_eq!(
+        vec![None, Some("old".as_bytes().into())],
+        tx.multi_get(&partition, ["b", "a"])?
+    );
+
+    assert_eq!(
+        vec![Some("new".as_bytes().into()), Some("new".as_bytes().into())],
+        keyspace.read_tx().multi_get(&partition, ["b", "a"])?
+    );
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const ITEM_COUNT: u64 = 10_000;
+
+#[test]
+fn partition_approximate_range() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition(
+        "default",
+        PartitionCreateOptions::default().block_size(1_024),
+    )?;
+
+    for x in 0..ITEM_COUNT {
+        partition.insert(x.to_be_bytes(), "a".repeat(100))?;
+    }
+
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+
+    assert_eq!(ITEM_COUNT, partition.approximate_count::<&[u8], _>(..)?);
+    assert_eq!(0, partition.approximate_count(ITEM_COUNT.to_be_bytes()..)?);
+
+    // NOTE: Estimates are block-granular
+    let count = partition.approximate_count(1_000u64.to_be_bytes()..2_000u64.to_be_bytes())?;
+    assert!((1_000..1_100).contains(&count), "count is {count}");
+
+    let count = partition.approximate_count(..=500u64.to_be_bytes())?;
+    assert!((501..600).contains(&count), "count is {count}");
+
+    let total_size = partition.approximate_size::<&[u8], _>(..)?;
+    let size = partition.approximate_size(..5_000u64.to_be_bytes())?;
+    assert!(size >= total_size / 2, "size is {size} of {total_size}");
+    assert!(
+        size < total_size / 2 + total_size / 50,
+        "size is {size} of {total_size}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn partition_approximate_range_memtables() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    // NOTE: Unflushed data is counted exactly
+    for x in 0..100u64 {
+        partition.insert(x.to_be_bytes(), "a".repeat(100))?;
+    }
+    partition.rotate_memtable()?;
+    partition.insert(u64::MAX.to_be_bytes(), "a")?;
+
+    assert_eq!(101, partition.approximate_count::<&[u8], _>(..)?);
+    assert_eq!(51, partition.approximate_count(50u64.to_be_bytes()..)?);
+    assert!(partition.approximate_size::<&[u8], _>(..)? > 100 * 100);
+
+    Ok(())
+}
+
+#[test]
+fn partition_approximate_range_empty() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    assert_eq!(0, partition.approximate_count::<&[u8], _>(..)?);
+    assert_eq!(0, partition.approximate_size::<&[u8], _>(..)?);
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const ITEM_COUNT: usize = 100;
+
+#[test]
+fn partition_clear() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).flush_workers(0).open()?;
+
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        let other_handle = partition.clone();
+
+        for x in 0..ITEM_COUNT as u64 {
+            partition.insert(x.to_be_bytes(), "old")?;
+        }
+
+        // NOTE: Have data in segments, sealed journals and the active memtable
+        partition.rotate_memtable()?;
+        keyspace.force_flush();
+
+        for x in 0..ITEM_COUNT as u64 {
+            partition.insert(x.to_be_bytes(), "old")?;
+        }
+        partition.rotate_memtable()?;
+
+        partition.insert(u64::MAX.to_be_bytes(), "old")?;
+
+        let old_path = partition.path();
+
+        partition.clear()?;
+
+        assert!(other_handle.is_empty()?);
+        assert!(!old_path.try_exists()?);
+        assert_eq!(1, keyspace.partition_count());
+
+        // NOTE: Existing handles keep working
+        other_handle.insert(0u64.to_be_bytes(), "new")?;
+        assert_eq!(1, partition.len()?);
+    }
+
+    for _ in 0..3 {
+        let keyspace = Config::new(&folder).flush_workers(0).open()?;
+
+        assert_eq!(1, keyspace.partition_count());
+
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        assert_eq!(1, partition.len()?);
+        assert_eq!(
+            Some("new".as_bytes().into()),
+            partition.get(0u64.to_be_bytes())?
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn partition_clear_open_snapshot() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    for x in 0..ITEM_COUNT as u64 {
+        partition.insert(x.to_be_bytes(), "old")?;
+    }
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+    assert_eq!(1, partition.segment_count());
+
+    let snapshot = partition.snapshot();
+    let mut iter = partition.iter();
+    let old_path = partition.path();
+
+    partition.clear()?;
+    assert!(partition.is_empty()?);
+
+    // NOTE: The old data is kept until it is not used anymore
+    assert!(old_path.try_exists()?);
+    assert_eq!(ITEM_COUNT, snapshot.len()?);
+    assert!(iter.next().is_some());
+
+    drop(iter);
+    drop(snapshot);
+
+    partition.clear()?;
+    assert!(!old_path.try_exists()?);
+
+    Ok(())
+}
+
+#[test]
+fn partition_clear_keeps_other_partitions() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).flush_workers(0).open()?;
+
+        let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+        let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+
+        for x in 0..ITEM_COUNT as u64 {
+            a.insert(x.to_be_bytes(), "a")?;
+            b.insert(x.to_be_bytes(), "b")?;
+        }
+
+        a.clear()?;
+
+        assert!(a.is_empty()?);
+        assert_eq!(ITEM_COUNT, b.len()?);
+    }
+
+    {
+        let keyspace = Config::new(&folder).flush_workers(0).open()?;
+
+        let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+        let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+
+        assert!(a.is_empty()?);
+        assert_eq!(ITEM_COUNT, b.len()?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn partition_clear_journal_eviction() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    for x in 0..ITEM_COUNT as u64 {
+        partition.insert(x.to_be_bytes(), "old")?;
+    }
+    partition.rotate_memtable()?;
+    assert_eq!(2, keyspace.journal_count());
+
+    partition.clear()?;
+
+    // NOTE: The sealed journal only contains cleared data, so it can be evicted
+    partition.insert("a", "new")?;
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+    assert_eq!(1, keyspace.journal_count());
+
+    Ok(())
+}
+
+#[test]
+fn partition_clear_deleted() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    keyspace.delete_partition(partition.clone())?;
+
+    assert!(matches!(
+        partition.clear(),
+        Err(fjall::Error::PartitionDeleted)
+    ));
+
+    Ok(())
+}
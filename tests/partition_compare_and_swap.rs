@@ -0,0 +1,226 @@
+use fjall::{Config, PartitionCreateOptions};
+use std::sync::{Arc, Barrier};
+use test_log::test;
+
+#[test]
+fn partition_compare_and_swap() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    partition.compare_and_swap("a", None::<&str>, Some("abc"))?;
+    assert_eq!(Some((*b"abc").into()), partition.get("a")?);
+
+    assert!(matches!(
+        partition.compare_and_swap("a", None::<&str>, Some("def")),
+        Err(fjall::Error::PreconditionFailed(Some(current))) if &*current == b"abc"
+    ));
+
+    let seqno = partition.compare_and_swap("a", Some("abc"), Some("def"))?;
+    assert_eq!(
+        Some(((*b"def").into(), seqno)),
+        partition.get_with_seqno("a")?
+    );
+
+    // NOTE: Removing the item
+    partition.compare_and_swap("a", Some("def"), None::<&str>)?;
+    assert_eq!(None, partition.get("a")?);
+
+    assert!(matches!(
+        partition.compare_and_swap("a", Some("def"), Some("ghi")),
+        Err(fjall::Error::PreconditionFailed(None))
+    ));
+    partition.compare_and_swap("a", None::<&str>, Some("ghi"))?;
+    assert_eq!(Some((*b"ghi").into()), partition.get("a")?);
+
+    Ok(())
+}
+
+#[test]
+fn partition_compare_and_swap_flushed() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    // NOTE: Have the current values in a segment and a sealed memtable
+    partition.insert("a", "abc")?;
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+
+    partition.insert("b", "abc")?;
+    partition.rotate_memtable()?;
+
+    assert!(matches!(
+        partition.compare_and_swap("a", None::<&str>, Some("def")),
+        Err(fjall::Error::PreconditionFailed(Some(_)))
+    ));
+    assert!(matches!(
+        partition.compare_and_swap("b", None::<&str>, Some("def")),
+        Err(fjall::Error::PreconditionFailed(Some(_)))
+    ));
+
+    partition.compare_and_swap("a", Some("abc"), Some("def"))?;
+    partition.compare_and_swap("b", Some("abc"), Some("def"))?;
+    assert_eq!(Some((*b"def").into()), partition.get("a")?);
+    assert_eq!(Some((*b"def").into()), partition.get("b")?);
+
+    Ok(())
+}
+
+#[test]
+fn partition_compare_and_swap_concurrent() -> fjall::Result<()> {
+    const THREADS: u64 = 4;
+    const INCREMENTS: u64 = 250;
+
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition =
+        Arc::new(keyspace.open_partition("default", PartitionCreateOptions::default())?);
+
+    partition.insert("counter", 0u64.to_be_bytes())?;
+
+    let handles = (0..THREADS)
+        .map(|_| {
+            let partition = partition.clone();
+
+            std::thread::spawn(move || -> fjall::Result<()> {
+                for _ in 0..INCREMENTS {
+                    loop {
+                        let current = partition.get("counter")?.expect("should exist");
+                        let next =
+                            u64::from_be_bytes((*current).try_into().expect("should be u64")) + 1;
+
+                        match partition.compare_and_swap(
+                            "counter",
+                            Some(current),
+                            Some(next.to_be_bytes()),
+                        ) {
+                            Ok(_) => break,
+                            Err(fjall::Error::PreconditionFailed(_)) => continue,
+                            Err(e) => return Err(e),
+                        }
+                    }
+                }
+                Ok(())
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        handle.join().expect("should join")?;
+    }
+
+    let counter = partition.get("counter")?.expect("should exist");
+    assert_eq!(
+        THREADS * INCREMENTS,
+        u64::from_be_bytes((*counter).try_into().expect("should be u64"))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn partition_compare_and_swap_concurrent_insert() -> fjall::Result<()> {
+    const ITEM_COUNT: u64 = 5_000;
+
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition =
+        Arc::new(keyspace.open_partition("default", PartitionCreateOptions::default())?);
+
+    // NOTE: Both threads write the same key at the same time, so they actually race
+    let barrier = Arc::new(Barrier::new(2));
+
+    let inserter = {
+        let partition = partition.clone();
+        let barrier = barrier.clone();
+
+        std::thread::spawn(move || -> fjall::Result<()> {
+            for x in 0..ITEM_COUNT {
+                barrier.wait();
+                partition.insert(x.to_be_bytes(), "plain")?;
+            }
+            Ok(())
+        })
+    };
+
+    let mut swaps = vec![];
+
+    for x in 0..ITEM_COUNT {
+        barrier.wait();
+
+        match partition.compare_and_swap(x.to_be_bytes(), None::<&str>, Some("swapped")) {
+            Ok(seqno) => swaps.push((x, seqno)),
+            Err(fjall::Error::PreconditionFailed(current)) => {
+                assert_eq!(Some((*b"plain").into()), current);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    inserter.join().expect("should join")?;
+
+    // NOTE: Either the swap saw the plain write and failed,
+    // or the plain write happened after the swap and overwrote it
+    for x in 0..ITEM_COUNT {
+        assert_eq!(Some((*b"plain").into()), partition.get(x.to_be_bytes())?);
+    }
+
+    for (x, swap_seqno) in swaps {
+        let (_, seqno) = partition
+            .get_with_seqno(x.to_be_bytes())?
+            .expect("should exist");
+        assert!(seqno > swap_seqno);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn batch_preconditions() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let accounts = keyspace.open_partition("accounts", PartitionCreateOptions::default())?;
+    let log = keyspace.open_partition("log", PartitionCreateOptions::default())?;
+
+    accounts.insert("alice", "100")?;
+    let (_, seqno) = accounts.get_with_seqno("alice")?.expect("should exist");
+
+    let mut batch = keyspace.batch();
+    batch.require_value(&accounts, "alice", Some("100"));
+    batch.require_value(&accounts, "bob", None::<&str>);
+    batch.require_seqno(&accounts, "alice", Some(seqno));
+    batch.insert(&accounts, "alice", "50");
+    batch.insert(&accounts, "bob", "50");
+    batch.insert(&log, "1", "alice -> bob: 50");
+    batch.commit()?;
+
+    assert_eq!(Some((*b"50").into()), accounts.get("bob")?);
+
+    // NOTE: Stale seqno, nothing is written
+    let mut batch = keyspace.batch();
+    batch.require_seqno(&accounts, "alice", Some(seqno));
+    batch.insert(&accounts, "alice", "0");
+    batch.insert(&log, "2", "alice -> bob: 50");
+
+    assert!(matches!(
+        batch.commit(),
+        Err(fjall::Error::PreconditionFailed(Some(current))) if &*current == b"50"
+    ));
+    assert_eq!(Some((*b"50").into()), accounts.get("alice")?);
+    assert_eq!(1, log.len()?);
+
+    // NOTE: A precondition on a partition that is not written to
+    let mut batch = keyspace.batch();
+    batch.require_seqno(&accounts, "carol", None);
+    batch.insert(&log, "2", "audit");
+    batch.commit()?;
+    assert_eq!(2, log.len()?);
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use fjall::{Config, DumpError, PartitionCreateOptions};
+use test_log::test;
+
+const ITEM_COUNT: usize = 100;
+
+#[test]
+fn partition_dump_round_trip() -> fjall::Result<()> {
+    let dump = {
+        let folder = tempfile::tempdir()?;
+        let keyspace = Config::new(&folder).open()?;
+
+        let partitions = &[
+            keyspace.open_partition("default1", PartitionCreateOptions::default())?,
+            keyspace.open_partition(
+                "default2",
+                PartitionCreateOptions::default()
+                    .block_size(8_192)
+                    .level_count(5),
+            )?,
+        ];
+
+        for tree in partitions {
+            for x in 0..ITEM_COUNT as u64 {
+                let key = x.to_be_bytes();
+                let value = nanoid::nanoid!();
+                tree.insert(key, value.as_bytes())?;
+            }
+        }
+
+        let mut dump = vec![];
+        assert_eq!(
+            ITEM_COUNT as u64 * 2,
+            keyspace.export(partitions, &mut dump)?
+        );
+
+        dump
+    };
+
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        assert_eq!(ITEM_COUNT as u64 * 2, keyspace.import(&*dump)?);
+        assert_eq!(2, keyspace.partition_count());
+
+        let partition = keyspace.open_partition("default2", PartitionCreateOptions::default())?;
+        assert_eq!(8_192, partition.tree().config.inner.block_size);
+        assert_eq!(5, partition.tree().config.inner.level_count);
+        assert_eq!(ITEM_COUNT, partition.len()?);
+
+        // NOTE: Imported partitions are bulk loaded into segments
+        assert_eq!(1, partition.segment_count());
+
+        partition.insert("z", "abc")?;
+        assert_eq!(ITEM_COUNT + 1, partition.len()?);
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let partition = keyspace.open_partition("default1", PartitionCreateOptions::default())?;
+        assert_eq!(ITEM_COUNT, partition.len()?);
+        assert!(!partition.contains_key("z")?);
+
+        let partition = keyspace.open_partition("default2", PartitionCreateOptions::default())?;
+        assert_eq!(8_192, partition.tree().config.inner.block_size);
+        assert_eq!(ITEM_COUNT + 1, partition.len()?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn partition_dump_import_existing() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let keyspace = Config::new(&folder).open()?;
+
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    for x in 0..ITEM_COUNT as u64 {
+        partition.insert(x.to_be_bytes(), "old")?;
+    }
+
+    let mut dump = vec![];
+    partition.export(&mut dump)?;
+
+    let folder = tempfile::tempdir()?;
+    let keyspace = Config::new(&folder).open()?;
+
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    partition.insert("a", "abc")?;
+
+    assert_eq!(ITEM_COUNT as u64, keyspace.import(&*dump)?);
+    assert_eq!(ITEM_COUNT + 1, partition.len()?);
+    assert_eq!(0, partition.segment_count());
+
+    Ok(())
+}
+
+#[test]
+fn partition_dump_corrupted() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let keyspace = Config::new(&folder).open()?;
+
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    for x in 0..ITEM_COUNT as u64 {
+        partition.insert(x.to_be_bytes(), "abc")?;
+    }
+
+    let mut dump = vec![];
+    partition.export(&mut dump)?;
+
+    let folder = tempfile::tempdir()?;
+    let keyspace = Config::new(&folder).open()?;
+
+    // Flip a byte inside the last value
+    let idx = dump.len() - 20;
+    *dump.get_mut(idx).expect("should exist") ^= 0xFF;
+
+    assert!(matches!(
+        keyspace.import(&*dump),
+        Err(fjall::Error::Dump(DumpError::CrcCheck))
+    ));
+    assert!(!keyspace.partition_exists("default"));
+    *dump.get_mut(idx).expect("should exist") ^= 0xFF;
+
+    // Corrupt the length of the last value, which should not be allocated upfront
+    let len_idx = dump.len() - 25;
+    let original_len = dump[len_idx..len_idx + 4].to_vec();
+    dump[len_idx..len_idx + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+
+    assert!(matches!(
+        keyspace.import(&*dump),
+        Err(fjall::Error::Dump(DumpError::Truncated))
+    ));
+    assert!(!keyspace.partition_exists("default"));
+    dump[len_idx..len_idx + 4].copy_from_slice(&original_len);
+
+    // Dump truncated inside the last item
+    dump.truncate(idx);
+
+    assert!(matches!(
+        keyspace.import(&*dump),
+        Err(fjall::Error::Dump(DumpError::Truncated))
+    ));
+    assert!(!keyspace.partition_exists("default"));
+
+    Ok(())
+}
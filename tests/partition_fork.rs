@@ -0,0 +1,127 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const ITEM_COUNT: usize = 100;
+
+#[test]
+fn partition_fork() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).flush_workers(0).open()?;
+        let source = keyspace.open_partition("source", PartitionCreateOptions::default())?;
+
+        // NOTE: Have data in segments, sealed memtables and the active memtable
+        for x in 0..ITEM_COUNT as u64 {
+            source.insert(x.to_be_bytes(), "segment")?;
+        }
+        source.rotate_memtable()?;
+        keyspace.force_flush();
+        assert_eq!(1, source.segment_count());
+
+        for x in ITEM_COUNT as u64..(ITEM_COUNT * 2) as u64 {
+            source.insert(x.to_be_bytes(), "sealed")?;
+        }
+        source.rotate_memtable()?;
+
+        for x in (ITEM_COUNT * 2) as u64..(ITEM_COUNT * 3) as u64 {
+            source.insert(x.to_be_bytes(), "active")?;
+        }
+
+        let fork = keyspace.fork_partition(&source, "fork")?;
+        assert_eq!(ITEM_COUNT * 3, fork.len()?);
+        assert_eq!(2, keyspace.partition_count());
+
+        // NOTE: Both partitions diverge independently
+        source.insert("source", "abc")?;
+        fork.insert("fork", "abc")?;
+        fork.remove(0u64.to_be_bytes())?;
+
+        assert!(!source.contains_key("fork")?);
+        assert!(!fork.contains_key("source")?);
+        assert!(source.contains_key(0u64.to_be_bytes())?);
+
+        assert_eq!(ITEM_COUNT * 3 + 1, source.len()?);
+        assert_eq!(ITEM_COUNT * 3, fork.len()?);
+    }
+
+    for _ in 0..3 {
+        let keyspace = Config::new(&folder).flush_workers(0).open()?;
+        assert_eq!(2, keyspace.partition_count());
+
+        let source = keyspace.open_partition("source", PartitionCreateOptions::default())?;
+        let fork = keyspace.open_partition("fork", PartitionCreateOptions::default())?;
+
+        assert_eq!(ITEM_COUNT * 3 + 1, source.len()?);
+        assert_eq!(ITEM_COUNT * 3, fork.len()?);
+
+        assert!(fork.contains_key("fork")?);
+        assert!(!fork.contains_key(0u64.to_be_bytes())?);
+        assert!(source.contains_key(0u64.to_be_bytes())?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn partition_fork_compaction() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let source = keyspace.open_partition("source", PartitionCreateOptions::default())?;
+
+    for batch in 0..3 {
+        for x in 0..ITEM_COUNT as u64 {
+            source.insert(x.to_be_bytes(), format!("v{batch}"))?;
+        }
+        source.rotate_memtable()?;
+        keyspace.force_flush();
+    }
+
+    let fork = keyspace.fork_partition(&source, "fork")?;
+    assert!(fork.segment_count() > 0);
+
+    // NOTE: Compacting the source removes its segment files,
+    // which must not affect the fork
+    source.tree().major_compact(u64::MAX)?;
+
+    assert_eq!(ITEM_COUNT, fork.len()?);
+    assert_eq!(Some("v2".as_bytes().into()), fork.get(0u64.to_be_bytes())?);
+
+    drop(fork);
+    drop(source);
+    drop(keyspace);
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let fork = keyspace.open_partition("fork", PartitionCreateOptions::default())?;
+    assert_eq!(ITEM_COUNT, fork.len()?);
+    assert_eq!(Some("v2".as_bytes().into()), fork.get(0u64.to_be_bytes())?);
+
+    Ok(())
+}
+
+#[test]
+fn partition_fork_conflict() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+    let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+    a.insert("a", "a")?;
+
+    assert!(matches!(
+        keyspace.fork_partition(&a, "b"),
+        Err(fjall::Error::PartitionExists)
+    ));
+    assert!(b.is_empty()?);
+
+    keyspace.delete_partition(a.clone())?;
+
+    assert!(matches!(
+        keyspace.fork_partition(&a, "c"),
+        Err(fjall::Error::PartitionDeleted)
+    ));
+    assert!(!keyspace.partition_exists("c"));
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const PARTITION_COUNT: usize = 50;
+
+#[test]
+fn partition_lazy_recover() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let instant = {
+        let keyspace = Config::new(&folder).open()?;
+
+        for idx in 0..PARTITION_COUNT {
+            let partition =
+                keyspace.open_partition(&format!("p{idx}"), PartitionCreateOptions::default())?;
+            partition.insert("a", idx.to_string())?;
+            keyspace.close_partition(partition)?;
+        }
+
+        assert_eq!(PARTITION_COUNT, keyspace.partition_count());
+
+        keyspace.instant()
+    };
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        // NOTE: Unloaded partitions still count as existing
+        assert_eq!(PARTITION_COUNT, keyspace.partition_count());
+        assert_eq!(PARTITION_COUNT, keyspace.list_partitions().len());
+        assert!(keyspace.partition_exists("p0"));
+        assert!(!keyspace.partition_exists("p9999"));
+
+        // NOTE: The seqno needs to be recovered, even if the partitions are not loaded
+        assert!(keyspace.instant() >= instant);
+
+        let partition = keyspace.open_partition("p7", PartitionCreateOptions::default())?;
+        let snapshot = partition.snapshot_at(keyspace.instant());
+        assert_eq!(Some("7".as_bytes().into()), snapshot.get("a")?);
+
+        keyspace.delete_partition(partition)?;
+        assert_eq!(PARTITION_COUNT - 1, keyspace.partition_count());
+        assert!(!keyspace.partition_exists("p7"));
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        assert_eq!(PARTITION_COUNT - 1, keyspace.partition_count());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn partition_lazy_closed_handle_reused() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        partition.insert("a", "abc")?;
+
+        keyspace.close_partition(partition.clone())?;
+        assert!(keyspace.partition_exists("default"));
+
+        // NOTE: The handle is still alive, so the partition is reused, and still writable
+        partition.insert("b", "abc")?;
+
+        let reopened = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        assert_eq!(2, reopened.len()?);
+
+        reopened.insert("c", "abc")?;
+        assert_eq!(3, partition.len()?);
+
+        // NOTE: Batches resolve closed partitions as well
+        keyspace.close_partition(reopened)?;
+
+        let mut batch = keyspace.batch();
+        batch.insert(&partition, "d", "abc");
+        batch.commit()?;
+        assert_eq!(4, partition.len()?);
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        assert_eq!(4, partition.len()?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn partition_lazy_without_seqno_marker() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let instant = {
+        let keyspace = Config::new(&folder).open()?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        partition.insert("a", "abc")?;
+        keyspace.close_partition(partition)?;
+        keyspace.instant()
+    };
+
+    // NOTE: Keyspaces created before lazy loading have no seqno marker,
+    // so all partitions are loaded once, and the marker is written
+    std::fs::remove_file(folder.path().join("seqno"))?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        assert_eq!(instant, keyspace.instant());
+    }
+
+    assert!(folder.path().join("seqno").try_exists()?);
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        assert_eq!(instant, keyspace.instant());
+
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+        assert_eq!(1, partition.len()?);
+    }
+
+    Ok(())
+}
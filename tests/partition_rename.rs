@@ -0,0 +1,184 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const ITEM_COUNT: usize = 100;
+
+#[test]
+fn partition_rename() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let partition = keyspace.open_partition("users_v2", PartitionCreateOptions::default())?;
+        let other_handle = partition.clone();
+
+        for x in 0..ITEM_COUNT as u64 {
+            partition.insert(x.to_be_bytes(), "abc")?;
+        }
+
+        keyspace.rename_partition(&partition, "users")?;
+
+        assert_eq!("users", &*other_handle.name());
+        assert!(keyspace.partition_exists("users"));
+        assert!(!keyspace.partition_exists("users_v2"));
+        assert_eq!(1, keyspace.partition_count());
+
+        // NOTE: Existing handles keep working
+        for x in ITEM_COUNT as u64..(ITEM_COUNT * 2) as u64 {
+            other_handle.insert(x.to_be_bytes(), "abc")?;
+        }
+
+        let mut batch = keyspace.batch();
+        batch.insert(&other_handle, u64::MAX.to_be_bytes(), "abc");
+        batch.commit()?;
+
+        assert_eq!(ITEM_COUNT * 2 + 1, partition.len()?);
+    }
+
+    for _ in 0..3 {
+        let keyspace = Config::new(&folder).open()?;
+
+        assert!(!keyspace.partition_exists("users_v2"));
+        assert_eq!(1, keyspace.partition_count());
+
+        let partition = keyspace.open_partition("users", PartitionCreateOptions::default())?;
+        assert_eq!(ITEM_COUNT * 2 + 1, partition.len()?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn partition_rename_sealed_journal() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+        let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+
+        for x in 0..ITEM_COUNT as u64 {
+            a.insert(x.to_be_bytes(), "a")?;
+            b.insert(x.to_be_bytes(), "b")?;
+        }
+
+        // NOTE: Seal the journal, so recovery needs to read its partition list
+        a.rotate_memtable()?;
+        assert_eq!(2, keyspace.journal_count());
+
+        // NOTE: Swap names
+        keyspace.rename_partition(&a, "tmp")?;
+        keyspace.rename_partition(&b, "a")?;
+        keyspace.rename_partition(&a, "b")?;
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+        let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+
+        assert_eq!(2, keyspace.partition_count());
+        assert_eq!(ITEM_COUNT, a.len()?);
+        assert_eq!(ITEM_COUNT, b.len()?);
+
+        assert_eq!(Some("b".as_bytes().into()), a.get(0u64.to_be_bytes())?);
+        assert_eq!(Some("a".as_bytes().into()), b.get(0u64.to_be_bytes())?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn partition_rename_closed() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+
+    let partition = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+    partition.insert("a", "a")?;
+
+    keyspace.close_partition(partition.clone())?;
+    keyspace.rename_partition(&partition, "b")?;
+
+    assert!(!keyspace.partition_exists("a"));
+    assert!(keyspace.partition_exists("b"));
+
+    drop(partition);
+
+    let partition = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+    assert!(partition.contains_key("a")?);
+
+    Ok(())
+}
+
+#[test]
+fn partition_rename_conflict() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+
+    let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+    let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+
+    assert!(matches!(
+        keyspace.rename_partition(&a, "b"),
+        Err(fjall::Error::PartitionExists)
+    ));
+    assert_eq!("a", &*a.name());
+
+    // NOTE: Renaming to the current name is a no-op
+    keyspace.rename_partition(&a, "a")?;
+
+    keyspace.delete_partition(b.clone())?;
+
+    assert!(matches!(
+        keyspace.rename_partition(&b, "c"),
+        Err(fjall::Error::PartitionDeleted)
+    ));
+
+    // NOTE: The name of a deleted partition can be reused
+    keyspace.rename_partition(&a, "b")?;
+    assert!(keyspace.partition_exists("b"));
+
+    Ok(())
+}
+
+#[test]
+fn partition_rename_pending_batch() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let partition = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+        partition.insert("a", "old")?;
+
+        let mut batch = keyspace.batch();
+        batch.require_value(&partition, "a", Some("old"));
+        batch.insert(&partition, "a", "new");
+
+        // NOTE: Reuse the old name for another partition before committing
+        keyspace.rename_partition(&partition, "b")?;
+        let reused = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+
+        batch.commit()?;
+
+        assert_eq!(Some("new".as_bytes().into()), partition.get("a")?);
+        assert!(reused.is_empty()?);
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+        let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+
+        assert!(a.is_empty()?);
+        assert_eq!(Some("new".as_bytes().into()), b.get("a")?);
+    }
+
+    Ok(())
+}
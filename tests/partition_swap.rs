@@ -0,0 +1,198 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+const ITEM_COUNT: usize = 100;
+
+#[test]
+fn partition_swap() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+        let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+        let other_handle = a.clone();
+
+        for x in 0..ITEM_COUNT as u64 {
+            a.insert(x.to_be_bytes(), "a")?;
+        }
+        b.insert(0u64.to_be_bytes(), "b")?;
+
+        keyspace.swap_partitions(&a, &b)?;
+
+        // NOTE: Existing handles observe the new contents
+        assert_eq!("a", &*other_handle.name());
+        assert_eq!(1, other_handle.len()?);
+        assert_eq!(ITEM_COUNT, b.len()?);
+        assert_eq!(
+            Some("b".as_bytes().into()),
+            other_handle.get(0u64.to_be_bytes())?
+        );
+
+        // NOTE: Writes go to the swapped partitions
+        other_handle.insert(1u64.to_be_bytes(), "b")?;
+
+        let mut batch = keyspace.batch();
+        batch.insert(&b, u64::MAX.to_be_bytes(), "a");
+        batch.commit()?;
+
+        assert_eq!(2, a.len()?);
+        assert_eq!(ITEM_COUNT + 1, b.len()?);
+    }
+
+    for _ in 0..3 {
+        let keyspace = Config::new(&folder).open()?;
+        assert_eq!(2, keyspace.partition_count());
+
+        let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+        let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+
+        assert_eq!(2, a.len()?);
+        assert_eq!(ITEM_COUNT + 1, b.len()?);
+        assert_eq!(Some("b".as_bytes().into()), a.get(0u64.to_be_bytes())?);
+        assert_eq!(Some("a".as_bytes().into()), b.get(0u64.to_be_bytes())?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn partition_swap_sealed_journal() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+        let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+
+        for x in 0..ITEM_COUNT as u64 {
+            a.insert(x.to_be_bytes(), "a")?;
+            b.insert(x.to_be_bytes(), "b")?;
+        }
+
+        // NOTE: Seal the journal, so recovery needs to map its data to the swapped partitions
+        a.rotate_memtable()?;
+        assert_eq!(2, keyspace.journal_count());
+
+        keyspace.swap_partitions(&a, &b)?;
+
+        a.insert(u64::MAX.to_be_bytes(), "b")?;
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+        let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+
+        assert_eq!(ITEM_COUNT + 1, a.len()?);
+        assert_eq!(ITEM_COUNT, b.len()?);
+
+        assert_eq!(Some("b".as_bytes().into()), a.get(0u64.to_be_bytes())?);
+        assert_eq!(Some("a".as_bytes().into()), b.get(0u64.to_be_bytes())?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn partition_replace() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+
+        let live = keyspace.open_partition("live", PartitionCreateOptions::default())?;
+        let other_handle = live.clone();
+
+        for x in 0..ITEM_COUNT as u64 {
+            live.insert(x.to_be_bytes(), "old")?;
+        }
+
+        let rebuilt = keyspace.open_partition("rebuilt", PartitionCreateOptions::default())?;
+        let rebuilt_handle = rebuilt.clone();
+        rebuilt.insert(0u64.to_be_bytes(), "new")?;
+
+        keyspace.replace_partition(&live, rebuilt)?;
+
+        assert_eq!(1, keyspace.partition_count());
+        assert!(!keyspace.partition_exists("rebuilt"));
+
+        assert_eq!(1, other_handle.len()?);
+        assert_eq!(
+            Some("new".as_bytes().into()),
+            other_handle.get(0u64.to_be_bytes())?
+        );
+
+        assert!(matches!(
+            rebuilt_handle.insert("a", "a"),
+            Err(fjall::Error::PartitionDeleted)
+        ));
+    }
+
+    for _ in 0..3 {
+        let keyspace = Config::new(&folder).open()?;
+
+        assert_eq!(1, keyspace.partition_count());
+        assert!(!keyspace.partition_exists("rebuilt"));
+
+        let live = keyspace.open_partition("live", PartitionCreateOptions::default())?;
+        assert_eq!(1, live.len()?);
+        assert_eq!(Some("new".as_bytes().into()), live.get(0u64.to_be_bytes())?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn partition_replace_removes_old_data() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+
+    let live = keyspace.open_partition("live", PartitionCreateOptions::default())?;
+    live.insert("a", "old")?;
+
+    let old_path = live.path();
+
+    let rebuilt = keyspace.open_partition("rebuilt", PartitionCreateOptions::default())?;
+    rebuilt.insert("a", "new")?;
+
+    let new_path = rebuilt.path();
+
+    keyspace.replace_partition(&live, rebuilt)?;
+
+    assert!(!old_path.try_exists()?);
+    assert!(new_path.try_exists()?);
+    assert_eq!(new_path, live.path());
+
+    Ok(())
+}
+
+#[test]
+fn partition_swap_deleted() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+
+    let a = keyspace.open_partition("a", PartitionCreateOptions::default())?;
+    let b = keyspace.open_partition("b", PartitionCreateOptions::default())?;
+    a.insert("a", "a")?;
+
+    keyspace.delete_partition(b.clone())?;
+
+    assert!(matches!(
+        keyspace.swap_partitions(&a, &b),
+        Err(fjall::Error::PartitionDeleted)
+    ));
+    assert!(matches!(
+        keyspace.replace_partition(&a, b),
+        Err(fjall::Error::PartitionDeleted)
+    ));
+
+    assert!(a.contains_key("a")?);
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use fjall::{Config, PartitionCreateOptions, PersistMode};
+use test_log::test;
+
+const ITEM_COUNT: usize = 100;
+
+#[test]
+fn partition_unjournaled_crash() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let data = keyspace.open_partition("data", PartitionCreateOptions::default())?;
+        let cache = keyspace.open_partition(
+            "cache",
+            PartitionCreateOptions::default().journaled(false),
+        )?;
+
+        for x in 0..ITEM_COUNT as u64 {
+            let key = x.to_be_bytes();
+            data.insert(key, "abc")?;
+            cache.insert(key, "abc")?;
+        }
+
+        let mut batch = keyspace.batch();
+        batch.insert(&data, "batch", "abc");
+        batch.insert(&cache, "batch", "abc");
+        batch.commit()?;
+
+        assert_eq!(data.len()?, ITEM_COUNT + 1);
+        assert_eq!(cache.len()?, ITEM_COUNT + 1);
+
+        keyspace.persist(PersistMode::SyncAll)?;
+
+        // NOTE: Simulate a crash by never running the destructors
+        std::mem::forget(data);
+        std::mem::forget(cache);
+        std::mem::forget(keyspace);
+    }
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let data = keyspace.open_partition("data", PartitionCreateOptions::default())?;
+        let cache = keyspace.open_partition("cache", PartitionCreateOptions::default())?;
+
+        assert_eq!(data.len()?, ITEM_COUNT + 1);
+        assert_eq!(cache.len()?, 0);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn partition_unjournaled_shutdown() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    {
+        let keyspace = Config::new(&folder).open()?;
+        let cache = keyspace.open_partition(
+            "cache",
+            PartitionCreateOptions::default().journaled(false),
+        )?;
+
+        for x in 0..ITEM_COUNT as u64 {
+            cache.insert(x.to_be_bytes(), "abc")?;
+        }
+
+        cache.rotate_memtable()?;
+
+        for x in ITEM_COUNT as u64..(ITEM_COUNT * 2) as u64 {
+            cache.insert(x.to_be_bytes(), "abc")?;
+        }
+
+        // NOTE: Non-journaled memtables do not rotate the journal
+        assert_eq!(1, keyspace.journal_count());
+    }
+
+    for _ in 0..3 {
+        let keyspace = Config::new(&folder).open()?;
+        let cache = keyspace.open_partition("cache", PartitionCreateOptions::default())?;
+
+        assert_eq!(cache.len()?, ITEM_COUNT * 2);
+    }
+
+    Ok(())
+}
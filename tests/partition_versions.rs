@@ -0,0 +1,93 @@
+use fjall::{Config, PartitionCreateOptions};
+use test_log::test;
+
+#[test]
+fn partition_get_versions() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    partition.insert("a", "v1")?;
+    partition.insert("b", "v1")?;
+
+    let instant = keyspace.instant();
+    partition.remove("a")?;
+    partition.insert("a", "v2")?;
+
+    let versions = partition.get_versions("a", 0)?;
+    assert_eq!(3, versions.len());
+
+    assert_eq!(b"v2", &**versions[0].value());
+    assert!(versions[1].is_tombstone());
+    assert!(versions[1].value().is_empty());
+    assert_eq!(b"v1", &**versions[2].value());
+    assert!(versions.windows(2).all(|w| w[0].seqno() > w[1].seqno()));
+
+    let versions = partition.get_versions("a", instant)?;
+    assert_eq!(2, versions.len());
+    assert!(versions.iter().all(|v| v.seqno() >= instant));
+
+    assert_eq!(1, partition.get_versions("b", 0)?.len());
+    assert!(partition.get_versions("c", 0)?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn partition_get_versions_flushed() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    // NOTE: Have one version in a segment, a sealed memtable and the active memtable each
+    partition.insert("a", "v1")?;
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+
+    partition.insert("a", "v2")?;
+    partition.rotate_memtable()?;
+
+    partition.insert("a", "v3")?;
+
+    let versions = partition.get_versions("a", 0)?;
+    assert_eq!(3, versions.len());
+
+    assert_eq!(b"v3", &**versions[0].value());
+    assert_eq!(b"v2", &**versions[1].value());
+    assert_eq!(b"v1", &**versions[2].value());
+
+    Ok(())
+}
+
+#[test]
+fn partition_get_with_seqno() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    assert_eq!(None, partition.get_with_seqno("a")?);
+
+    let instant = keyspace.instant();
+    partition.insert("a", "abc")?;
+
+    let (value, seqno) = partition.get_with_seqno("a")?.expect("should exist");
+    assert_eq!(b"abc", &*value);
+    assert_eq!(instant, seqno);
+
+    let versions = partition.get_versions("a", 0)?;
+    assert_eq!(seqno, versions[0].seqno());
+
+    partition.insert("b", "abc")?;
+    assert_eq!(
+        seqno,
+        partition.get_with_seqno("a")?.expect("should exist").1
+    );
+
+    partition.remove("a")?;
+    assert_eq!(None, partition.get_with_seqno("a")?);
+
+    Ok(())
+}
@@ -0,0 +1,384 @@
+use fjall::{BlockCache, Config, PartitionCreateOptions, ReadOptions};
+use std::sync::Arc;
+use test_log::test;
+
+const ITEM_COUNT: usize = 1_000;
+
+#[test]
+fn read_options_scan() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    for x in 0..ITEM_COUNT as u64 {
+        partition.insert(x.to_be_bytes(), x.to_string())?;
+    }
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+
+    let options = ReadOptions::default()
+        .fill_cache(false)
+        .verify_checksums(true)
+        .readahead(4_096);
+
+    let items = partition
+        .range_with_options::<&[u8], _>(.., options)
+        .collect::<fjall::Result<Vec<_>>>()?;
+    let expected = partition.iter().collect::<fjall::Result<Vec<_>>>()?;
+    assert_eq!(ITEM_COUNT, items.len());
+    assert_eq!(expected, items);
+
+    let items = partition
+        .range_with_options::<&[u8], _>(.., options)
+        .rev()
+        .collect::<fjall::Result<Vec<_>>>()?;
+    let expected = partition.iter().rev().collect::<fjall::Result<Vec<_>>>()?;
+    assert_eq!(expected, items);
+
+    let items = partition
+        .range_with_options(10u64.to_be_bytes()..=20u64.to_be_bytes(), options)
+        .collect::<fjall::Result<Vec<_>>>()?;
+    assert_eq!(11, items.len());
+    assert_eq!(&*items[0].1, b"10");
+
+    assert_eq!(
+        256,
+        partition.prefix_with_options([0u8; 7], options).count()
+    );
+
+    assert_eq!(
+        Some((*b"3").into()),
+        partition.get_with_options(3u64.to_be_bytes(), options)?
+    );
+    assert_eq!(
+        None,
+        partition.get_with_options(u64::MAX.to_be_bytes(), options)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn read_options_scan_memtables() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    // NOTE: Shadow flushed items by a sealed and the active memtable
+    partition.insert("a", "segment")?;
+    partition.insert("b", "segment")?;
+    partition.insert("c", "segment")?;
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+
+    partition.insert("b", "sealed")?;
+    partition.rotate_memtable()?;
+
+    partition.remove("c")?;
+    partition.insert("d", "active")?;
+
+    let options = ReadOptions::default().fill_cache(false);
+
+    let items = partition
+        .range_with_options::<&str, _>(.., options)
+        .collect::<fjall::Result<Vec<_>>>()?;
+    assert_eq!(
+        vec![
+            ((*b"a").into(), (*b"segment").into()),
+            ((*b"b").into(), (*b"sealed").into()),
+            ((*b"d").into(), (*b"active").into()),
+        ],
+        items
+    );
+    assert_eq!(
+        3,
+        partition
+            .range_with_options::<&str, _>(.., options)
+            .rev()
+            .count()
+    );
+
+    assert_eq!(None, partition.get_with_options("c", options)?);
+    assert_eq!(
+        Some((*b"sealed").into()),
+        partition.get_with_options("b", options)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn read_options_scan_active_memtable_sealed() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    for x in 0..ITEM_COUNT as u64 {
+        partition.insert(x.to_be_bytes(), x.to_string())?;
+    }
+
+    let options = ReadOptions::default().fill_cache(false);
+
+    let mut iter = partition.range_with_options(..(ITEM_COUNT as u64).to_be_bytes(), options);
+
+    let mut front = iter.by_ref().take(300).collect::<fjall::Result<Vec<_>>>()?;
+    let back = iter
+        .by_ref()
+        .rev()
+        .take(300)
+        .collect::<fjall::Result<Vec<_>>>()?;
+
+    // NOTE: The iterator does not lock the memtable while it is not advanced
+    partition.insert(u64::MAX.to_be_bytes(), "other")?;
+
+    // NOTE: Seal and flush the memtable while it is being read
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+
+    front.extend(iter.collect::<fjall::Result<Vec<_>>>()?);
+    front.extend(back.into_iter().rev());
+
+    let expected = (0..ITEM_COUNT as u64)
+        .map(|x| (x.to_be_bytes().into(), x.to_string().as_bytes().into()))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, front);
+
+    Ok(())
+}
+
+#[test]
+fn read_options_fill_cache() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let block_cache = Arc::new(BlockCache::with_capacity_bytes(16 * 1_024 * 1_024));
+
+    let keyspace = Config::new(&folder)
+        .block_cache(block_cache.clone())
+        .flush_workers(0)
+        .open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    for x in 0..ITEM_COUNT as u64 {
+        partition.insert(x.to_be_bytes(), "a".repeat(100))?;
+    }
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+
+    let cached_blocks = block_cache.len();
+
+    let options = ReadOptions::default().fill_cache(false);
+    assert_eq!(
+        ITEM_COUNT,
+        partition
+            .range_with_options::<&[u8], _>(.., options)
+            .count()
+    );
+    assert_eq!(cached_blocks, block_cache.len());
+
+    assert_eq!(ITEM_COUNT, partition.iter().count());
+    assert!(block_cache.len() > cached_blocks);
+
+    Ok(())
+}
+
+#[test]
+fn read_options_readahead() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let block_cache = Arc::new(BlockCache::with_capacity_bytes(16 * 1_024 * 1_024));
+
+    let keyspace = Config::new(&folder)
+        .block_cache(block_cache.clone())
+        .flush_workers(0)
+        .open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    for x in 0..ITEM_COUNT as u64 {
+        partition.insert(x.to_be_bytes(), "a".repeat(100))?;
+    }
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+
+    let cached_blocks = block_cache.len();
+
+    // NOTE: Reading the first item reads the following data blocks as well
+    let options = ReadOptions::default().readahead(32 * 1_024);
+    let mut iter = partition.range_with_options::<&[u8], _>(.., options);
+    assert!(iter.next().is_some());
+    assert!(block_cache.len() >= cached_blocks + 4);
+
+    let items = iter.collect::<fjall::Result<Vec<_>>>()?;
+    let expected = partition
+        .iter()
+        .skip(1)
+        .collect::<fjall::Result<Vec<_>>>()?;
+    assert_eq!(expected, items);
+
+    // NOTE: Cached blocks are not read again
+    let items = partition
+        .range_with_options::<&[u8], _>(.., options)
+        .collect::<fjall::Result<Vec<_>>>()?;
+    assert_eq!(ITEM_COUNT, items.len());
+
+    Ok(())
+}
+
+#[test]
+fn read_options_snapshot() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    partition.insert("a", "abc")?;
+    let instant = keyspace.instant();
+    partition.insert("a", "def")?;
+    partition.insert("b", "def")?;
+
+    let options = ReadOptions::default()
+        .fill_cache(false)
+        .snapshot(Some(instant));
+
+    assert_eq!(
+        Some((*b"abc").into()),
+        partition.get_with_options("a", options)?
+    );
+    assert_eq!(None, partition.get_with_options("b", options)?);
+    assert_eq!(
+        1,
+        partition.range_with_options::<&str, _>(.., options).count()
+    );
+
+    let options = options.snapshot(None);
+    assert_eq!(
+        Some((*b"def").into()),
+        partition.get_with_options("a", options)?
+    );
+    assert_eq!(
+        2,
+        partition.range_with_options::<&str, _>(.., options).count()
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "single_writer_tx")]
+fn tx_read_options() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open_transactional()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    partition.insert("a", "abc")?;
+    partition.insert("b", "abc")?;
+
+    let read_tx = keyspace.read_tx();
+    let options = ReadOptions::default().fill_cache(false);
+
+    let mut tx = keyspace.write_tx();
+    tx.insert(&partition, "c", "abc");
+    tx.remove(&partition, "a");
+
+    assert_eq!(None, tx.get_with_options(&partition, "a", options)?);
+    assert_eq!(
+        Some((*b"abc").into()),
+        tx.get_with_options(&partition, "c", options)?
+    );
+    assert_eq!(
+        2,
+        tx.range_with_options::<&str, _>(&partition, .., options)
+            .count()
+    );
+    assert_eq!(1, tx.prefix_with_options(&partition, "c", options).count());
+
+    tx.commit()?;
+
+    // NOTE: Read transaction still sees its snapshot
+    assert_eq!(
+        Some((*b"abc").into()),
+        read_tx.get_with_options(&partition, "a", options)?
+    );
+    assert_eq!(
+        2,
+        read_tx
+            .range_with_options::<&str, _>(&partition, .., options)
+            .rev()
+            .count()
+    );
+    assert_eq!(
+        0,
+        read_tx
+            .prefix_with_options(&partition, "c", options)
+            .count()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn read_options_verify_checksums() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).flush_workers(0).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    // NOTE: Use incompressible values, so the corrupted byte
+    // is part of the block's items, not its compressed framing
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+
+    for x in 0..ITEM_COUNT as u64 {
+        let value = (0..13)
+            .flat_map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state.to_le_bytes()
+            })
+            .collect::<Vec<_>>();
+
+        partition.insert(x.to_be_bytes(), value)?;
+    }
+    partition.rotate_memtable()?;
+    keyspace.force_flush();
+
+    let options = ReadOptions::default()
+        .fill_cache(false)
+        .verify_checksums(true);
+    assert_eq!(
+        ITEM_COUNT,
+        partition
+            .range_with_options::<&[u8], _>(.., options)
+            .count()
+    );
+
+    drop(partition);
+    drop(keyspace);
+
+    // NOTE: Partition folders are named by ID
+    let partitions_folder = folder.path().join("partitions");
+    let partition_folder = std::fs::read_dir(partitions_folder)?
+        .next()
+        .expect("partition folder should exist")?
+        .path();
+
+    for dirent in std::fs::read_dir(partition_folder.join("segments"))? {
+        let path = dirent?.path();
+        let mut bytes = std::fs::read(&path)?;
+        bytes[1_000] ^= 0xFF;
+        std::fs::write(&path, bytes)?;
+    }
+
+    let keyspace = Config::new(&folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let result = partition
+        .range_with_options::<&[u8], _>(.., options)
+        .collect::<fjall::Result<Vec<_>>>();
+    assert!(matches!(result, Err(fjall::Error::ChecksumMismatch)));
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use fjall::{Config, Keyspace, PartitionCreateOptions};
+use std::path::Path;
+use test_log::test;
+
+const ITEM_COUNT: usize = 10;
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+
+    for dirent in std::fs::read_dir(from)? {
+        let dirent = dirent?;
+        let target = to.join(dirent.file_name());
+
+        if dirent.file_type()?.is_dir() {
+            copy_dir(&dirent.path(), &target)?;
+        } else {
+            std::fs::copy(dirent.path(), target)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn recover_until_archived_journal() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+    let checkpoint_folder = tempfile::tempdir()?;
+    let archive_folder = tempfile::tempdir()?;
+
+    let config = || Config::new(&folder).journal_archive(&archive_folder);
+
+    {
+        let keyspace = Keyspace::create_or_recover(config())?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        for x in 0..ITEM_COUNT as u64 {
+            partition.insert(x.to_be_bytes(), "good")?;
+        }
+    }
+
+    copy_dir(folder.path(), checkpoint_folder.path())?;
+
+    let good_instant = {
+        let keyspace = Keyspace::create_or_recover(config())?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        for x in 0..ITEM_COUNT as u64 {
+            partition.insert((x + ITEM_COUNT as u64).to_be_bytes(), "good")?;
+        }
+
+        let good_instant = keyspace.instant();
+
+        // Bad deploy
+        for x in 0..(ITEM_COUNT * 2) as u64 {
+            partition.insert(x.to_be_bytes(), "bad")?;
+        }
+
+        partition.rotate_memtable()?;
+        keyspace.force_flush();
+
+        // NOTE: The sealed journal was moved into the archive
+        assert_eq!(1, keyspace.journal_count());
+        assert_eq!(1, std::fs::read_dir(&archive_folder)?.count());
+
+        good_instant
+    };
+
+    // NOTE: The keyspace itself has the bad data in its segments already
+    assert!(matches!(
+        Keyspace::create_or_recover(config().recover_until(good_instant)),
+        Err(fjall::Error::RecoveryTargetUnreachable(_))
+    ));
+
+    // NOTE: Unrelated files in the archive are skipped
+    std::fs::write(archive_folder.path().join(".DS_Store"), "")?;
+
+    {
+        let keyspace = Keyspace::create_or_recover(
+            Config::new(&checkpoint_folder)
+                .journal_archive(&archive_folder)
+                .recover_until(good_instant),
+        )?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        assert_eq!(ITEM_COUNT * 2, partition.len()?);
+        assert!(partition
+            .iter()
+            .all(|kv| &*kv.expect("should read").1 == b"good"));
+        assert_eq!(good_instant, keyspace.instant());
+
+        partition.insert("new", "abc")?;
+    }
+
+    for _ in 0..3 {
+        let keyspace = Keyspace::create_or_recover(Config::new(&checkpoint_folder))?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        assert_eq!(ITEM_COUNT * 2 + 1, partition.len()?);
+        assert_eq!(1, keyspace.journal_count());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn recover_until_local_journal() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let instant = {
+        let keyspace = Keyspace::create_or_recover(Config::new(&folder))?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        partition.insert("a", "good")?;
+        let instant = keyspace.instant();
+
+        partition.insert("a", "bad")?;
+        partition.insert("b", "bad")?;
+
+        instant
+    };
+
+    {
+        let keyspace = Keyspace::create_or_recover(Config::new(&folder).recover_until(instant))?;
+        let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+        assert_eq!(1, partition.len()?);
+        assert_eq!(Some("good".as_bytes().into()), partition.get("a")?);
+    }
+
+    Ok(())
+}
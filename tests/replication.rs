@@ -0,0 +1,158 @@
+use fjall::{Config, PartitionCreateOptions, ReplicationError};
+use std::io::Read;
+use test_log::test;
+
+const ITEM_COUNT: usize = 100;
+
+#[test]
+fn replication_pipe_follower() -> fjall::Result<()> {
+    let primary_folder = tempfile::tempdir()?;
+    let primary = Config::new(&primary_folder).open()?;
+
+    let follower_folder = tempfile::tempdir()?;
+    let follower = Config::new(&follower_folder).open()?;
+
+    let (writer, reader) = fjall::replication::pipe();
+    assert_eq!(0, primary.replicate_to(writer));
+
+    let replicator = {
+        let follower = follower.clone();
+        std::thread::spawn(move || follower.replicate_from(reader))
+    };
+
+    let partition1 = primary.open_partition("default1", PartitionCreateOptions::default())?;
+    let partition2 = primary.open_partition("default2", PartitionCreateOptions::default())?;
+
+    for x in 0..ITEM_COUNT as u64 {
+        partition1.insert(x.to_be_bytes(), "abc")?;
+    }
+
+    let instant = primary.instant();
+
+    for x in 0..(ITEM_COUNT / 2) as u64 {
+        partition1.remove(x.to_be_bytes())?;
+    }
+
+    let mut batch = primary.batch();
+    for x in 0..ITEM_COUNT as u64 {
+        batch.insert(&partition1, x.to_be_bytes(), "def");
+        batch.insert(&partition2, x.to_be_bytes(), "def");
+    }
+    batch.commit()?;
+
+    primary.stop_replication();
+
+    let batch_count = replicator.join().expect("should join")?;
+    assert_eq!(ITEM_COUNT as u64 + ITEM_COUNT as u64 / 2 + 1, batch_count);
+
+    assert_eq!(primary.instant(), follower.applied_instant());
+    assert_eq!(primary.instant(), follower.instant());
+    assert_eq!(2, follower.partition_count());
+
+    let replica1 = follower.open_partition("default1", PartitionCreateOptions::default())?;
+    let replica2 = follower.open_partition("default2", PartitionCreateOptions::default())?;
+
+    assert_eq!(ITEM_COUNT, replica1.len()?);
+    assert_eq!(ITEM_COUNT, replica2.len()?);
+    assert!(replica1
+        .iter()
+        .all(|kv| &*kv.expect("should read").1 == b"def"));
+
+    // NOTE: Sequence numbers are preserved, so snapshots see the same state
+    assert_eq!(
+        partition1.snapshot_at(instant).len()?,
+        replica1.snapshot_at(instant).len()?,
+    );
+    assert_eq!(ITEM_COUNT, replica1.snapshot_at(instant).len()?);
+    assert!(replica2.snapshot_at(instant).is_empty()?);
+
+    Ok(())
+}
+
+#[test]
+fn replication_resume() -> fjall::Result<()> {
+    let primary_folder = tempfile::tempdir()?;
+    let primary = Config::new(&primary_folder).open()?;
+    let partition = primary.open_partition("default", PartitionCreateOptions::default())?;
+
+    let (writer, mut reader) = fjall::replication::pipe();
+    primary.replicate_to(writer);
+
+    for x in 0..ITEM_COUNT as u64 {
+        partition.insert(x.to_be_bytes(), "abc")?;
+    }
+
+    primary.stop_replication();
+
+    // NOTE: Not shipped anymore
+    partition.insert("a", "abc")?;
+
+    let mut stream = vec![];
+    reader.read_to_end(&mut stream)?;
+
+    let follower_folder = tempfile::tempdir()?;
+
+    {
+        let follower = Config::new(&follower_folder).open()?;
+        assert_eq!(0, follower.applied_instant());
+
+        // Follower lags behind
+        assert_eq!(
+            ITEM_COUNT as u64 / 2,
+            follower.replicate_from(&stream[..stream.len() / 2])?
+        );
+        assert!(follower.applied_instant() < primary.instant());
+    }
+
+    {
+        let follower = Config::new(&follower_folder).open()?;
+        assert_eq!(ITEM_COUNT as u64 / 2, follower.applied_instant());
+
+        // NOTE: Already applied batches are skipped
+        assert_eq!(ITEM_COUNT as u64 / 2, follower.replicate_from(&*stream)?);
+        assert_eq!(ITEM_COUNT as u64, follower.applied_instant());
+    }
+
+    for _ in 0..3 {
+        let follower = Config::new(&follower_folder).open()?;
+        assert_eq!(ITEM_COUNT as u64, follower.applied_instant());
+        assert_eq!(0, follower.replicate_from(&*stream)?);
+
+        let replica = follower.open_partition("default", PartitionCreateOptions::default())?;
+        assert_eq!(ITEM_COUNT, replica.len()?);
+        assert!(!replica.contains_key("a")?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn replication_corrupted_stream() -> fjall::Result<()> {
+    let primary_folder = tempfile::tempdir()?;
+    let primary = Config::new(&primary_folder).open()?;
+    let partition = primary.open_partition("default", PartitionCreateOptions::default())?;
+
+    let (writer, mut reader) = fjall::replication::pipe();
+    primary.replicate_to(writer);
+
+    partition.insert("a", "abc")?;
+    partition.insert("b", "abc")?;
+
+    primary.stop_replication();
+
+    let mut stream = vec![];
+    reader.read_to_end(&mut stream)?;
+
+    let follower_folder = tempfile::tempdir()?;
+    let follower = Config::new(&follower_folder).open()?;
+
+    assert!(matches!(
+        follower.replicate_from(&stream[..stream.len() - 1]),
+        Err(fjall::Error::Replication(ReplicationError::Truncated))
+    ));
+
+    // NOTE: The first batch was complete, so it was applied
+    assert_eq!(1, follower.applied_instant());
+
+    Ok(())
+}
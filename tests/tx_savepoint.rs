@@ -0,0 +1,232 @@
+#[test_log::test]
+#[cfg(feature = "single_writer_tx")]
+fn tx_savepoint() -> fjall::Result<()> {
+    use fjall::{Config, PartitionCreateOptions};
+
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open_transactional()?;
+
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    let other = keyspace.open_partition("other", PartitionCreateOptions::default())?;
+
+    partition.insert("a", "0")?;
+
+    let mut tx = keyspace.write_tx();
+    tx.insert(&partition, "a", "1");
+
+    let first = tx.savepoint();
+    tx.insert(&partition, "a", "2");
+    tx.insert(&partition, "b", "2");
+    tx.insert(&other, "c", "2");
+
+    let second = tx.savepoint();
+    tx.remove(&partition, "a");
+    assert_eq!(None, tx.get(&partition, "a")?);
+
+    tx.rollback_to(&second);
+    assert_eq!(Some((*b"2").into()), tx.get(&partition, "a")?);
+
+    tx.rollback_to(&first);
+    assert_eq!(Some((*b"1").into()), tx.get(&partition, "a")?);
+    assert_eq!(None, tx.get(&partition, "b")?);
+    assert_eq!(None, tx.get(&other, "c")?);
+    assert_eq!(1, tx.len(&partition)?);
+    assert_eq!(0, tx.len(&other)?);
+
+    // NOTE: The savepoint stays valid after being rolled back to
+    tx.insert(&partition, "d", "3");
+    tx.rollback_to(&first);
+    assert_eq!(None, tx.get(&partition, "d")?);
+
+    tx.insert(&partition, "e", "4");
+    tx.commit()?;
+
+    assert_eq!(Some((*b"1").into()), partition.get("a")?);
+    assert_eq!(None, partition.get("b")?);
+    assert_eq!(Some((*b"4").into()), partition.get("e")?);
+    assert_eq!(None, other.get("c")?);
+
+    Ok(())
+}
+
+#[test_log::test]
+#[cfg(feature = "single_writer_tx")]
+fn tx_savepoint_many() -> fjall::Result<()> {
+    use fjall::{Config, PartitionCreateOptions, ReadOptions};
+
+    const ITEM_COUNT: u64 = 10_000;
+
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open_transactional()?;
+
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let mut tx = keyspace.write_tx();
+
+    for x in 0..ITEM_COUNT {
+        let savepoint = tx.savepoint();
+        tx.insert(&partition, x.to_be_bytes(), "abc");
+        tx.insert(&partition, "counter", x.to_be_bytes());
+
+        // NOTE: Every other iteration is undone
+        if x % 2 == 1 {
+            tx.rollback_to(&savepoint);
+        }
+    }
+
+    assert_eq!(ITEM_COUNT as usize / 2 + 1, tx.len(&partition)?);
+    assert_eq!(
+        Some((ITEM_COUNT - 2).to_be_bytes().into()),
+        tx.get(&partition, "counter")?
+    );
+    assert_eq!(None, tx.get(&partition, 1u64.to_be_bytes())?);
+
+    let options = ReadOptions::default().fill_cache(false);
+    assert_eq!(
+        Some((*b"abc").into()),
+        tx.get_with_options(&partition, 0u64.to_be_bytes(), options)?
+    );
+    assert_eq!(
+        None,
+        tx.get_with_options(&partition, 1u64.to_be_bytes(), options)?
+    );
+    assert_eq!(
+        3,
+        tx.range(&partition, 0u64.to_be_bytes()..6u64.to_be_bytes())
+            .rev()
+            .count()
+    );
+
+    tx.commit()?;
+
+    let read_tx = keyspace.read_tx();
+    assert_eq!(ITEM_COUNT as usize / 2 + 1, read_tx.len(&partition)?);
+    assert_eq!(
+        Some((ITEM_COUNT - 2).to_be_bytes().into()),
+        read_tx.get(&partition, "counter")?
+    );
+
+    Ok(())
+}
+
+#[test_log::test]
+#[cfg(feature = "single_writer_tx")]
+fn tx_savepoint_cursor() -> fjall::Result<()> {
+    use fjall::{Config, PartitionCreateOptions};
+
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open_transactional()?;
+
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    partition.insert("a", "0")?;
+
+    let mut tx = keyspace.write_tx();
+
+    // NOTE: The cursor observes writes made after it was opened
+    let mut cursor = tx.cursor(&partition);
+    tx.insert(&partition, "b", "1");
+    cursor.seek("b")?;
+    assert_eq!(Some(&"b".as_bytes().into()), cursor.key());
+
+    // NOTE: Savepoints do not detach the cursor from the pending writes
+    let savepoint = tx.savepoint();
+    tx.insert(&partition, "c", "2");
+    cursor.seek("c")?;
+    assert_eq!(Some(&"c".as_bytes().into()), cursor.key());
+
+    tx.rollback_to(&savepoint);
+    assert_eq!(None, tx.get(&partition, "c")?);
+
+    let mut cursor = tx.cursor(&partition);
+    cursor.seek("b")?;
+    assert_eq!(Some(&"b".as_bytes().into()), cursor.key());
+    cursor.next()?;
+    assert!(!cursor.is_valid());
+
+    Ok(())
+}
+
+#[test_log::test]
+#[cfg(feature = "single_writer_tx")]
+fn tx_fetch_update() -> fjall::Result<()> {
+    use fjall::{Config, PartitionCreateOptions};
+
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open_transactional()?;
+
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+    partition.insert("a", "0")?;
+
+    let mut tx = keyspace.write_tx();
+    tx.insert(&partition, "a", "1");
+
+    let prev = tx.fetch_update(&partition, "a", |value| {
+        assert_eq!(Some(&b"1"[..]), value.map(|v| &**v));
+        Some((*b"2").into())
+    })?;
+    assert_eq!(Some((*b"1").into()), prev);
+
+    let updated = tx.update_fetch(&partition, "a", |_| Some((*b"3").into()))?;
+    assert_eq!(Some((*b"3").into()), updated);
+
+    assert_eq!(Some((*b"3").into()), tx.take(&partition, "a")?);
+    assert_eq!(None, tx.take(&partition, "a")?);
+
+    // NOTE: Not committed yet
+    assert_eq!(Some((*b"0").into()), partition.get("a")?);
+
+    tx.commit()?;
+    assert_eq!(None, partition.get("a")?);
+
+    Ok(())
+}
+
+#[test_log::test]
+#[cfg(feature = "single_writer_tx")]
+fn tx_remove_range() -> fjall::Result<()> {
+    use fjall::{Config, PartitionCreateOptions};
+
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(&folder).open_transactional()?;
+
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    for key in ["a", "b", "c", "user#1", "user#2"] {
+        partition.insert(key, "abc")?;
+    }
+
+    let mut tx = keyspace.write_tx();
+    tx.insert(&partition, "bb", "abc");
+    tx.remove(&partition, "c");
+    tx.insert(&partition, "user#3", "abc");
+
+    let savepoint = tx.savepoint();
+
+    assert_eq!(2, tx.remove_range(&partition, "b"..="c")?);
+    assert_eq!(3, tx.remove_prefix(&partition, "user#")?);
+    assert_eq!(0, tx.remove_prefix(&partition, "user#")?);
+    assert_eq!(1, tx.len(&partition)?);
+
+    tx.rollback_to(&savepoint);
+    assert_eq!(6, tx.len(&partition)?);
+
+    assert_eq!(3, tx.remove_prefix(&partition, "user#")?);
+    tx.commit()?;
+
+    let keys = keyspace
+        .read_tx()
+        .iter(&partition)
+        .map(|kv| Ok(kv?.0))
+        .collect::<fjall::Result<Vec<_>>>()?;
+    assert_eq!(
+        vec![fjall::UserKey::from(*b"a"), (*b"b").into(), (*b"bb").into()],
+        keys
+    );
+
+    Ok(())
+}
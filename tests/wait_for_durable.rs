@@ -0,0 +1,127 @@
+use fjall::{Config, PartitionCreateOptions, PersistMode, WriteOptions};
+use std::time::Duration;
+use test_log::test;
+
+#[test]
+fn batch_commit_seqno() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let mut batch = keyspace.batch();
+    batch.insert(&partition, "a", "abc");
+    let seqno1 = batch.commit()?;
+
+    let mut batch = keyspace.batch();
+    batch.insert(&partition, "a", "def");
+    let seqno2 = batch.commit()?;
+
+    assert!(seqno2 > seqno1);
+
+    // NOTE: A snapshot sees writes with a lower seqno than its instant only
+    assert_eq!(None, partition.snapshot_at(seqno1).get("a")?);
+    assert_eq!(
+        Some("abc".as_bytes().into()),
+        partition.snapshot_at(seqno1 + 1).get("a")?
+    );
+    assert_eq!(
+        Some("def".as_bytes().into()),
+        partition.snapshot_at(seqno2 + 1).get("a")?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn wait_for_durable_persist() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).fsync_ms(None).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let mut batch = keyspace.batch();
+    batch.insert(&partition, "a", "abc");
+    let seqno = batch.commit()?;
+
+    let waiter = {
+        let keyspace = keyspace.clone();
+        std::thread::spawn(move || keyspace.wait_for_durable(seqno))
+    };
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!waiter.is_finished());
+
+    // NOTE: Buffering does not make the write durable
+    keyspace.persist(PersistMode::Buffer)?;
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!waiter.is_finished());
+
+    keyspace.persist(PersistMode::SyncAll)?;
+    waiter.join().expect("should join")?;
+
+    // NOTE: Already durable, so returns immediately
+    keyspace.wait_for_durable(seqno)?;
+
+    Ok(())
+}
+
+#[test]
+fn wait_for_durable_fsync_thread() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).fsync_ms(Some(50)).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let mut batch = keyspace.batch();
+    batch.insert(&partition, "a", "abc");
+    let seqno = batch.commit()?;
+
+    keyspace.wait_for_durable(seqno)?;
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "single_writer_tx")]
+fn tx_commit_seqno() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).open_transactional()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let mut tx = keyspace.write_tx();
+    tx.insert(&partition, "a", "abc");
+    let seqno = tx.commit()?;
+
+    keyspace.persist(PersistMode::SyncAll)?;
+    keyspace.wait_for_durable(seqno)?;
+
+    assert!(!partition.inner().snapshot_at(seqno).contains_key("a")?);
+    assert!(partition.inner().snapshot_at(seqno + 1).contains_key("a")?);
+
+    Ok(())
+}
+
+#[test]
+fn wait_for_durable_group_commit() -> fjall::Result<()> {
+    let folder = tempfile::tempdir()?;
+
+    let keyspace = Config::new(folder).fsync_ms(None).open()?;
+    let partition = keyspace.open_partition("default", PartitionCreateOptions::default())?;
+
+    let options = WriteOptions::default().durability(Some(PersistMode::SyncAll));
+
+    let mut batch = keyspace.batch();
+    batch.insert(&partition, "a", "abc");
+    let seqno = batch.commit_with_options(options)?;
+
+    // NOTE: The group commit synced the write, so neither the fsync thread
+    // nor an explicit persist is needed
+    keyspace.wait_for_durable(seqno)?;
+
+    let seqno = partition.insert_with_options("b", "abc", options)?;
+    keyspace.wait_for_durable(seqno)?;
+
+    Ok(())
+}